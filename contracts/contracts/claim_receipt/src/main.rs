@@ -0,0 +1,145 @@
+#![cfg_attr(not(any(feature = "library", test)), no_std)]
+#![cfg_attr(not(test), no_main)]
+
+#[cfg(any(feature = "library", test))]
+extern crate alloc;
+
+mod error;
+use error::Error;
+
+use ckb_std::{
+    ckb_constants::Source,
+    ckb_types::{bytes::Bytes, prelude::*},
+    high_level::{load_cell_data, load_cell_lock, load_cell_lock_hash, load_header, load_script, QueryIter},
+};
+use core::result::Result;
+
+#[cfg(not(any(feature = "library", test)))]
+ckb_std::entry!(program_entry);
+#[cfg(not(any(feature = "library", test)))]
+ckb_std::default_alloc!(4096, 65536, 64);
+
+/// Entry point for the CKB script runtime.
+/// Returns 0 for success, error code for failure.
+pub fn program_entry() -> i8 {
+    match main() {
+        Ok(()) => 0,
+        Err(err) => err as i8,
+    }
+}
+
+// Type script args structure (32 bytes total)
+const VESTING_LOCK_HASH_OFFSET: usize = 0;
+const ARGS_LEN: usize = 32;
+
+// Beneficiary lock hash position within the vesting lock args
+const VESTING_BENEFICIARY_LOCK_HASH_OFFSET: usize = 32;
+
+// Vesting cell data positions of the fields a claim depends on
+const VESTING_TOTAL_AMOUNT_OFFSET: usize = 0;
+const VESTING_BENEFICIARY_CLAIMED_OFFSET: usize = 8;
+const VESTING_CREATOR_CLAIMED_OFFSET: usize = 16;
+
+// Cell data structure: amount (8) + epoch (8)
+const RECEIPT_AMOUNT_OFFSET: usize = 0;
+const RECEIPT_EPOCH_OFFSET: usize = 8;
+const RECEIPT_LEN: usize = 16;
+
+/// Reads a u64 value from bytes at the given offset.
+/// Avoids unwrap so no panic formatting code is linked; missing bytes read as zero.
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    let mut buf = [0u8; 8];
+    if let Some(slice) = bytes.get(offset..offset + 8) {
+        buf.copy_from_slice(slice);
+    }
+    u64::from_le_bytes(buf)
+}
+
+/// Reads a 32-byte hash at the given offset.
+/// Avoids unwrap so no panic formatting code is linked; missing bytes read as zero.
+fn read_hash(bytes: &[u8], offset: usize) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    if let Some(slice) = bytes.get(offset..offset + 32) {
+        hash.copy_from_slice(slice);
+    }
+    hash
+}
+
+/// Calculates the amount the beneficiary claims from the vesting cell in this transaction.
+/// Without a continuation cell the claim takes everything neither party has claimed yet.
+fn claimed_amount(vesting_lock_hash: &[u8; 32], input_index: usize) -> Result<u64, Error> {
+    let input_data = load_cell_data(input_index, Source::Input)?;
+    let input_claimed = read_u64(&input_data, VESTING_BENEFICIARY_CLAIMED_OFFSET);
+
+    let output_index = QueryIter::new(load_cell_lock_hash, Source::Output).position(|lock_hash| &lock_hash == vesting_lock_hash);
+    match output_index {
+        Some(index) => {
+            let output_data = load_cell_data(index, Source::Output)?;
+            read_u64(&output_data, VESTING_BENEFICIARY_CLAIMED_OFFSET)
+                .checked_sub(input_claimed)
+                .ok_or(Error::AmountMismatch)
+        }
+        None => Ok(read_u64(&input_data, VESTING_TOTAL_AMOUNT_OFFSET)
+            .saturating_sub(read_u64(&input_data, VESTING_CREATOR_CLAIMED_OFFSET))
+            .saturating_sub(input_claimed)),
+    }
+}
+
+/// Validates that the receipt epoch is the epoch of one of the transaction's header deps.
+/// The vesting lock reads the same headers, so the receipt cannot name an epoch the claim did not use.
+fn validate_receipt_epoch(epoch: u64) -> Result<(), Error> {
+    for header in QueryIter::new(load_header, Source::HeaderDep) {
+        let header_epoch: u64 = header.raw().epoch().unpack();
+        if header_epoch == epoch {
+            return Ok(());
+        }
+    }
+
+    Err(Error::EpochMismatch)
+}
+
+/// Main entry point for the claim receipt type script.
+/// A receipt may only be created by the beneficiary claim it records; destroying receipts is left to their lock.
+pub fn main() -> Result<(), Error> {
+    let script = load_script()?;
+    let args: Bytes = script.args().unpack();
+    if args.len() != ARGS_LEN {
+        return Err(Error::InvalidArgs);
+    }
+
+    let vesting_lock_hash = read_hash(&args, VESTING_LOCK_HASH_OFFSET);
+
+    // Receipts are immutable records, so only new outputs need checking.
+    let mut receipts = QueryIter::new(load_cell_data, Source::GroupOutput);
+    let receipt = match receipts.next() {
+        Some(receipt) => receipt,
+        None => return Ok(()),
+    };
+
+    // A claim is recorded by exactly one receipt.
+    if receipts.next().is_some() || receipt.len() != RECEIPT_LEN {
+        return Err(Error::InvalidReceipt);
+    }
+
+    // The claim must spend the vesting cell the receipt refers to.
+    let vesting_index = QueryIter::new(load_cell_lock_hash, Source::Input)
+        .position(|lock_hash| lock_hash == vesting_lock_hash)
+        .ok_or(Error::VestingCellNotFound)?;
+
+    // Only the beneficiary claims, authorized through the proxy lock pattern.
+    let vesting_args: Bytes = load_cell_lock(vesting_index, Source::Input)?.args().unpack();
+    let beneficiary_lock_hash = read_hash(&vesting_args, VESTING_BENEFICIARY_LOCK_HASH_OFFSET);
+    let beneficiary_authorized = QueryIter::new(load_cell_lock_hash, Source::Input).any(|lock_hash| lock_hash == beneficiary_lock_hash);
+    if !beneficiary_authorized {
+        return Err(Error::Unauthorized);
+    }
+
+    let amount = read_u64(&receipt, RECEIPT_AMOUNT_OFFSET);
+    if amount == 0 || amount != claimed_amount(&vesting_lock_hash, vesting_index)? {
+        return Err(Error::AmountMismatch);
+    }
+
+    validate_receipt_epoch(read_u64(&receipt, RECEIPT_EPOCH_OFFSET))?;
+
+    Ok(())
+}
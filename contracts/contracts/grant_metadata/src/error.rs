@@ -0,0 +1,31 @@
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Error {
+    // CKB syscall errors
+    IndexOutOfBound = 1,
+    ItemMissing = 2,
+    LengthNotEnough = 3,
+    InvalidData = 4,
+
+    // Script-specific errors
+    InvalidArgs = 10,
+    InvalidMetadata = 11,
+
+    // Authorization errors
+    Unauthorized = 20,
+    VestingCellNotFound = 21,
+    CreatorMismatch = 22,
+}
+
+impl From<ckb_std::error::SysError> for Error {
+    fn from(err: ckb_std::error::SysError) -> Self {
+        use ckb_std::error::SysError;
+        match err {
+            SysError::IndexOutOfBound => Error::IndexOutOfBound,
+            SysError::ItemMissing => Error::ItemMissing,
+            SysError::LengthNotEnough(_) => Error::LengthNotEnough,
+            SysError::Encoding => Error::InvalidData,
+            SysError::Unknown(_) => Error::InvalidData,
+            _ => Error::InvalidData,
+        }
+    }
+}
@@ -0,0 +1,130 @@
+#![cfg_attr(not(any(feature = "library", test)), no_std)]
+#![cfg_attr(not(test), no_main)]
+
+#[cfg(any(feature = "library", test))]
+extern crate alloc;
+
+mod error;
+use error::Error;
+
+use ckb_std::{
+    ckb_constants::Source,
+    ckb_types::{bytes::Bytes, prelude::*},
+    high_level::{load_cell_data, load_cell_lock, load_cell_lock_hash, load_script, QueryIter},
+};
+use core::result::Result;
+
+#[cfg(not(any(feature = "library", test)))]
+ckb_std::entry!(program_entry);
+#[cfg(not(any(feature = "library", test)))]
+ckb_std::default_alloc!(4096, 65536, 64);
+
+/// Entry point for the CKB script runtime.
+/// Returns 0 for success, error code for failure.
+pub fn program_entry() -> i8 {
+    match main() {
+        Ok(()) => 0,
+        Err(err) => err as i8,
+    }
+}
+
+// Type script args structure (64 bytes total)
+const VESTING_LOCK_HASH_OFFSET: usize = 0;
+const CREATOR_LOCK_HASH_OFFSET: usize = 32;
+const ARGS_LEN: usize = 64;
+
+// Creator lock hash position within the vesting lock args
+const VESTING_CREATOR_LOCK_HASH_OFFSET: usize = 0;
+
+// Cell data structure: version (1) + label, memo and external reference, each length-prefixed
+const METADATA_VERSION: u8 = 1;
+const MAX_LABEL_LEN: usize = 64;
+const MAX_MEMO_LEN: usize = 255;
+const MAX_EXTERNAL_REF_LEN: usize = 64;
+
+/// Reads a 32-byte hash at the given offset.
+/// Avoids unwrap so no panic formatting code is linked; missing bytes read as zero.
+fn read_hash(bytes: &[u8], offset: usize) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    if let Some(slice) = bytes.get(offset..offset + 32) {
+        hash.copy_from_slice(slice);
+    }
+    hash
+}
+
+/// Skips one length-prefixed field and returns the offset just past it.
+/// Rejects fields that are truncated or longer than the given maximum.
+fn skip_field(data: &[u8], offset: usize, max_len: usize) -> Result<usize, Error> {
+    let len = *data.get(offset).ok_or(Error::InvalidMetadata)? as usize;
+    if len > max_len || data.len() < offset + 1 + len {
+        return Err(Error::InvalidMetadata);
+    }
+    Ok(offset + 1 + len)
+}
+
+/// Validates the layout of a metadata cell's data.
+/// Requires the known version and exactly a label, a memo and an external reference.
+fn validate_metadata(data: &[u8]) -> Result<(), Error> {
+    if data.first() != Some(&METADATA_VERSION) {
+        return Err(Error::InvalidMetadata);
+    }
+
+    let offset = skip_field(data, 1, MAX_LABEL_LEN)?;
+    let offset = skip_field(data, offset, MAX_MEMO_LEN)?;
+    let offset = skip_field(data, offset, MAX_EXTERNAL_REF_LEN)?;
+
+    // Trailing bytes would hide data from readers that follow the layout.
+    if offset != data.len() {
+        return Err(Error::InvalidMetadata);
+    }
+
+    Ok(())
+}
+
+/// Validates that the linked vesting cell is in the transaction and names the same creator.
+/// Searches inputs first, then outputs, so grants can be labelled when created or later.
+fn validate_vesting_link(vesting_lock_hash: &[u8; 32], creator_lock_hash: &[u8; 32]) -> Result<(), Error> {
+    for source in [Source::Input, Source::Output] {
+        let position = QueryIter::new(load_cell_lock_hash, source).position(|lock_hash| &lock_hash == vesting_lock_hash);
+        if let Some(index) = position {
+            let vesting_args: Bytes = load_cell_lock(index, source)?.args().unpack();
+            if &read_hash(&vesting_args, VESTING_CREATOR_LOCK_HASH_OFFSET) != creator_lock_hash {
+                return Err(Error::CreatorMismatch);
+            }
+            return Ok(());
+        }
+    }
+
+    Err(Error::VestingCellNotFound)
+}
+
+/// Main entry point for the grant metadata type script.
+/// Only the grant's creator may create, update or destroy metadata, and new metadata must link to a real grant.
+pub fn main() -> Result<(), Error> {
+    let script = load_script()?;
+    let args: Bytes = script.args().unpack();
+    if args.len() != ARGS_LEN {
+        return Err(Error::InvalidArgs);
+    }
+
+    let vesting_lock_hash = read_hash(&args, VESTING_LOCK_HASH_OFFSET);
+    let creator_lock_hash = read_hash(&args, CREATOR_LOCK_HASH_OFFSET);
+
+    // Every operation requires the creator's authorization through the proxy lock pattern.
+    let creator_authorized = QueryIter::new(load_cell_lock_hash, Source::Input).any(|lock_hash| lock_hash == creator_lock_hash);
+    if !creator_authorized {
+        return Err(Error::Unauthorized);
+    }
+
+    // New metadata must be created next to the vesting cell it describes.
+    let is_creation = QueryIter::new(load_cell_data, Source::GroupInput).next().is_none();
+    if is_creation {
+        validate_vesting_link(&vesting_lock_hash, &creator_lock_hash)?;
+    }
+
+    for data in QueryIter::new(load_cell_data, Source::GroupOutput) {
+        validate_metadata(&data)?;
+    }
+
+    Ok(())
+}
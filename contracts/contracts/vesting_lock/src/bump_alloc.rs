@@ -0,0 +1,57 @@
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::UnsafeCell;
+
+// Same total heap as the default_alloc configuration in main.rs.
+const HEAP_SIZE: usize = 68 * 1024;
+
+/// Bump allocator over a fixed buffer, used only by allocator benchmark builds.
+/// Memory is never reused, so the bump offset is also the peak heap usage.
+pub struct BumpAllocator {
+    heap: UnsafeCell<[u8; HEAP_SIZE]>,
+    next: UnsafeCell<usize>,
+}
+
+// CKB-VM runs a script on a single thread, so unsynchronized interior mutability is sound.
+unsafe impl Sync for BumpAllocator {}
+
+impl BumpAllocator {
+    /// Creates an allocator with an empty heap.
+    /// Intended for the global allocator static.
+    pub const fn new() -> Self {
+        BumpAllocator {
+            heap: UnsafeCell::new([0; HEAP_SIZE]),
+            next: UnsafeCell::new(0),
+        }
+    }
+
+    /// Reports the heap high-water mark through the debug syscall.
+    /// The benchmark tests read this line from the captured script output.
+    pub fn report(&self) {
+        // Read the mark before formatting, which allocates itself.
+        let used = unsafe { *self.next.get() };
+        ckb_std::syscalls::debug(alloc::format!("heap peak: {} bytes", used));
+    }
+}
+
+unsafe impl GlobalAlloc for BumpAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let base = self.heap.get() as *mut u8;
+        let next = &mut *self.next.get();
+
+        // Align the absolute address, then convert back to an offset into the buffer.
+        let align_mask = layout.align() - 1;
+        let start = match (base as usize).checked_add(*next).and_then(|addr| addr.checked_add(align_mask)) {
+            Some(addr) => (addr & !align_mask) - base as usize,
+            None => return core::ptr::null_mut(),
+        };
+        match start.checked_add(layout.size()) {
+            Some(end) if end <= HEAP_SIZE => {
+                *next = end;
+                base.add(start)
+            }
+            _ => core::ptr::null_mut(),
+        }
+    }
+
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+}
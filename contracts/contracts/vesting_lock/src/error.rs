@@ -1,49 +1,155 @@
+/// Exit codes of the vesting lock.
+/// Codes up to 64 keep the numbers they were released with. Errors are grouped by the validation stage that
+/// returns them, and codes added since come from the block reserved for that stage, given on each section.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Error {
-    // CKB syscall errors
+    // CKB syscall errors (new codes 5-9)
+    /// A syscall index is out of bounds.
     IndexOutOfBound = 1,
+    /// A requested transaction item is missing.
     ItemMissing = 2,
+    /// A syscall buffer is too small for the loaded data.
     LengthNotEnough = 3,
+    /// A syscall returned data that failed to decode.
     InvalidData = 4,
 
-    // Script-specific errors
+    // Argument errors (new codes 65-72)
+    /// Script args are malformed or carry an invalid option.
     InvalidArgs = 10,
-    InvalidWitness = 11,
-    InvalidTransaction = 12,
-    InvalidTransactionStructure = 13,
-    TotalAmountChanged = 14,
-    InvalidBeneficiaryClaimedDelta = 15,
-    InvalidCreatorClaimedDelta = 16,
-    InvalidStateChange = 17,
+    /// Epochs are not ordered start <= cliff <= end with start < end.
+    InvalidEpoch = 23,
 
-    // Vesting logic errors
+    // Vesting math errors (new codes 73-80)
+    /// Termination did not reclaim exactly the unvested amount.
     InvalidAmount = 20,
+    /// The claim exceeds the vested amount.
     InsufficientVested = 21,
+    /// The grant has already been terminated.
     AlreadyTerminated = 22,
-    InvalidEpoch = 23,
+    /// Everything is vested, so there is nothing to terminate.
+    NothingToTerminate = 44,
+    /// A tranche schedule or the tranche count changed.
+    TrancheChanged = 48,
+    /// A tranche claim exceeds its vested amount or decreased.
+    InvalidTrancheClaim = 49,
+    /// One transition increased both beneficiary_claimed and creator_claimed.
+    SimultaneousClaimAndTermination = 58,
+    /// A claim is not a multiple of the configured claim unit.
+    InvalidClaimUnit = 63,
+    /// A beneficiary's claims exceed its share of the vested amount.
+    InsufficientVestedShare = 73,
+
+    // Header errors (new codes 81-88)
+    /// No header dependency is newer than highest_block_seen.
     StaleHeader = 24,
-    Unauthorized = 25,
+    /// The continuation cell lowered highest_block_seen.
     BlockNumberDecrease = 26,
+    /// The continuation cell's highest_block_seen does not match the highest header block.
     BlockNumberMismatch = 27,
+    /// The transaction has no header dependencies.
+    NoHeaderDependencies = 35,
+    /// The transaction has more than 32 header dependencies.
+    TooManyHeaderDependencies = 45,
+    /// An anonymous update advanced fewer blocks than the minimum interval.
+    UpdateTooFrequent = 53,
+    /// Fewer distinct header dependencies than required.
+    InsufficientHeaderDependencies = 54,
+    /// The header block advances further than the configured maximum.
+    BlockAdvanceTooLarge = 55,
+    /// The vesting input's since is not an absolute epoch.
+    InvalidSince = 81,
+    /// The since is earlier than the point recorded by the previous transition.
+    StaleSince = 82,
 
-    // Encoding errors
-    InvalidCellData = 30, // Deprecated - use specific errors below
-    LoadCellDataFailed = 31,
-    WrongDataLength = 32,
+    // Transaction structure errors (new codes 89-96)
+    /// Reserved: invalid transaction.
+    InvalidTransaction = 12,
+    /// Reserved: invalid transaction structure.
+    InvalidTransactionStructure = 13,
+    /// No input cell uses this vesting lock.
     NoMatchingInputCell = 33,
+    /// No output cell uses this vesting lock.
     NoMatchingOutputCell = 34,
-    NoHeaderDependencies = 35,
-
-    // Transaction structure errors
+    /// More than one input cell uses this vesting lock.
     MultipleInputsNotAllowed = 36,
+    /// A partial termination has no continuation cell.
     CreatorOperationMissingOutput = 37,
+    /// An anonymous update has no continuation cell.
     AnonymousUpdateMissingOutput = 38,
-    InputDataWrongLength = 39,
-    OutputDataWrongLength = 40,
+    /// A full termination left a continuation cell.
     CreatorFullTerminationHasOutput = 41,
+    /// A full claim left a continuation cell.
     BeneficiaryFullClaimHasOutput = 42,
+    /// A partial claim has no continuation cell.
     BeneficiaryPartialClaimMissingOutput = 43,
-    NothingToTerminate = 44,
+
+    // Cell data errors (new codes 97-104)
+    /// Deprecated: invalid cell data.
+    InvalidCellData = 30, // Deprecated - use specific errors below
+    /// Cell data could not be loaded.
+    LoadCellDataFailed = 31,
+    /// The vesting input cell data has the wrong length.
+    WrongDataLength = 32,
+    /// The input cell data has the wrong length.
+    InputDataWrongLength = 39,
+    /// The continuation cell data has the wrong length.
+    OutputDataWrongLength = 40,
+    /// A tranche record is invalid.
+    InvalidTrancheData = 47,
+    /// A payout output carries unexpected data.
+    PayoutOutputHasData = 50,
+    /// The cell data extension area has an unknown version, does not match the co-beneficiaries or merkle tree, or was not carried forward unchanged.
+    InvalidDataExtension = 59,
+    /// A UDT vesting cell or its continuation lacks the configured type script, a token amount or a valid xUDT data table.
+    InvalidUdtCell = 97,
+
+    // State transition errors (new codes 105-112)
+    /// The continuation cell changed total_amount.
+    TotalAmountChanged = 14,
+    /// The beneficiary_claimed counter changed in a way the operation does not allow.
+    InvalidBeneficiaryClaimedDelta = 15,
+    /// The creator_claimed counter changed in a way the operation does not allow.
+    InvalidCreatorClaimedDelta = 16,
+    /// An anonymous update changed state other than highest_block_seen.
+    InvalidStateChange = 17,
+    /// A beneficiary's claimed amount decreased or several beneficiaries claimed in one transition.
+    InvalidBeneficiaryClaims = 105,
+    /// The continuation's merkle root does not record the claimed leaf's new claimed amount.
+    MerkleRootMismatch = 106,
+
+    // Authorization and witness errors (new codes 113-120)
+    /// The vesting input witness is malformed or not allowed for the operation.
+    InvalidWitness = 11,
+    /// The transaction lacks the authorization the operation requires.
+    Unauthorized = 25,
+    /// The witness event does not match the transition.
+    InvalidEvent = 51,
+    /// The role rotation is invalid.
+    InvalidRotation = 56,
+    /// The role rotation is not yet effective.
+    RotationNotReady = 57,
+    /// The creator buyout is malformed, keeps a continuation cell or pays less than the vested amount.
+    InvalidBuyout = 61,
+    /// The merkle proof does not prove the claimed leaf against the cell's current root.
+    InvalidMerkleProof = 113,
+
+    // Payout errors (new codes 121-127)
+    /// The redirected payout, or the tokens delivered by a UDT claim, are smaller than the claimed amount.
+    InsufficientPayout = 46,
+    /// An anonymous update removed more capacity than the keeper tip.
+    KeeperTipExceeded = 52,
+    /// A basis-point cell's total is not 10000 or a transition released more capacity than it claimed.
+    InvalidBasisPointBacking = 60,
+    /// A grantor received less than its share of the reclaimed amount.
+    InsufficientGrantorShare = 62,
+    /// A sweep sent less than the settled cell's capacity to the sweep lock.
+    InsufficientSweep = 64,
+    /// A UDT transition left fewer tokens in the continuation cell than it did not claim.
+    InvalidUdtBacking = 121,
+    /// A settled cell was swept without a sweep lock option naming where its capacity goes.
+    MissingSweepLock = 122,
+    /// A UDT continuation cell kept less capacity than its input less the keeper tip and any capacity vested with the claim.
+    UdtCapacityNotConserved = 123,
 }
 
 impl From<ckb_std::error::SysError> for Error {
@@ -1,34 +1,53 @@
 #![cfg_attr(not(any(feature = "library", test)), no_std)]
 #![cfg_attr(not(test), no_main)]
 
-#[cfg(any(feature = "library", test))]
+#[cfg(any(feature = "library", feature = "bump-allocator", test))]
 extern crate alloc;
 
+#[cfg(all(feature = "bump-allocator", not(any(feature = "library", test))))]
+mod bump_alloc;
 mod error;
 use error::Error;
 
+use ckb_hash::new_blake2b;
 use ckb_std::{
     ckb_constants::Source,
-    ckb_types::{bytes::Bytes, prelude::*},
+    ckb_types::{
+        bytes::Bytes,
+        packed::{Script, WitnessArgs},
+        prelude::*,
+    },
+    error::SysError,
     high_level::{
-        load_cell, load_cell_data, load_cell_lock_hash, load_header, load_script,
-        QueryIter,
+        load_cell_capacity, load_cell_lock, load_cell_lock_hash, load_cell_occupied_capacity, load_cell_type,
+        load_cell_type_hash, load_header, load_input_since, load_script, load_witness_args, QueryIter,
     },
+    syscalls,
 };
 use core::result::Result;
 
 #[cfg(not(any(feature = "library", test)))]
 ckb_std::entry!(program_entry);
-#[cfg(not(any(feature = "library", test)))]
-ckb_std::default_alloc!(16384, 1258306, 64);
+// Heap usage is limited to the script and transient header copies, so a small heap suffices.
+#[cfg(not(any(feature = "library", feature = "bump-allocator", test)))]
+ckb_std::default_alloc!(4096, 65536, 64);
+// Benchmark builds swap in a bump allocator so peak heap usage can be measured.
+#[cfg(all(feature = "bump-allocator", not(any(feature = "library", test))))]
+#[global_allocator]
+static ALLOC: bump_alloc::BumpAllocator = bump_alloc::BumpAllocator::new();
 
 /// Entry point for the CKB script runtime.
 /// Returns 0 for success, error code for failure.
 pub fn program_entry() -> i8 {
-    match main() {
+    let code = match main() {
         Ok(()) => 0,
         Err(err) => err as i8,
-    }
+    };
+
+    #[cfg(all(feature = "bump-allocator", not(any(feature = "library", test))))]
+    ALLOC.report();
+
+    code
 }
 
 // Lock script args structure (88 bytes total)
@@ -39,6 +58,153 @@ const END_EPOCH_OFFSET: usize = 72;
 const CLIFF_EPOCH_OFFSET: usize = 80;
 const ARGS_LEN: usize = 88;
 
+// Optional args extension: a version byte followed by (tag, length, value) option records
+const ARGS_EXTENSION_VERSION: u8 = 1;
+const OPTION_CREATOR_AUTH_POLICY: u8 = 0x01;
+const OPTION_BENEFICIARY_AUTH_POLICY: u8 = 0x02;
+const OPTION_KEEPER_TIP: u8 = 0x03;
+const OPTION_MIN_UPDATE_INTERVAL: u8 = 0x04;
+const OPTION_MIN_HEADER_DEPS: u8 = 0x05;
+const OPTION_MAX_BLOCK_ADVANCE: u8 = 0x06;
+const OPTION_CREATOR_MIGRATION_DELAY: u8 = 0x07;
+const OPTION_PENDING_CREATOR_MIGRATION: u8 = 0x08;
+const OPTION_GUARDIANS: u8 = 0x09;
+const OPTION_PENDING_BENEFICIARY_RECOVERY: u8 = 0x0a;
+const OPTION_PERFORMANCE_ORACLE: u8 = 0x0b;
+const OPTION_END_BONUS: u8 = 0x0c;
+const OPTION_BPS_DENOMINATION: u8 = 0x0d;
+const OPTION_TERMINATION_ACCELERATION: u8 = 0x0e;
+const OPTION_CO_GRANTORS: u8 = 0x0f;
+const OPTION_CLIFF_ACCRUAL: u8 = 0x10;
+const OPTION_CLAIM_UNIT: u8 = 0x11;
+const OPTION_SWEEP_LOCK: u8 = 0x12;
+const OPTION_UDT_TYPE: u8 = 0x13;
+const OPTION_XUDT_TYPE: u8 = 0x14;
+const OPTION_SCHEDULE: u8 = 0x15;
+const OPTION_GRANULARITY: u8 = 0x16;
+const OPTION_SCHEDULE_UNIT: u8 = 0x17;
+const OPTION_TIME_SOURCE: u8 = 0x18;
+const OPTION_CO_BENEFICIARIES: u8 = 0x19;
+const OPTION_MERKLE_BENEFICIARIES: u8 = 0x1a;
+const OPTION_CREATOR_SIGNERS: u8 = 0x1b;
+const OPTION_UDT_UNIT: u8 = 0x1c;
+const OPTION_CHEQUE_LOCK: u8 = 0x1d;
+const OPTION_VESTED_CAPACITY: u8 = 0x1e;
+
+// Auth policy option value: policy (1) + code_hash (32) + hash_type (1) + args prefix
+const AUTH_POLICY_EXACT_HASH: u8 = 0;
+const AUTH_POLICY_CODE_HASH: u8 = 1;
+const AUTH_POLICY_ARGS_PREFIX: u8 = 2;
+const PATTERN_CODE_HASH_OFFSET: usize = 1;
+const PATTERN_HASH_TYPE_OFFSET: usize = 33;
+const PATTERN_ARGS_PREFIX_OFFSET: usize = 34;
+const MAX_ARGS_PREFIX_LEN: usize = 32;
+
+// Guardians option value: threshold (1) + recovery delay (8) + guardian lock hashes (32 each)
+const GUARDIAN_THRESHOLD_OFFSET: usize = 0;
+const GUARDIAN_DELAY_OFFSET: usize = 1;
+const GUARDIAN_HASHES_OFFSET: usize = 9;
+const MAX_GUARDIANS: usize = 7;
+
+// Creator signers option value: threshold (1) + signer lock hashes (32 each)
+const CREATOR_SIGNER_THRESHOLD_OFFSET: usize = 0;
+const CREATOR_SIGNER_HASHES_OFFSET: usize = 1;
+const MAX_CREATOR_SIGNERS: usize = 7;
+
+// Cheque lock option value: code_hash (32) + hash_type (1)
+// Cheque args: receiver lock hash prefix (20) + sender lock hash prefix (20)
+const CHEQUE_HASH_TYPE_OFFSET: usize = 32;
+const CHEQUE_OPTION_LEN: usize = 33;
+const CHEQUE_LOCK_HASH_PREFIX_LEN: usize = 20;
+
+// Performance oracle option value: oracle lock hash (32) + min, max and current multiplier in bps (2 each)
+const ORACLE_LOCK_HASH_OFFSET: usize = 0;
+const ORACLE_MIN_BPS_OFFSET: usize = 32;
+const ORACLE_MAX_BPS_OFFSET: usize = 34;
+const ORACLE_CURRENT_BPS_OFFSET: usize = 36;
+const ORACLE_LEN: usize = 38;
+const BPS_DENOMINATOR: u128 = 10_000;
+
+// Cliff accrual option values (1 byte)
+const CLIFF_ACCRUAL_RETROACTIVE: u8 = 0;
+const CLIFF_ACCRUAL_FROM_CLIFF: u8 = 1;
+
+// Co-grantors option value: one or more records of lock hash (32) + share of reclaimed funds in bps (2)
+const CO_GRANTOR_LOCK_HASH_OFFSET: usize = 0;
+const CO_GRANTOR_SHARE_OFFSET: usize = 32;
+const CO_GRANTOR_LEN: usize = 34;
+const MAX_CO_GRANTORS: usize = 4;
+
+// Co-beneficiaries option value: one or more records of lock hash (32) + share of the vested amount in bps (2)
+const CO_BENEFICIARY_LOCK_HASH_OFFSET: usize = 0;
+const CO_BENEFICIARY_SHARE_OFFSET: usize = 32;
+const CO_BENEFICIARY_LEN: usize = 34;
+const MAX_CO_BENEFICIARIES: usize = 4;
+
+// Merkle beneficiaries option value: tree depth (1) + root of the tree before any claim (32)
+const MERKLE_DEPTH_OFFSET: usize = 0;
+const MERKLE_OPTION_LEN: usize = 33;
+const MAX_MERKLE_DEPTH: usize = 20;
+
+// Merkle leaf: lock hash (32) + amount (8) + start, end and cliff epoch (8 each) + claimed (8)
+const LEAF_LOCK_HASH_OFFSET: usize = 0;
+const LEAF_AMOUNT_OFFSET: usize = 32;
+const LEAF_START_EPOCH_OFFSET: usize = 40;
+const LEAF_END_EPOCH_OFFSET: usize = 48;
+const LEAF_CLIFF_EPOCH_OFFSET: usize = 56;
+const LEAF_CLAIMED_OFFSET: usize = 64;
+const LEAF_LEN: usize = 72;
+
+// Schedule option value: schedule type (1) followed by that type's fields
+const SCHEDULE_LINEAR: u8 = 0;
+const SCHEDULE_MILESTONES: u8 = 1;
+const SCHEDULE_FIELDS_OFFSET: usize = 1;
+
+// Schedule unit option values (1 byte)
+const SCHEDULE_UNIT_EPOCHS: u8 = 0;
+const SCHEDULE_UNIT_BLOCKS: u8 = 1;
+
+// Time source option values (1 byte)
+const TIME_SOURCE_HEADER_DEPS: u8 = 0;
+const TIME_SOURCE_INPUT_SINCE: u8 = 1;
+
+// Milestone schedule fields: one or more records of epoch (8) + share of the total in bps (2)
+const MILESTONE_EPOCH_OFFSET: usize = 0;
+const MILESTONE_SHARE_OFFSET: usize = 8;
+const MILESTONE_LEN: usize = 10;
+const MAX_MILESTONES: usize = 8;
+
+// Pending creator migration or beneficiary recovery option value (40 bytes)
+const MIGRATION_NEW_LOCK_HASH_OFFSET: usize = 0;
+const MIGRATION_EFFECTIVE_BLOCK_OFFSET: usize = 32;
+const MIGRATION_LEN: usize = 40;
+
+// UDT cell data prefix: the 128-bit token amount precedes the vesting state, as the sUDT standard requires
+const UDT_AMOUNT_OFFSET: usize = 0;
+const UDT_AMOUNT_LEN: usize = 16;
+
+// xUDT cells place a molecule XudtData table after the amount; its first four bytes hold its full size
+const XUDT_DATA_SIZE_LEN: usize = 4;
+const XUDT_DATA_MIN_LEN: usize = 20;
+
+// xUDT type script args: owner lock hash (32) + optional flags (4); the low 29 bits of the flags select extension scripts
+const XUDT_FLAGS_OFFSET: usize = 32;
+const XUDT_EXTENSION_FLAGS_MASK: u32 = 0x1fff_ffff;
+
+// Packed header epoch: number (24 bits), index (16 bits) and length (16 bits) of the epoch in progress
+const EPOCH_NUMBER_MASK: u64 = 0xff_ffff;
+const EPOCH_INDEX_SHIFT: u32 = 24;
+const EPOCH_LENGTH_SHIFT: u32 = 40;
+const EPOCH_FRACTION_MASK: u64 = 0xffff;
+
+// Input since: flags in the top byte, where an absolute epoch carries a packed epoch in the low 56 bits
+const SINCE_FLAGS_SHIFT: u32 = 56;
+const SINCE_ABSOLUTE_EPOCH_FLAGS: u64 = 0x20;
+const SINCE_VALUE_MASK: u64 = (1 << SINCE_FLAGS_SHIFT) - 1;
+
+// Schedule points count whole epochs (or blocks) in units of this many fractions
+const SCHEDULE_POINT_SCALE: u64 = 1 << 16;
+
 // Cell data structure (32 bytes total)
 const TOTAL_AMOUNT_OFFSET: usize = 0;
 const BENEFICIARY_CLAIMED_OFFSET: usize = 8;
@@ -46,467 +212,2969 @@ const CREATOR_CLAIMED_OFFSET: usize = 16;
 const HIGHEST_BLOCK_SEEN_OFFSET: usize = 24;
 const DATA_LEN: usize = 32;
 
-#[derive(Debug, Clone, Copy)]
+// Layout byte that follows the cell data whenever more data does: the number of tranche records (1)
+const TRANCHE_COUNT_OFFSET: usize = DATA_LEN;
+const TRANCHES_OFFSET: usize = DATA_LEN + 1;
+
+// Optional tranche records after the layout byte (40 bytes each)
+const TRANCHE_TOTAL_AMOUNT_OFFSET: usize = 0;
+const TRANCHE_CLAIMED_OFFSET: usize = 8;
+const TRANCHE_START_EPOCH_OFFSET: usize = 16;
+const TRANCHE_END_EPOCH_OFFSET: usize = 24;
+const TRANCHE_CLIFF_EPOCH_OFFSET: usize = 32;
+const TRANCHE_LEN: usize = 40;
+const MAX_TRANCHES: usize = 8;
+
+// Optional extension area after the counted tranche records (1-39 bytes): a version byte followed by versioned fields
+const DATA_EXTENSION_VERSION_OFFSET: usize = 0;
+const DATA_EXTENSION_VERSION: u8 = 1;
+const MAX_DATA_EXTENSION_LEN: usize = TRANCHE_LEN - 1;
+
+// Claims extension (version 2) of cells with co-beneficiaries: each co-beneficiary's claimed counter (8 each), in option order
+const DATA_EXTENSION_CLAIMS_VERSION: u8 = 2;
+const CO_BENEFICIARY_CLAIMED_OFFSET: usize = 1;
+const CO_BENEFICIARY_CLAIMED_LEN: usize = 8;
+
+// Merkle extension (version 3) of merkle cells: the root of the tree with every leaf's current claimed amount (32)
+const DATA_EXTENSION_MERKLE_VERSION: u8 = 3;
+const MERKLE_EXTENSION_ROOT_OFFSET: usize = 1;
+const MERKLE_EXTENSION_LEN: usize = 33;
+const MAX_DATA_LEN: usize = TRANCHES_OFFSET + MAX_TRANCHES * TRANCHE_LEN + MAX_DATA_EXTENSION_LEN;
+
+// Optional event in the witness output_type field (26 bytes)
+const EVENT_VERSION_OFFSET: usize = 0;
+const EVENT_OPERATION_OFFSET: usize = 1;
+const EVENT_AMOUNT_OFFSET: usize = 2;
+const EVENT_EPOCH_OFFSET: usize = 10;
+const EVENT_BLOCK_NUMBER_OFFSET: usize = 18;
+const EVENT_LEN: usize = 26;
+const EVENT_VERSION: u8 = 1;
+const EVENT_OPERATION_BLOCK_UPDATE: u8 = 0;
+const EVENT_OPERATION_CLAIM: u8 = 1;
+const EVENT_OPERATION_TERMINATION: u8 = 2;
+
+// Optional role rotation in the witness input_type field (5 bytes)
+const ROTATION_OPERATION_OFFSET: usize = 0;
+const ROTATION_OUTPUT_INDEX_OFFSET: usize = 1;
+const ROTATION_LEN: usize = 5;
+const ROTATION_INITIATE_CREATOR_MIGRATION: u8 = 1;
+const ROTATION_VETO_CREATOR_MIGRATION: u8 = 2;
+const ROTATION_FINALIZE_CREATOR_MIGRATION: u8 = 3;
+const ROTATION_INITIATE_BENEFICIARY_RECOVERY: u8 = 4;
+const ROTATION_OBJECT_BENEFICIARY_RECOVERY: u8 = 5;
+const ROTATION_FINALIZE_BENEFICIARY_RECOVERY: u8 = 6;
+const ROTATION_SET_PERFORMANCE_MULTIPLIER: u8 = 7;
+const ROTATION_TRANSFER_BENEFICIARY: u8 = 10;
+
+// Optional creator buyout in the witness input_type field (9 bytes), sharing the operation byte with rotations
+const BUYOUT_OPERATION_OFFSET: usize = 0;
+const BUYOUT_AMOUNT_OFFSET: usize = 1;
+const BUYOUT_LEN: usize = 9;
+const OPERATION_CREATOR_BUYOUT: u8 = 8;
+
+// Optional merkle claim in the witness input_type field, sharing the operation byte with rotations:
+// operation (1) + leaf index (4) + leaf (72) + one sibling hash (32) per tree level, starting at the leaves
+const MERKLE_CLAIM_OPERATION_OFFSET: usize = 0;
+const MERKLE_CLAIM_INDEX_OFFSET: usize = 1;
+const MERKLE_CLAIM_LEAF_OFFSET: usize = 5;
+const MERKLE_CLAIM_SIBLINGS_OFFSET: usize = 77;
+const OPERATION_MERKLE_CLAIM: u8 = 9;
+
+// Largest data a typed payout output may carry: a 16-byte UDT amount (NervosDAO deposits use 8)
+const MAX_TYPED_PAYOUT_DATA_LEN: usize = 16;
+
+// Upper bound on header deps inspected, keeping header iteration cost predictable.
+const MAX_HEADER_DEPS: usize = 32;
+
+#[derive(Clone, Copy)]
 enum AuthorizationType {
     Creator,
     Beneficiary,
     None,
 }
 
-#[derive(Debug)]
+/// Roles whose lock hashes appear among the transaction inputs.
+#[derive(Clone, Copy, Default)]
+struct AuthorizedRoles {
+    creator: bool,
+    beneficiary: bool,
+    co_grantor: bool,
+    co_beneficiaries: [bool; MAX_CO_BENEFICIARIES],
+}
+
+/// Matches lock scripts by code hash, hash type and args prefix instead of the exact lock hash.
+/// Supports locks such as JoyID whose args, and therefore hash, change with credential rotation.
+#[derive(Clone, Copy, PartialEq)]
+struct LockPattern {
+    code_hash: [u8; 32],
+    hash_type: u8,
+    args_prefix: [u8; MAX_ARGS_PREFIX_LEN],
+    args_prefix_len: usize,
+}
+
+/// The cheque lock through which UDT claims may pay receivers that have no token cell of their own.
+/// Cheque cells are matched by code hash and hash type, and their args name the receiver and the sender.
+#[derive(Clone, Copy, PartialEq)]
+struct ChequeLock {
+    code_hash: [u8; 32],
+    hash_type: u8,
+}
+
+/// A role lock hash change that takes effect once the chain reaches the effective block.
+#[derive(Clone, Copy, PartialEq)]
+struct PendingMigration {
+    new_lock_hash: [u8; 32],
+    effective_block: u64,
+}
+
+/// Guardians that may jointly rotate a lost beneficiary key after a waiting period.
+#[derive(Clone, Copy, PartialEq)]
+struct GuardianSet {
+    threshold: usize,
+    recovery_delay: u64,
+    lock_hashes: [[u8; 32]; MAX_GUARDIANS],
+    count: usize,
+}
+
+/// An m-of-n set of locks that must jointly authorize the creator role in place of the creator lock.
+/// The creator lock hash still receives reclaimed funds.
+#[derive(Clone, Copy, PartialEq)]
+struct CreatorSigners {
+    threshold: usize,
+    lock_hashes: [[u8; 32]; MAX_CREATOR_SIGNERS],
+    count: usize,
+}
+
+/// Additional creators of a jointly funded grant and their shares of reclaimed funds.
+/// The primary creator's share is whatever the co-grantors' shares leave of 10000 bps.
+#[derive(Clone, Copy, PartialEq)]
+struct CoGrantors {
+    lock_hashes: [[u8; 32]; MAX_CO_GRANTORS],
+    shares_bps: [u16; MAX_CO_GRANTORS],
+    count: usize,
+}
+
+/// Beneficiaries sharing a grant with the primary beneficiary and their shares of the vested amount.
+/// The primary beneficiary's share is whatever the co-beneficiaries' shares leave of 10000 bps.
+#[derive(Clone, Copy, PartialEq)]
+struct CoBeneficiaries {
+    lock_hashes: [[u8; 32]; MAX_CO_BENEFICIARIES],
+    shares_bps: [u16; MAX_CO_BENEFICIARIES],
+    count: usize,
+}
+
+/// A merkle tree of beneficiary leaves, each with its own amount, schedule and claimed amount.
+/// Only the depth is kept: the args root names the tree as created, and the cell data tracks the root as claims update it.
+#[derive(Clone, Copy, PartialEq)]
+struct MerkleBeneficiaries {
+    depth: usize,
+}
+
+/// Discrete unlocks of a milestone schedule, each releasing a share of the total at its epoch.
+/// Epochs are strictly increasing and the shares total exactly 10000 bps.
+#[derive(Clone, Copy, PartialEq)]
+struct Milestones {
+    epochs: [u64; MAX_MILESTONES],
+    shares_bps: [u16; MAX_MILESTONES],
+    count: usize,
+}
+
+/// How the base schedule vests between its start and end epochs.
+#[derive(Clone, Copy, PartialEq)]
+enum Schedule {
+    Linear,
+    Milestones(Milestones),
+}
+
+/// The shape of one vesting schedule, either the base schedule or a tranche.
+#[derive(Clone, Copy)]
+struct ScheduleParams<'a> {
+    schedule: &'a Schedule,
+    granularity: Option<u64>,
+    start_epoch: u64,
+    end_epoch: u64,
+    cliff_epoch: u64,
+}
+
+/// An oracle that scales vested amounts by a multiplier within creator-defined bounds.
+#[derive(Clone, Copy, PartialEq)]
+struct PerformanceOracle {
+    lock_hash: [u8; 32],
+    min_bps: u16,
+    max_bps: u16,
+    current_bps: u16,
+}
+
+#[derive(Clone, Copy, PartialEq)]
 struct VestingConfig {
     creator_lock_hash: [u8; 32],
     beneficiary_lock_hash: [u8; 32],
     start_epoch: u64,
     end_epoch: u64,
     cliff_epoch: u64,
+    schedule: Schedule,
+    granularity: Option<u64>,
+    schedule_in_blocks: bool,
+    since_timing: bool,
+    creator_auth_lock: Option<LockPattern>,
+    beneficiary_auth_lock: Option<LockPattern>,
+    keeper_tip: u64,
+    min_update_interval: u64,
+    min_header_deps: usize,
+    max_block_advance: Option<u64>,
+    creator_migration_delay: Option<u64>,
+    pending_creator_migration: Option<PendingMigration>,
+    guardians: Option<GuardianSet>,
+    pending_beneficiary_recovery: Option<PendingMigration>,
+    performance_oracle: Option<PerformanceOracle>,
+    end_bonus: u64,
+    bps_reserve: Option<u64>,
+    termination_acceleration_bps: u16,
+    co_grantors: Option<CoGrantors>,
+    creator_signers: Option<CreatorSigners>,
+    co_beneficiaries: Option<CoBeneficiaries>,
+    merkle_beneficiaries: Option<MerkleBeneficiaries>,
+    accrual_from_cliff: bool,
+    claim_unit: Option<u64>,
+    sweep_lock_hash: Option<[u8; 32]>,
+    udt_type_hash: Option<[u8; 32]>,
+    xudt: bool,
+    udt_unit: Option<u64>,
+    cheque_lock: Option<ChequeLock>,
+    capacity_per_unit: Option<u64>,
+}
+
+/// An additional grant carried in the same cell with its own schedule and claimed counter.
+#[derive(Clone, Copy, Default, PartialEq)]
+struct Tranche {
+    total_amount: u64,
+    claimed: u64,
+    start_epoch: u64,
+    end_epoch: u64,
+    cliff_epoch: u64,
 }
 
-#[derive(Debug)]
+/// Aggregate vesting state of a cell plus its tranche records.
+/// The base schedule from the args covers the part of the total not assigned to a tranche.
 struct VestingState {
     total_amount: u64,
     beneficiary_claimed: u64,
     creator_claimed: u64,
     highest_block_seen: u64,
+    tranches: [Tranche; MAX_TRANCHES],
+    tranche_count: usize,
+    extension: [u8; MAX_DATA_EXTENSION_LEN],
+    extension_len: usize,
+}
+
+/// Raw cell data loaded into a fixed-size buffer.
+struct StateData {
+    bytes: [u8; MAX_DATA_LEN],
+    len: usize,
+}
+
+/// Loads the vesting state data of a cell, starting at the given data offset, without allocating.
+/// Returns the given wrong-length error unless the data is DATA_LEN bytes, optionally followed by
+/// the tranche count, at most MAX_TRANCHES tranche records and an extension area.
+fn load_state_data(index: usize, source: Source, data_offset: usize, wrong_length: Error) -> Result<StateData, Error> {
+    let mut bytes = [0u8; MAX_DATA_LEN];
+    match syscalls::load_cell_data(&mut bytes, data_offset, index, source) {
+        Ok(len) if len >= DATA_LEN => Ok(StateData { bytes, len }),
+        // Shorter data fits the buffer, longer data reports its full length.
+        Ok(_) | Err(SysError::LengthNotEnough(_)) => Err(wrong_length),
+        Err(_) => Err(Error::LoadCellDataFailed),
+    }
 }
 
 /// Finds the input cell data that matches the current script's lock hash.
 /// Used for lock scripts to locate their input cell.
-fn find_matching_input_data() -> Result<Bytes, Error> {
+fn find_matching_input_data(script_hash: &[u8; 32], vesting_config: &VestingConfig) -> Result<StateData, Error> {
     // Locate input cell with matching lock script hash.
-    let current_script = load_script()?;
-    let current_script_hash = current_script.calc_script_hash();
+    let index = QueryIter::new(load_cell_lock_hash, Source::Input)
+        .position(|lock_hash| &lock_hash == script_hash)
+        .ok_or(Error::NoMatchingInputCell)?;
+    let data_offset = state_data_offset(vesting_config, index, Source::Input)?;
+    load_state_data(index, Source::Input, data_offset, Error::WrongDataLength)
+}
 
-    let mut index = 0;
-    while let Ok(input_cell) = load_cell(index, Source::Input) {
-        if input_cell.lock().calc_script_hash() == current_script_hash {
-            let data = load_cell_data(index, Source::Input).map_err(|_| Error::LoadCellDataFailed)?;
-            return Ok(Bytes::from(data));
-        }
-        index += 1;
-    }
-    Err(Error::NoMatchingInputCell)
+/// Finds the index of the output cell that matches the current script's lock hash.
+/// Returns an error if no matching output cell is found.
+fn find_matching_output_index(script_hash: &[u8; 32]) -> Result<usize, Error> {
+    QueryIter::new(load_cell_lock_hash, Source::Output)
+        .position(|lock_hash| &lock_hash == script_hash)
+        .ok_or(Error::NoMatchingOutputCell)
 }
 
 /// Finds the output cell data that matches the current script's lock hash.
-/// Returns an error if no matching output cell is found.
-fn find_matching_output_data() -> Result<Bytes, Error> {
-    let current_script = load_script()?;
-    let current_script_hash = current_script.calc_script_hash();
+/// Returns an error if no matching output cell is found or its data has the wrong length.
+fn find_matching_output_data(
+    script_hash: &[u8; 32],
+    vesting_config: &VestingConfig,
+    wrong_length: Error,
+) -> Result<StateData, Error> {
+    let index = find_matching_output_index(script_hash)?;
+    let data_offset = state_data_offset(vesting_config, index, Source::Output)?;
+    load_state_data(index, Source::Output, data_offset, wrong_length)
+}
 
-    let mut index = 0;
-    while let Ok(output_cell) = load_cell(index, Source::Output) {
-        if output_cell.lock().calc_script_hash() == current_script_hash {
-            let data = load_cell_data(index, Source::Output).map_err(|_| Error::LoadCellDataFailed)?;
-            return Ok(Bytes::from(data));
-        }
-        index += 1;
+/// Returns where the vesting state starts in a cell's data.
+/// UDT cells keep the token amount in front of it, and xUDT cells also their XudtData table.
+fn state_data_offset(vesting_config: &VestingConfig, index: usize, source: Source) -> Result<usize, Error> {
+    if vesting_config.udt_type_hash.is_none() {
+        return Ok(0);
+    }
+    if !vesting_config.xudt || !has_xudt_data(index, source)? {
+        return Ok(UDT_AMOUNT_LEN);
+    }
+
+    // Only the table's size header is needed to skip over it.
+    let mut size = [0u8; XUDT_DATA_SIZE_LEN];
+    match syscalls::load_cell_data(&mut size, UDT_AMOUNT_LEN, index, source) {
+        Ok(len) | Err(SysError::LengthNotEnough(len)) if len >= XUDT_DATA_SIZE_LEN => {}
+        Ok(_) | Err(SysError::LengthNotEnough(_)) => return Err(Error::InvalidUdtCell),
+        Err(_) => return Err(Error::LoadCellDataFailed),
+    }
+
+    let xudt_data_len = u32::from_le_bytes(size) as usize;
+    if xudt_data_len < XUDT_DATA_MIN_LEN {
+        return Err(Error::InvalidUdtCell);
+    }
+    Ok(UDT_AMOUNT_LEN + xudt_data_len)
+}
+
+/// Returns whether an xUDT cell carries a XudtData table after its amount.
+/// The table only exists when the flags in the cell's type script args enable extension scripts.
+fn has_xudt_data(index: usize, source: Source) -> Result<bool, Error> {
+    let Some(type_script) = load_cell_type(index, source)? else {
+        return Ok(false);
+    };
+    // Args without flags read as zero flags.
+    let flags = read_u32(&type_script.args().raw_data(), XUDT_FLAGS_OFFSET);
+    Ok(flags & XUDT_EXTENSION_FLAGS_MASK != 0)
+}
+
+/// Reads a little-endian u64 at the given offset.
+/// Avoids unwrap so no panic formatting code is linked; missing bytes read as zero.
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    let mut value = [0u8; 8];
+    if let Some(slice) = bytes.get(offset..offset + 8) {
+        value.copy_from_slice(slice);
+    }
+    u64::from_le_bytes(value)
+}
+
+/// Reads a little-endian u32 at the given offset.
+/// Avoids unwrap so no panic formatting code is linked; missing bytes read as zero.
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    let mut value = [0u8; 4];
+    if let Some(slice) = bytes.get(offset..offset + 4) {
+        value.copy_from_slice(slice);
+    }
+    u32::from_le_bytes(value)
+}
+
+/// Reads a little-endian u16 at the given offset.
+/// Avoids unwrap so no panic formatting code is linked; missing bytes read as zero.
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    let mut value = [0u8; 2];
+    if let Some(slice) = bytes.get(offset..offset + 2) {
+        value.copy_from_slice(slice);
     }
-    Err(Error::NoMatchingOutputCell)
+    u16::from_le_bytes(value)
 }
 
+/// Reads a 32-byte hash at the given offset.
+/// Avoids unwrap so no panic formatting code is linked; missing bytes read as zero.
+fn read_hash(bytes: &[u8], offset: usize) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    if let Some(slice) = bytes.get(offset..offset + 32) {
+        hash.copy_from_slice(slice);
+    }
+    hash
+}
 
 /// Parses and validates the vesting configuration from script arguments.
 /// Validates epoch ordering constraints.
 fn parse_vesting_config(args: &[u8]) -> Result<VestingConfig, Error> {
-    let mut creator_lock_hash = [0u8; 32];
-    let mut beneficiary_lock_hash = [0u8; 32];
-
-    creator_lock_hash
-        .copy_from_slice(&args[CREATOR_LOCK_HASH_OFFSET..CREATOR_LOCK_HASH_OFFSET + 32]);
-    beneficiary_lock_hash
-        .copy_from_slice(&args[BENEFICIARY_LOCK_HASH_OFFSET..BENEFICIARY_LOCK_HASH_OFFSET + 32]);
-
-    let start_epoch = u64::from_le_bytes(
-        args[START_EPOCH_OFFSET..START_EPOCH_OFFSET + 8]
-            .try_into()
-            .unwrap(),
-    );
-    let end_epoch = u64::from_le_bytes(
-        args[END_EPOCH_OFFSET..END_EPOCH_OFFSET + 8]
-            .try_into()
-            .unwrap(),
-    );
-    let cliff_epoch = u64::from_le_bytes(
-        args[CLIFF_EPOCH_OFFSET..CLIFF_EPOCH_OFFSET + 8]
-            .try_into()
-            .unwrap(),
-    );
+    let creator_lock_hash = read_hash(args, CREATOR_LOCK_HASH_OFFSET);
+    let beneficiary_lock_hash = read_hash(args, BENEFICIARY_LOCK_HASH_OFFSET);
+    let start_epoch = read_u64(args, START_EPOCH_OFFSET);
+    let end_epoch = read_u64(args, END_EPOCH_OFFSET);
+    let cliff_epoch = read_u64(args, CLIFF_EPOCH_OFFSET);
 
     // Ensure epochs are in proper order: start <= cliff <= end.
     if start_epoch >= end_epoch || cliff_epoch < start_epoch || cliff_epoch > end_epoch {
         return Err(Error::InvalidEpoch);
     }
 
-    Ok(VestingConfig {
+    let mut vesting_config = VestingConfig {
         creator_lock_hash,
         beneficiary_lock_hash,
         start_epoch,
         end_epoch,
         cliff_epoch,
-    })
-}
+        schedule: Schedule::Linear,
+        granularity: None,
+        schedule_in_blocks: false,
+        since_timing: false,
+        creator_auth_lock: None,
+        beneficiary_auth_lock: None,
+        keeper_tip: 0,
+        min_update_interval: 0,
+        min_header_deps: 0,
+        max_block_advance: None,
+        creator_migration_delay: None,
+        pending_creator_migration: None,
+        guardians: None,
+        pending_beneficiary_recovery: None,
+        performance_oracle: None,
+        end_bonus: 0,
+        bps_reserve: None,
+        termination_acceleration_bps: 0,
+        co_grantors: None,
+        creator_signers: None,
+        co_beneficiaries: None,
+        merkle_beneficiaries: None,
+        accrual_from_cliff: false,
+        claim_unit: None,
+        sweep_lock_hash: None,
+        udt_type_hash: None,
+        xudt: false,
+        udt_unit: None,
+        cheque_lock: None,
+        capacity_per_unit: None,
+    };
+    parse_args_extension(args, &mut vesting_config)?;
 
-/// Parses the vesting state from cell data.
-/// Extracts amounts and block tracking information.
-fn parse_vesting_state(data: &[u8]) -> Result<VestingState, Error> {
-    let total_amount = u64::from_le_bytes(
-        data[TOTAL_AMOUNT_OFFSET..TOTAL_AMOUNT_OFFSET + 8]
-            .try_into()
-            .unwrap(),
-    );
-    let beneficiary_claimed = u64::from_le_bytes(
-        data[BENEFICIARY_CLAIMED_OFFSET..BENEFICIARY_CLAIMED_OFFSET + 8]
-            .try_into()
-            .unwrap(),
-    );
-    let creator_claimed = u64::from_le_bytes(
-        data[CREATOR_CLAIMED_OFFSET..CREATOR_CLAIMED_OFFSET + 8]
-            .try_into()
-            .unwrap(),
-    );
-    let highest_block_seen = u64::from_le_bytes(
-        data[HIGHEST_BLOCK_SEEN_OFFSET..HIGHEST_BLOCK_SEEN_OFFSET + 8]
-            .try_into()
-            .unwrap(),
-    );
-
-    Ok(VestingState {
-        total_amount,
-        beneficiary_claimed,
-        creator_claimed,
-        highest_block_seen,
-    })
+    Ok(vesting_config)
 }
 
+/// Parses the optional args extension that follows the base args.
+/// Rejects unknown versions, unknown or repeated option tags, and truncated records.
+fn parse_args_extension(args: &[u8], vesting_config: &mut VestingConfig) -> Result<(), Error> {
+    // Legacy args carry no extension.
+    if args.len() == ARGS_LEN {
+        return Ok(());
+    }
 
-/// Finds the highest block number seen across all input cells.
-/// Used for preventing temporal attacks with stale headers.
-fn get_highest_block_from_inputs() -> Result<u64, Error> {
-    let current_script = load_script()?;
-    let current_script_hash = current_script.calc_script_hash();
-    
-    let mut highest_block = 0;
-    let mut index = 0;
-    
-    while let Ok(input_cell) = load_cell(index, Source::Input) {
-        if input_cell.lock().calc_script_hash() == current_script_hash {
-            let data = load_cell_data(index, Source::Input).map_err(|_| Error::LoadCellDataFailed)?;
-            if data.len() != DATA_LEN {
-                return Err(Error::InputDataWrongLength);
+    if args.get(ARGS_LEN) != Some(&ARGS_EXTENSION_VERSION) {
+        return Err(Error::InvalidArgs);
+    }
+
+    let mut seen_tags: u64 = 0;
+    let mut offset = ARGS_LEN + 1;
+    while offset < args.len() {
+        let (tag, value_len) = match args.get(offset..offset + 2) {
+            Some(&[tag, value_len]) => (tag, value_len as usize),
+            _ => return Err(Error::InvalidArgs),
+        };
+        let value = args
+            .get(offset + 2..offset + 2 + value_len)
+            .ok_or(Error::InvalidArgs)?;
+
+        // Each option may appear at most once.
+        let tag_bit = 1u64.checked_shl(tag as u32).ok_or(Error::InvalidArgs)?;
+        if seen_tags & tag_bit != 0 {
+            return Err(Error::InvalidArgs);
+        }
+        seen_tags |= tag_bit;
+
+        match tag {
+            OPTION_CREATOR_AUTH_POLICY => vesting_config.creator_auth_lock = parse_auth_policy(value)?,
+            OPTION_BENEFICIARY_AUTH_POLICY => vesting_config.beneficiary_auth_lock = parse_auth_policy(value)?,
+            OPTION_KEEPER_TIP => vesting_config.keeper_tip = parse_u64_option(value)?,
+            OPTION_MIN_UPDATE_INTERVAL => vesting_config.min_update_interval = parse_u64_option(value)?,
+            OPTION_MIN_HEADER_DEPS => vesting_config.min_header_deps = parse_min_header_deps(value)?,
+            OPTION_MAX_BLOCK_ADVANCE => vesting_config.max_block_advance = Some(parse_u64_option(value)?),
+            OPTION_CREATOR_MIGRATION_DELAY => vesting_config.creator_migration_delay = Some(parse_u64_option(value)?),
+            OPTION_PENDING_CREATOR_MIGRATION => {
+                vesting_config.pending_creator_migration = Some(parse_pending_migration(value)?)
             }
-            let state = parse_vesting_state(&data)?;
-            if state.highest_block_seen > highest_block {
-                highest_block = state.highest_block_seen;
+            OPTION_GUARDIANS => vesting_config.guardians = Some(parse_guardian_set(value)?),
+            OPTION_PENDING_BENEFICIARY_RECOVERY => {
+                vesting_config.pending_beneficiary_recovery = Some(parse_pending_migration(value)?)
+            }
+            OPTION_PERFORMANCE_ORACLE => vesting_config.performance_oracle = Some(parse_performance_oracle(value)?),
+            OPTION_END_BONUS => vesting_config.end_bonus = parse_u64_option(value)?,
+            OPTION_BPS_DENOMINATION => vesting_config.bps_reserve = Some(parse_u64_option(value)?),
+            OPTION_TERMINATION_ACCELERATION => {
+                vesting_config.termination_acceleration_bps = parse_termination_acceleration(value)?
+            }
+            OPTION_CO_GRANTORS => {
+                vesting_config.co_grantors = Some(parse_co_grantors(value, &vesting_config.creator_lock_hash)?)
+            }
+            OPTION_CLIFF_ACCRUAL => vesting_config.accrual_from_cliff = parse_cliff_accrual(value)?,
+            OPTION_CLAIM_UNIT => vesting_config.claim_unit = Some(parse_claim_unit(value)?),
+            OPTION_SWEEP_LOCK => vesting_config.sweep_lock_hash = Some(parse_sweep_lock(value)?),
+            OPTION_UDT_TYPE | OPTION_XUDT_TYPE => {
+                // A cell vests a single token, so at most one of the UDT options may be given.
+                if vesting_config.udt_type_hash.is_some() {
+                    return Err(Error::InvalidArgs);
+                }
+                vesting_config.udt_type_hash = Some(parse_udt_type(value)?);
+                vesting_config.xudt = tag == OPTION_XUDT_TYPE;
+            }
+            OPTION_SCHEDULE => {
+                vesting_config.schedule =
+                    parse_schedule(value, vesting_config.start_epoch, vesting_config.end_epoch)?
+            }
+            OPTION_GRANULARITY => vesting_config.granularity = Some(parse_granularity(value)?),
+            OPTION_SCHEDULE_UNIT => vesting_config.schedule_in_blocks = parse_schedule_unit(value)?,
+            OPTION_TIME_SOURCE => vesting_config.since_timing = parse_time_source(value)?,
+            OPTION_CO_BENEFICIARIES => {
+                vesting_config.co_beneficiaries =
+                    Some(parse_co_beneficiaries(value, &vesting_config.beneficiary_lock_hash)?)
+            }
+            OPTION_MERKLE_BENEFICIARIES => {
+                vesting_config.merkle_beneficiaries = Some(parse_merkle_beneficiaries(value)?)
             }
+            OPTION_CREATOR_SIGNERS => vesting_config.creator_signers = Some(parse_creator_signers(value)?),
+            OPTION_UDT_UNIT => vesting_config.udt_unit = Some(parse_udt_unit(value)?),
+            OPTION_CHEQUE_LOCK => vesting_config.cheque_lock = Some(parse_cheque_lock(value)?),
+            OPTION_VESTED_CAPACITY => vesting_config.capacity_per_unit = Some(parse_vested_capacity(value)?),
+            _ => return Err(Error::InvalidArgs),
         }
-        index += 1;
+
+        offset += 2 + value_len;
     }
-    
-    Ok(highest_block)
+
+    // UDT cells count tokens, which neither basis points of capacity nor capacity-paid grantor splits can express.
+    if vesting_config.udt_type_hash.is_some()
+        && (vesting_config.bps_reserve.is_some() || vesting_config.co_grantors.is_some())
+    {
+        return Err(Error::InvalidArgs);
+    }
+
+    // A UDT unit scales token amounts, cheques carry tokens and vested capacity follows them, so none applies without a UDT option.
+    if (vesting_config.udt_unit.is_some() || vesting_config.cheque_lock.is_some() || vesting_config.capacity_per_unit.is_some())
+        && vesting_config.udt_type_hash.is_none()
+    {
+        return Err(Error::InvalidArgs);
+    }
+
+    // A pattern or a co-grantor could act as the creator alone, bypassing the signer threshold.
+    if vesting_config.creator_signers.is_some()
+        && (vesting_config.creator_auth_lock.is_some() || vesting_config.co_grantors.is_some())
+    {
+        return Err(Error::InvalidArgs);
+    }
+
+    // Merkle leaves carry their own schedules and claimants, so options tied to the base schedule or the beneficiary cannot apply.
+    // Only the root of the tree is known, not its unvested remainder, so merkle cells cannot be terminated and creator options have nothing to authorize.
+    if vesting_config.merkle_beneficiaries.is_some()
+        && (vesting_config.co_beneficiaries.is_some()
+            || vesting_config.co_grantors.is_some()
+            || vesting_config.creator_auth_lock.is_some()
+            || vesting_config.creator_signers.is_some()
+            || matches!(vesting_config.schedule, Schedule::Milestones(_))
+            || vesting_config.end_bonus > 0
+            || vesting_config.performance_oracle.is_some()
+            || vesting_config.termination_acceleration_bps > 0
+            || vesting_config.claim_unit.is_some()
+            || vesting_config.beneficiary_auth_lock.is_some()
+            || vesting_config.guardians.is_some()
+            || vesting_config.pending_beneficiary_recovery.is_some()
+            || vesting_config.creator_migration_delay.is_some()
+            || vesting_config.pending_creator_migration.is_some())
+    {
+        return Err(Error::InvalidArgs);
+    }
+
+    // A tipped update pays whoever submits it, so updates must be spaced out or keepers could drain the cell block by block.
+    if vesting_config.keeper_tip > 0 && vesting_config.min_update_interval == 0 {
+        return Err(Error::InvalidArgs);
+    }
+
+    // Milestones already unlock in discrete steps of their own.
+    if vesting_config.granularity.is_some() && matches!(vesting_config.schedule, Schedule::Milestones(_)) {
+        return Err(Error::InvalidArgs);
+    }
+
+    // Since-timed cells see no header blocks, so options measured in blocks or relying on header deps cannot apply.
+    if vesting_config.since_timing
+        && (vesting_config.schedule_in_blocks
+            || vesting_config.min_header_deps > 0
+            || vesting_config.max_block_advance.is_some()
+            || vesting_config.min_update_interval > 0
+            || vesting_config.keeper_tip > 0
+            || vesting_config.creator_migration_delay.is_some()
+            || vesting_config.pending_creator_migration.is_some()
+            || vesting_config.guardians.is_some()
+            || vesting_config.pending_beneficiary_recovery.is_some())
+    {
+        return Err(Error::InvalidArgs);
+    }
+
+    Ok(())
 }
 
-/// Finds the highest block number from all header dependencies.
-/// Used to verify header freshness.
-fn get_highest_block_from_headers() -> Result<u64, Error> {
-    let mut highest_block = 0;
-    let mut index = 0;
-    
-    while let Ok(header) = load_header(index, Source::HeaderDep) {
-        let block_number = header.raw().number().unpack();
-        if block_number > highest_block {
-            highest_block = block_number;
+/// Parses an option value holding a single little-endian u64.
+/// Rejects values that are not exactly 8 bytes long.
+fn parse_u64_option(value: &[u8]) -> Result<u64, Error> {
+    if value.len() != 8 {
+        return Err(Error::InvalidArgs);
+    }
+    Ok(read_u64(value, 0))
+}
+
+/// Parses the co-grantors option value into lock hashes and shares of reclaimed funds.
+/// Co-grantors must be distinct from each other and the creator, with non-zero shares totalling at most 10000 bps.
+fn parse_co_grantors(value: &[u8], creator_lock_hash: &[u8; 32]) -> Result<CoGrantors, Error> {
+    let count = value.len() / CO_GRANTOR_LEN;
+    if !value.len().is_multiple_of(CO_GRANTOR_LEN) || count == 0 || count > MAX_CO_GRANTORS {
+        return Err(Error::InvalidArgs);
+    }
+
+    let mut co_grantors = CoGrantors {
+        lock_hashes: [[0u8; 32]; MAX_CO_GRANTORS],
+        shares_bps: [0u16; MAX_CO_GRANTORS],
+        count,
+    };
+    let mut total_share: u128 = 0;
+    for index in 0..count {
+        let offset = index * CO_GRANTOR_LEN;
+        let lock_hash = read_hash(value, offset + CO_GRANTOR_LOCK_HASH_OFFSET);
+        let share_bps = read_u16(value, offset + CO_GRANTOR_SHARE_OFFSET);
+
+        // A repeated grantor would be owed its share twice.
+        if &lock_hash == creator_lock_hash
+            || co_grantors.lock_hashes.iter().take(index).any(|co_grantor| co_grantor == &lock_hash)
+            || share_bps == 0
+        {
+            return Err(Error::InvalidArgs);
+        }
+        total_share += share_bps as u128;
+
+        if let Some(slot) = co_grantors.lock_hashes.get_mut(index) {
+            *slot = lock_hash;
+        }
+        if let Some(slot) = co_grantors.shares_bps.get_mut(index) {
+            *slot = share_bps;
         }
-        index += 1;
     }
-    
-    Ok(highest_block)
+
+    if total_share > BPS_DENOMINATOR {
+        return Err(Error::InvalidArgs);
+    }
+
+    Ok(co_grantors)
 }
 
-/// Finds the highest epoch number from all header dependencies.
-/// Used for vesting calculations.
-fn get_highest_epoch_from_headers() -> Result<u64, Error> {
-    let mut highest_epoch = 0;
-    let mut index = 0;
-    
-    while let Ok(header) = load_header(index, Source::HeaderDep) {
-        let epoch = header.raw().epoch().unpack();
-        if epoch > highest_epoch {
-            highest_epoch = epoch;
+/// Parses the co-beneficiaries option value into lock hashes and shares of the vested amount.
+/// Co-beneficiaries must be distinct from each other and the beneficiary, with non-zero shares totalling at most 10000 bps.
+fn parse_co_beneficiaries(value: &[u8], beneficiary_lock_hash: &[u8; 32]) -> Result<CoBeneficiaries, Error> {
+    let count = value.len() / CO_BENEFICIARY_LEN;
+    if !value.len().is_multiple_of(CO_BENEFICIARY_LEN) || count == 0 || count > MAX_CO_BENEFICIARIES {
+        return Err(Error::InvalidArgs);
+    }
+
+    let mut co_beneficiaries = CoBeneficiaries {
+        lock_hashes: [[0u8; 32]; MAX_CO_BENEFICIARIES],
+        shares_bps: [0u16; MAX_CO_BENEFICIARIES],
+        count,
+    };
+    let mut total_share: u128 = 0;
+    for index in 0..count {
+        let offset = index * CO_BENEFICIARY_LEN;
+        let lock_hash = read_hash(value, offset + CO_BENEFICIARY_LOCK_HASH_OFFSET);
+        let share_bps = read_u16(value, offset + CO_BENEFICIARY_SHARE_OFFSET);
+
+        // A repeated beneficiary would be owed its share twice.
+        if &lock_hash == beneficiary_lock_hash
+            || co_beneficiaries.lock_hashes.iter().take(index).any(|co_beneficiary| co_beneficiary == &lock_hash)
+            || share_bps == 0
+        {
+            return Err(Error::InvalidArgs);
         }
-        index += 1;
+        total_share += share_bps as u128;
+
+        if let Some(slot) = co_beneficiaries.lock_hashes.get_mut(index) {
+            *slot = lock_hash;
+        }
+        if let Some(slot) = co_beneficiaries.shares_bps.get_mut(index) {
+            *slot = share_bps;
+        }
+    }
+
+    if total_share > BPS_DENOMINATOR {
+        return Err(Error::InvalidArgs);
     }
-    
-    Ok(highest_epoch)
+
+    Ok(co_beneficiaries)
 }
 
-/// Validates that at least one header dependency exists in the transaction.
-/// Required for epoch and block number validation.
-fn validate_headers_exist() -> Result<(), Error> {
-    match load_header(0, Source::HeaderDep) {
-        Ok(_) => Ok(()),
-        Err(_) => Err(Error::NoHeaderDependencies), // No headers found.
+/// Parses the merkle beneficiaries option value into the tree depth.
+/// The depth must lie between one and MAX_MERKLE_DEPTH, which bounds the proof size.
+fn parse_merkle_beneficiaries(value: &[u8]) -> Result<MerkleBeneficiaries, Error> {
+    let depth = value.get(MERKLE_DEPTH_OFFSET).copied().unwrap_or(0) as usize;
+    if value.len() != MERKLE_OPTION_LEN || depth == 0 || depth > MAX_MERKLE_DEPTH {
+        return Err(Error::InvalidArgs);
     }
+
+    Ok(MerkleBeneficiaries { depth })
 }
 
-/// Validates that headers are fresher than input cells.
-/// Prevents stale header attacks by ensuring headers have higher block numbers.
-fn validate_header_freshness(
-    highest_block_from_inputs: u64,
-    highest_block_from_headers: u64,
-) -> Result<(), Error> {
-    if highest_block_from_headers <= highest_block_from_inputs {
-        return Err(Error::StaleHeader);
+/// Parses the cliff accrual option value into whether accrual begins at the cliff.
+/// Retroactive accrual, the default, may also be selected explicitly.
+fn parse_cliff_accrual(value: &[u8]) -> Result<bool, Error> {
+    match value {
+        [CLIFF_ACCRUAL_RETROACTIVE] => Ok(false),
+        [CLIFF_ACCRUAL_FROM_CLIFF] => Ok(true),
+        _ => Err(Error::InvalidArgs),
     }
-    Ok(())
 }
 
-/// Validates that the highest block number update is correct.
-/// Ensures monotonic progression and exact matching with header data.
-fn validate_highest_block_update(
-    input_state: &VestingState,
-    output_state: &VestingState,
-    highest_block_from_headers: u64,
-) -> Result<(), Error> {
-    // Enforce monotonic block number progression.
-    if output_state.highest_block_seen < input_state.highest_block_seen {
-        return Err(Error::BlockNumberDecrease);
+/// Parses the termination acceleration option value, a share of the unvested amount in bps.
+/// The share must leave something to reclaim, otherwise termination could not be recorded.
+fn parse_termination_acceleration(value: &[u8]) -> Result<u16, Error> {
+    if value.len() != 2 {
+        return Err(Error::InvalidArgs);
+    }
+    let acceleration_bps = read_u16(value, 0);
+    if acceleration_bps == 0 || acceleration_bps as u128 >= BPS_DENOMINATOR {
+        return Err(Error::InvalidArgs);
     }
+    Ok(acceleration_bps)
+}
 
-    // Require exact match between output and header block numbers.
-    if output_state.highest_block_seen != highest_block_from_headers {
-        return Err(Error::BlockNumberMismatch);
+/// Parses the claim unit option value.
+/// A zero unit would divide every claim by zero, so it is rejected.
+fn parse_claim_unit(value: &[u8]) -> Result<u64, Error> {
+    let claim_unit = parse_u64_option(value)?;
+    if claim_unit == 0 {
+        return Err(Error::InvalidArgs);
     }
+    Ok(claim_unit)
+}
 
-    Ok(())
+/// Parses the UDT unit option value, the number of tokens each unit of the cell's amounts stands for.
+/// A zero unit would vest no tokens at all, so it is rejected.
+fn parse_udt_unit(value: &[u8]) -> Result<u64, Error> {
+    let udt_unit = parse_u64_option(value)?;
+    if udt_unit == 0 {
+        return Err(Error::InvalidArgs);
+    }
+    Ok(udt_unit)
 }
 
-/// Validates a beneficiary claim operation.
-/// Checks vesting schedule, termination status, and claim amounts.
-fn validate_beneficiary_claim(
-    config: &VestingConfig,
-    input_state: &VestingState,
-    output_state: &VestingState,
-    highest_epoch: u64,
-) -> Result<(), Error> {
-    // Calculate vested amount using current epoch.
-    let vested_amount = calculate_vested_amount(
-        highest_epoch,
-        config.start_epoch,
-        config.end_epoch,
-        config.cliff_epoch,
-        input_state.total_amount,
-        input_state.creator_claimed,
-    );
+/// Parses the vested capacity option value, the shannons that vest alongside each unit of a UDT cell's amounts.
+/// A zero ratio would vest no capacity, so it is rejected.
+fn parse_vested_capacity(value: &[u8]) -> Result<u64, Error> {
+    let capacity_per_unit = parse_u64_option(value)?;
+    if capacity_per_unit == 0 {
+        return Err(Error::InvalidArgs);
+    }
+    Ok(capacity_per_unit)
+}
 
-    // Determine available claim amount.
-    let available_to_claim = vested_amount.saturating_sub(input_state.beneficiary_claimed);
-    let claimed_amount = output_state
-        .beneficiary_claimed
-        .saturating_sub(input_state.beneficiary_claimed);
+/// Parses the cheque lock option value, the code hash and hash type of the cheque lock script.
+/// Rejects values that are not exactly 33 bytes long.
+fn parse_cheque_lock(value: &[u8]) -> Result<ChequeLock, Error> {
+    if value.len() != CHEQUE_OPTION_LEN {
+        return Err(Error::InvalidArgs);
+    }
+    Ok(ChequeLock {
+        code_hash: read_hash(value, 0),
+        hash_type: value.get(CHEQUE_HASH_TYPE_OFFSET).copied().unwrap_or_default(),
+    })
+}
+
+/// Parses the sweep lock option value, the lock hash that receives a settled cell's capacity.
+/// Rejects values that are not exactly 32 bytes long.
+fn parse_sweep_lock(value: &[u8]) -> Result<[u8; 32], Error> {
+    if value.len() != 32 {
+        return Err(Error::InvalidArgs);
+    }
+    Ok(read_hash(value, 0))
+}
+
+/// Parses the UDT or xUDT option value, the type script hash of the token the cell vests.
+/// Rejects values that are not exactly 32 bytes long.
+fn parse_udt_type(value: &[u8]) -> Result<[u8; 32], Error> {
+    if value.len() != 32 {
+        return Err(Error::InvalidArgs);
+    }
+    Ok(read_hash(value, 0))
+}
+
+/// Parses the schedule option value into the base schedule's type and fields.
+/// Milestones must lie within the schedule, with strictly increasing epochs and non-zero shares totalling 10000 bps.
+fn parse_schedule(value: &[u8], start_epoch: u64, end_epoch: u64) -> Result<Schedule, Error> {
+    let fields = value.get(SCHEDULE_FIELDS_OFFSET..).ok_or(Error::InvalidArgs)?;
+    match value.first() {
+        Some(&SCHEDULE_LINEAR) if fields.is_empty() => return Ok(Schedule::Linear),
+        Some(&SCHEDULE_MILESTONES) => {}
+        _ => return Err(Error::InvalidArgs),
+    }
+
+    let count = fields.len() / MILESTONE_LEN;
+    if !fields.len().is_multiple_of(MILESTONE_LEN) || count == 0 || count > MAX_MILESTONES {
+        return Err(Error::InvalidArgs);
+    }
+
+    let mut milestones = Milestones {
+        epochs: [0u64; MAX_MILESTONES],
+        shares_bps: [0u16; MAX_MILESTONES],
+        count,
+    };
+    let mut total_share: u128 = 0;
+    let mut previous_epoch: Option<u64> = None;
+    for index in 0..count {
+        let offset = index * MILESTONE_LEN;
+        let epoch = read_u64(fields, offset + MILESTONE_EPOCH_OFFSET);
+        let share_bps = read_u16(fields, offset + MILESTONE_SHARE_OFFSET);
+
+        // Out-of-order or repeated epochs would make the unlock table ambiguous.
+        if epoch < start_epoch
+            || epoch > end_epoch
+            || previous_epoch.is_some_and(|previous| epoch <= previous)
+            || share_bps == 0
+        {
+            return Err(Error::InvalidArgs);
+        }
+        previous_epoch = Some(epoch);
+        total_share += share_bps as u128;
+
+        if let Some(slot) = milestones.epochs.get_mut(index) {
+            *slot = epoch;
+        }
+        if let Some(slot) = milestones.shares_bps.get_mut(index) {
+            *slot = share_bps;
+        }
+    }
+
+    if total_share != BPS_DENOMINATOR {
+        return Err(Error::InvalidArgs);
+    }
+
+    Ok(Schedule::Milestones(milestones))
+}
+
+/// Parses the schedule unit option value into whether schedule points are block numbers.
+/// Epochs, the default, may also be selected explicitly.
+fn parse_schedule_unit(value: &[u8]) -> Result<bool, Error> {
+    match value {
+        [SCHEDULE_UNIT_EPOCHS] => Ok(false),
+        [SCHEDULE_UNIT_BLOCKS] => Ok(true),
+        _ => Err(Error::InvalidArgs),
+    }
+}
+
+/// Parses the time source option value into whether the vesting input's since supplies the current epoch.
+/// Header deps, the default, may also be selected explicitly.
+fn parse_time_source(value: &[u8]) -> Result<bool, Error> {
+    match value {
+        [TIME_SOURCE_HEADER_DEPS] => Ok(false),
+        [TIME_SOURCE_INPUT_SINCE] => Ok(true),
+        _ => Err(Error::InvalidArgs),
+    }
+}
+
+/// Parses the granularity option value, the number of equal steps linear schedules vest in.
+/// Zero steps could never vest anything, so it is rejected.
+fn parse_granularity(value: &[u8]) -> Result<u64, Error> {
+    let granularity = parse_u64_option(value)?;
+    if granularity == 0 {
+        return Err(Error::InvalidArgs);
+    }
+    Ok(granularity)
+}
+
+/// Parses the minimum header deps option value.
+/// The count must lie between one and MAX_HEADER_DEPS, otherwise no transaction could satisfy it.
+fn parse_min_header_deps(value: &[u8]) -> Result<usize, Error> {
+    let min_header_deps = parse_u64_option(value)?;
+    if min_header_deps == 0 || min_header_deps > MAX_HEADER_DEPS as u64 {
+        return Err(Error::InvalidArgs);
+    }
+    Ok(min_header_deps as usize)
+}
+
+/// Parses a pending migration option value into the new lock hash and its effective block.
+/// Rejects values that are not exactly MIGRATION_LEN bytes long.
+fn parse_pending_migration(value: &[u8]) -> Result<PendingMigration, Error> {
+    if value.len() != MIGRATION_LEN {
+        return Err(Error::InvalidArgs);
+    }
+    Ok(PendingMigration {
+        new_lock_hash: read_hash(value, MIGRATION_NEW_LOCK_HASH_OFFSET),
+        effective_block: read_u64(value, MIGRATION_EFFECTIVE_BLOCK_OFFSET),
+    })
+}
+
+/// Parses a guardians option value into the guardian set.
+/// Requires one to MAX_GUARDIANS distinct lock hashes and a threshold no larger than the guardian count.
+fn parse_guardian_set(value: &[u8]) -> Result<GuardianSet, Error> {
+    let hashes_len = value.len().checked_sub(GUARDIAN_HASHES_OFFSET).ok_or(Error::InvalidArgs)?;
+    let count = hashes_len / 32;
+    if !hashes_len.is_multiple_of(32) || count == 0 || count > MAX_GUARDIANS {
+        return Err(Error::InvalidArgs);
+    }
+
+    let threshold = value.get(GUARDIAN_THRESHOLD_OFFSET).copied().unwrap_or(0) as usize;
+    if threshold == 0 || threshold > count {
+        return Err(Error::InvalidArgs);
+    }
+
+    let mut lock_hashes = [[0u8; 32]; MAX_GUARDIANS];
+    for index in 0..count {
+        let lock_hash = read_hash(value, GUARDIAN_HASHES_OFFSET + index * 32);
+
+        // A repeated guardian would count twice toward the threshold.
+        if lock_hashes.iter().take(index).any(|guardian| guardian == &lock_hash) {
+            return Err(Error::InvalidArgs);
+        }
+        if let Some(slot) = lock_hashes.get_mut(index) {
+            *slot = lock_hash;
+        }
+    }
+
+    Ok(GuardianSet {
+        threshold,
+        recovery_delay: read_u64(value, GUARDIAN_DELAY_OFFSET),
+        lock_hashes,
+        count,
+    })
+}
+
+/// Parses a creator signers option value into the m-of-n signer set.
+/// Requires one to MAX_CREATOR_SIGNERS distinct lock hashes and a threshold no larger than the signer count.
+fn parse_creator_signers(value: &[u8]) -> Result<CreatorSigners, Error> {
+    let hashes_len = value.len().checked_sub(CREATOR_SIGNER_HASHES_OFFSET).ok_or(Error::InvalidArgs)?;
+    let count = hashes_len / 32;
+    if !hashes_len.is_multiple_of(32) || count == 0 || count > MAX_CREATOR_SIGNERS {
+        return Err(Error::InvalidArgs);
+    }
+
+    let threshold = value.get(CREATOR_SIGNER_THRESHOLD_OFFSET).copied().unwrap_or(0) as usize;
+    if threshold == 0 || threshold > count {
+        return Err(Error::InvalidArgs);
+    }
+
+    let mut lock_hashes = [[0u8; 32]; MAX_CREATOR_SIGNERS];
+    for index in 0..count {
+        let lock_hash = read_hash(value, CREATOR_SIGNER_HASHES_OFFSET + index * 32);
+
+        // A repeated signer would make the threshold look reachable with fewer distinct signers than listed.
+        if lock_hashes.iter().take(index).any(|signer| signer == &lock_hash) {
+            return Err(Error::InvalidArgs);
+        }
+        if let Some(slot) = lock_hashes.get_mut(index) {
+            *slot = lock_hash;
+        }
+    }
+
+    Ok(CreatorSigners {
+        threshold,
+        lock_hashes,
+        count,
+    })
+}
+
+/// Parses a performance oracle option value.
+/// Requires min <= current <= max so the multiplier always starts within its bounds.
+fn parse_performance_oracle(value: &[u8]) -> Result<PerformanceOracle, Error> {
+    if value.len() != ORACLE_LEN {
+        return Err(Error::InvalidArgs);
+    }
+
+    let performance_oracle = PerformanceOracle {
+        lock_hash: read_hash(value, ORACLE_LOCK_HASH_OFFSET),
+        min_bps: read_u16(value, ORACLE_MIN_BPS_OFFSET),
+        max_bps: read_u16(value, ORACLE_MAX_BPS_OFFSET),
+        current_bps: read_u16(value, ORACLE_CURRENT_BPS_OFFSET),
+    };
+    if performance_oracle.current_bps < performance_oracle.min_bps
+        || performance_oracle.current_bps > performance_oracle.max_bps
+    {
+        return Err(Error::InvalidArgs);
+    }
+
+    Ok(performance_oracle)
+}
+
+/// Parses an auth policy option value into the lock pattern it adds, if any.
+/// The exact hash policy adds nothing, the code hash policy matches any args, and the
+/// args prefix policy also requires the args to start with the given prefix.
+fn parse_auth_policy(value: &[u8]) -> Result<Option<LockPattern>, Error> {
+    match value.first() {
+        Some(&AUTH_POLICY_EXACT_HASH) if value.len() == 1 => Ok(None),
+        Some(&AUTH_POLICY_CODE_HASH) if value.len() == PATTERN_ARGS_PREFIX_OFFSET => parse_lock_pattern(value).map(Some),
+        Some(&AUTH_POLICY_ARGS_PREFIX) => parse_lock_pattern(value).map(Some),
+        _ => Err(Error::InvalidArgs),
+    }
+}
+
+/// Parses the lock pattern part of an auth policy option value.
+/// The value holds a code hash, a hash type and an args prefix of at most MAX_ARGS_PREFIX_LEN bytes.
+fn parse_lock_pattern(value: &[u8]) -> Result<LockPattern, Error> {
+    let args_prefix_len = value
+        .len()
+        .checked_sub(PATTERN_ARGS_PREFIX_OFFSET)
+        .ok_or(Error::InvalidArgs)?;
+    if args_prefix_len > MAX_ARGS_PREFIX_LEN {
+        return Err(Error::InvalidArgs);
+    }
+
+    let mut args_prefix = [0u8; MAX_ARGS_PREFIX_LEN];
+    for (target, source) in args_prefix.iter_mut().zip(value.iter().skip(PATTERN_ARGS_PREFIX_OFFSET)) {
+        *target = *source;
+    }
+
+    Ok(LockPattern {
+        code_hash: read_hash(value, PATTERN_CODE_HASH_OFFSET),
+        hash_type: value.get(PATTERN_HASH_TYPE_OFFSET).copied().unwrap_or_default(),
+        args_prefix,
+        args_prefix_len,
+    })
+}
+
+/// Checks whether a lock script matches an auth lock pattern.
+/// The code hash and hash type must be equal and the args must start with the prefix.
+fn lock_matches_pattern(lock: &Script, pattern: &LockPattern) -> bool {
+    let code_hash: [u8; 32] = lock.code_hash().unpack();
+    let hash_type: u8 = lock.hash_type().into();
+    let args = lock.args().raw_data();
+
+    code_hash == pattern.code_hash
+        && hash_type == pattern.hash_type
+        && args.get(..pattern.args_prefix_len) == pattern.args_prefix.get(..pattern.args_prefix_len)
+}
+
+/// Parses the vesting state, tranche records and extension area from cell data.
+/// Validates the tranche count, tranche schedules, the extension version and that tranches fit within the aggregate amounts.
+fn parse_vesting_state(data: &StateData) -> Result<VestingState, Error> {
+    let bytes = &data.bytes;
+    let (tranche_count, extension_len) = parse_data_layout(data)?;
+    let mut state = VestingState {
+        total_amount: read_u64(bytes, TOTAL_AMOUNT_OFFSET),
+        beneficiary_claimed: read_u64(bytes, BENEFICIARY_CLAIMED_OFFSET),
+        creator_claimed: read_u64(bytes, CREATOR_CLAIMED_OFFSET),
+        highest_block_seen: read_u64(bytes, HIGHEST_BLOCK_SEEN_OFFSET),
+        tranches: [Tranche::default(); MAX_TRANCHES],
+        tranche_count,
+        extension: [0u8; MAX_DATA_EXTENSION_LEN],
+        extension_len,
+    };
+
+    // Whatever follows the counted tranche records is the extension area, led by its version.
+    if state.extension_len > 0 {
+        let extension_offset = TRANCHES_OFFSET + state.tranche_count * TRANCHE_LEN;
+        let extension = bytes
+            .get(extension_offset..extension_offset + state.extension_len)
+            .ok_or(Error::InvalidDataExtension)?;
+        if !matches!(
+            extension.get(DATA_EXTENSION_VERSION_OFFSET),
+            Some(&DATA_EXTENSION_VERSION) | Some(&DATA_EXTENSION_CLAIMS_VERSION) | Some(&DATA_EXTENSION_MERKLE_VERSION)
+        ) {
+            return Err(Error::InvalidDataExtension);
+        }
+        if let Some(target) = state.extension.get_mut(..state.extension_len) {
+            target.copy_from_slice(extension);
+        }
+    }
+
+    let mut tranche_total: u64 = 0;
+    let mut tranche_claimed: u64 = 0;
+    for (index, tranche) in state.tranches.iter_mut().take(state.tranche_count).enumerate() {
+        let offset = TRANCHES_OFFSET + index * TRANCHE_LEN;
+        *tranche = Tranche {
+            total_amount: read_u64(bytes, offset + TRANCHE_TOTAL_AMOUNT_OFFSET),
+            claimed: read_u64(bytes, offset + TRANCHE_CLAIMED_OFFSET),
+            start_epoch: read_u64(bytes, offset + TRANCHE_START_EPOCH_OFFSET),
+            end_epoch: read_u64(bytes, offset + TRANCHE_END_EPOCH_OFFSET),
+            cliff_epoch: read_u64(bytes, offset + TRANCHE_CLIFF_EPOCH_OFFSET),
+        };
+
+        // Each tranche follows the same ordering rules as the base schedule.
+        if tranche.start_epoch >= tranche.end_epoch
+            || tranche.cliff_epoch < tranche.start_epoch
+            || tranche.cliff_epoch > tranche.end_epoch
+            || tranche.claimed > tranche.total_amount
+        {
+            return Err(Error::InvalidTrancheData);
+        }
+
+        tranche_total = tranche_total
+            .checked_add(tranche.total_amount)
+            .ok_or(Error::InvalidTrancheData)?;
+        tranche_claimed = tranche_claimed
+            .checked_add(tranche.claimed)
+            .ok_or(Error::InvalidTrancheData)?;
+    }
+
+    // Tranches are part of the aggregate, never in addition to it.
+    if tranche_total > state.total_amount || tranche_claimed > state.beneficiary_claimed {
+        return Err(Error::InvalidTrancheData);
+    }
+
+    Ok(state)
+}
+
+/// Splits the data after the cell data into tranche records and the extension area.
+/// Data longer than DATA_LEN starts with the tranche count, and the extension area is whatever follows the counted records.
+fn parse_data_layout(data: &StateData) -> Result<(usize, usize), Error> {
+    if data.len == DATA_LEN {
+        return Ok((0, 0));
+    }
+
+    let tranche_count = data.bytes.get(TRANCHE_COUNT_OFFSET).copied().map_or(0, usize::from);
+    let records_end = TRANCHES_OFFSET + tranche_count * TRANCHE_LEN;
+    if tranche_count > MAX_TRANCHES || records_end > data.len {
+        return Err(Error::InvalidTrancheData);
+    }
+
+    // A count byte must introduce something, so each state has a single encoding.
+    let extension_len = data.len - records_end;
+    if tranche_count == 0 && extension_len == 0 {
+        return Err(Error::InvalidTrancheData);
+    }
+    if extension_len > MAX_DATA_EXTENSION_LEN {
+        return Err(Error::InvalidDataExtension);
+    }
+
+    Ok((tranche_count, extension_len))
+}
+
+/// Sums the total amounts of all tranches in the state.
+/// Parsing guarantees the sum does not overflow.
+fn tranche_total_amount(state: &VestingState) -> u64 {
+    state
+        .tranches
+        .iter()
+        .take(state.tranche_count)
+        .fold(0u64, |sum, tranche| sum.saturating_add(tranche.total_amount))
+}
+
+/// Sums the claimed counters of all tranches in the state.
+/// Parsing guarantees the sum does not overflow.
+fn tranche_claimed_amount(state: &VestingState) -> u64 {
+    state
+        .tranches
+        .iter()
+        .take(state.tranche_count)
+        .fold(0u64, |sum, tranche| sum.saturating_add(tranche.claimed))
+}
+
+/// Returns the epoch linear accrual is measured from for a schedule.
+/// Retroactive accrual starts at the start epoch; otherwise nothing accrues before the cliff.
+fn accrual_start_epoch(vesting_config: &VestingConfig, start_epoch: u64, cliff_epoch: u64) -> u64 {
+    if vesting_config.accrual_from_cliff {
+        cliff_epoch
+    } else {
+        start_epoch
+    }
+}
+
+/// Calculates the vested amount of a single tranche at the given epoch.
+/// Tranches vest linearly with their own cliff, independent of termination and the base schedule type.
+fn calculate_tranche_vested(vesting_config: &VestingConfig, tranche: &Tranche, current_epoch: u64) -> u64 {
+    let params = ScheduleParams {
+        schedule: &Schedule::Linear,
+        granularity: vesting_config.granularity,
+        start_epoch: accrual_start_epoch(vesting_config, tranche.start_epoch, tranche.cliff_epoch),
+        end_epoch: tranche.end_epoch,
+        cliff_epoch: tranche.cliff_epoch,
+    };
+    calculate_vested_amount(&params, current_epoch, tranche.total_amount, 0)
+}
+
+/// Scales the vested amount of one schedule by the performance multiplier, if configured.
+/// The result never exceeds the schedule's total, so a bonus only brings full vesting forward.
+fn apply_performance_multiplier(vesting_config: &VestingConfig, vested: u64, total_amount: u64) -> u64 {
+    let performance_oracle = match vesting_config.performance_oracle {
+        Some(performance_oracle) => performance_oracle,
+        None => return vested,
+    };
+
+    let scaled = vested as u128 * performance_oracle.current_bps as u128 / BPS_DENOMINATOR;
+    scaled.min(total_amount as u128) as u64
+}
+
+/// Calculates the vested amount of the base schedule, including any end-of-schedule bonus.
+/// The base schedule covers whatever part of the total neither the tranches nor the bonus account for.
+fn calculate_base_vested(vesting_config: &VestingConfig, state: &VestingState, current_epoch: u64) -> u64 {
+    let remaining = state.total_amount.saturating_sub(tranche_total_amount(state));
+    let bonus = vesting_config.end_bonus.min(remaining);
+    let base_total = remaining.saturating_sub(bonus);
+
+    let params = ScheduleParams {
+        schedule: &vesting_config.schedule,
+        granularity: vesting_config.granularity,
+        start_epoch: accrual_start_epoch(vesting_config, vesting_config.start_epoch, vesting_config.cliff_epoch),
+        end_epoch: vesting_config.end_epoch,
+        cliff_epoch: vesting_config.cliff_epoch,
+    };
+    let base_vested = calculate_vested_amount(&params, current_epoch, base_total, 0);
+    let base_vested = apply_performance_multiplier(vesting_config, base_vested, base_total);
+
+    // The bonus unlocks all at once when the schedule completes.
+    if current_epoch >= schedule_point(vesting_config.end_epoch) {
+        base_vested.saturating_add(bonus)
+    } else {
+        base_vested
+    }
+}
+
+/// Calculates the aggregate vested amount of the base schedule and all tranches.
+/// After termination everything not claimed by the creator is vested.
+fn calculate_total_vested(vesting_config: &VestingConfig, state: &VestingState, current_epoch: u64) -> u64 {
+    if state.creator_claimed > 0 {
+        return state.total_amount.saturating_sub(state.creator_claimed);
+    }
+
+    let base_vested = calculate_base_vested(vesting_config, state, current_epoch);
+
+    state
+        .tranches
+        .iter()
+        .take(state.tranche_count)
+        .fold(base_vested, |sum, tranche| {
+            let tranche_vested = calculate_tranche_vested(vesting_config, tranche, current_epoch);
+            sum.saturating_add(apply_performance_multiplier(vesting_config, tranche_vested, tranche.total_amount))
+        })
+}
+
+/// Calculates the amount vested once a termination takes effect at the given epoch.
+/// The configured share of the unvested amount vests immediately, rounded down.
+fn calculate_vested_at_termination(vesting_config: &VestingConfig, state: &VestingState, current_epoch: u64) -> u64 {
+    let vested = calculate_total_vested(vesting_config, state, current_epoch);
+    let unvested = state.total_amount.saturating_sub(vested);
+    let accelerated = unvested as u128 * vesting_config.termination_acceleration_bps as u128 / BPS_DENOMINATOR;
+    vested.saturating_add(accelerated as u64)
+}
+
+/// Checks that two states carry identical tranche records.
+/// Used by operations that must not touch tranche schedules or claimed counters.
+fn tranches_unchanged(input_state: &VestingState, output_state: &VestingState) -> bool {
+    input_state.tranche_count == output_state.tranche_count
+        && input_state
+            .tranches
+            .iter()
+            .zip(output_state.tranches.iter())
+            .take(input_state.tranche_count)
+            .all(|(input, output)| input == output)
+}
+
+/// Validates the per-tranche side of a beneficiary claim.
+/// Schedules are immutable and each part, including the base schedule, only claims what it has vested.
+fn validate_tranche_claims(
+    vesting_config: &VestingConfig,
+    input_state: &VestingState,
+    output_state: &VestingState,
+    highest_epoch: u64,
+) -> Result<(), Error> {
+    if input_state.tranche_count != output_state.tranche_count {
+        return Err(Error::TrancheChanged);
+    }
+
+    // After termination the aggregate check alone bounds the claim.
+    let terminated = input_state.creator_claimed > 0;
+
+    for (input, output) in input_state
+        .tranches
+        .iter()
+        .zip(output_state.tranches.iter())
+        .take(input_state.tranche_count)
+    {
+        if output.total_amount != input.total_amount
+            || output.start_epoch != input.start_epoch
+            || output.end_epoch != input.end_epoch
+            || output.cliff_epoch != input.cliff_epoch
+        {
+            return Err(Error::TrancheChanged);
+        }
+
+        let tranche_vested = apply_performance_multiplier(
+            vesting_config,
+            calculate_tranche_vested(vesting_config, input, highest_epoch),
+            input.total_amount,
+        );
+        if output.claimed < input.claimed || (!terminated && output.claimed > tranche_vested) {
+            return Err(Error::InvalidTrancheClaim);
+        }
+    }
+
+    // The base schedule's claimed amount is whatever the tranches do not account for.
+    let input_base_claimed = input_state
+        .beneficiary_claimed
+        .saturating_sub(tranche_claimed_amount(input_state));
+    let output_base_claimed = output_state
+        .beneficiary_claimed
+        .checked_sub(tranche_claimed_amount(output_state))
+        .ok_or(Error::InvalidTrancheClaim)?;
+
+    if output_base_claimed < input_base_claimed {
+        return Err(Error::InvalidTrancheClaim);
+    }
+
+    if !terminated {
+        let base_vested = calculate_base_vested(vesting_config, input_state, highest_epoch);
+        if output_base_claimed > base_vested {
+            return Err(Error::InvalidTrancheClaim);
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a beneficiary's claimed amount, where the co-beneficiary count stands for the primary beneficiary.
+/// The primary beneficiary has claimed whatever the aggregate holds beyond the co-beneficiaries' counters.
+fn beneficiary_claimed(co_beneficiaries: &CoBeneficiaries, state: &VestingState, index: usize) -> u64 {
+    let co_beneficiary_claimed = |index: usize| {
+        read_u64(&state.extension, CO_BENEFICIARY_CLAIMED_OFFSET + index * CO_BENEFICIARY_CLAIMED_LEN)
+    };
+    if index < co_beneficiaries.count {
+        return co_beneficiary_claimed(index);
+    }
+    (0..co_beneficiaries.count).fold(state.beneficiary_claimed, |claimed, index| {
+        claimed.saturating_sub(co_beneficiary_claimed(index))
+    })
+}
+
+/// Calculates a beneficiary's share of the vested amount, where the co-beneficiary count stands for the primary beneficiary.
+/// Co-beneficiary shares are rounded down and the primary beneficiary vests whatever they leave.
+fn beneficiary_vested_share(co_beneficiaries: &CoBeneficiaries, index: usize, vested_amount: u64) -> u64 {
+    let co_beneficiary_share = |share_bps: &u16| (vested_amount as u128 * *share_bps as u128 / BPS_DENOMINATOR) as u64;
+    if index < co_beneficiaries.count {
+        return co_beneficiaries.shares_bps.get(index).map(co_beneficiary_share).unwrap_or(0);
+    }
+    co_beneficiaries
+        .shares_bps
+        .iter()
+        .take(co_beneficiaries.count)
+        .fold(vested_amount, |share, share_bps| share.saturating_sub(co_beneficiary_share(share_bps)))
+}
+
+/// Returns a beneficiary's lock hash, where the co-beneficiary count stands for the primary beneficiary.
+/// Claims of a shared cell are delivered to the lock of the beneficiary that made them.
+fn beneficiary_lock_hash(vesting_config: &VestingConfig, co_beneficiaries: &CoBeneficiaries, index: usize) -> [u8; 32] {
+    co_beneficiaries
+        .lock_hashes
+        .get(index)
+        .filter(|_| index < co_beneficiaries.count)
+        .copied()
+        .unwrap_or(vesting_config.beneficiary_lock_hash)
+}
+
+/// Validates that a cell's extension area holds exactly one claimed counter per co-beneficiary.
+/// Cells without co-beneficiaries may not use the claims version, and the counters never exceed the aggregate.
+fn validate_co_beneficiary_extension(vesting_config: &VestingConfig, state: &VestingState) -> Result<(), Error> {
+    let version = state
+        .extension
+        .get(DATA_EXTENSION_VERSION_OFFSET)
+        .filter(|_| state.extension_len > 0);
+
+    let co_beneficiaries = match &vesting_config.co_beneficiaries {
+        Some(co_beneficiaries) => co_beneficiaries,
+        None if version == Some(&DATA_EXTENSION_CLAIMS_VERSION) => return Err(Error::InvalidDataExtension),
+        None => return Ok(()),
+    };
+
+    if version != Some(&DATA_EXTENSION_CLAIMS_VERSION)
+        || state.extension_len != CO_BENEFICIARY_CLAIMED_OFFSET + co_beneficiaries.count * CO_BENEFICIARY_CLAIMED_LEN
+    {
+        return Err(Error::InvalidDataExtension);
+    }
+
+    // The counters are part of the aggregate, never in addition to it.
+    let co_beneficiary_claimed = (0..co_beneficiaries.count).try_fold(0u64, |claimed, index| {
+        claimed.checked_add(beneficiary_claimed(co_beneficiaries, state, index))
+    });
+    if co_beneficiary_claimed.is_none_or(|claimed| claimed > state.beneficiary_claimed) {
+        return Err(Error::InvalidDataExtension);
+    }
+
+    Ok(())
+}
+
+/// Validates that a merkle cell's extension area holds its current root and that other cells do not use the merkle version.
+/// Merkle leaves carry their own schedules, so merkle cells have no tranche records.
+fn validate_merkle_extension(vesting_config: &VestingConfig, state: &VestingState) -> Result<(), Error> {
+    let version = state
+        .extension
+        .get(DATA_EXTENSION_VERSION_OFFSET)
+        .filter(|_| state.extension_len > 0);
+
+    match &vesting_config.merkle_beneficiaries {
+        Some(_) => {}
+        None if version == Some(&DATA_EXTENSION_MERKLE_VERSION) => return Err(Error::InvalidDataExtension),
+        None => return Ok(()),
+    }
+
+    if state.tranche_count > 0 {
+        return Err(Error::InvalidTrancheData);
+    }
+    if version != Some(&DATA_EXTENSION_MERKLE_VERSION) || state.extension_len != MERKLE_EXTENSION_LEN {
+        return Err(Error::InvalidDataExtension);
+    }
+
+    Ok(())
+}
+
+/// Validates that no beneficiary's claimed amount decreases and that at most one beneficiary claims.
+/// Returns the claimant, where the co-beneficiary count stands for the primary beneficiary.
+fn validate_co_beneficiary_counters(
+    co_beneficiaries: &CoBeneficiaries,
+    input_state: &VestingState,
+    output_state: &VestingState,
+) -> Result<Option<usize>, Error> {
+    let mut claimant = None;
+    for index in 0..=co_beneficiaries.count {
+        let input_claimed = beneficiary_claimed(co_beneficiaries, input_state, index);
+        let output_claimed = beneficiary_claimed(co_beneficiaries, output_state, index);
+        if output_claimed < input_claimed {
+            return Err(Error::InvalidBeneficiaryClaims);
+        }
+
+        // Each beneficiary claims for itself, so a second claimant would claim without its own authorization.
+        if output_claimed > input_claimed {
+            if claimant.is_some() {
+                return Err(Error::InvalidBeneficiaryClaims);
+            }
+            claimant = Some(index);
+        }
+    }
+
+    Ok(claimant)
+}
+
+/// Validates that the claimant of a shared cell authorized the transaction itself.
+/// Beneficiaries cannot claim each other's shares, even though any of them may update the cell.
+fn validate_claimant_authorized(roles: AuthorizedRoles, co_beneficiaries: &CoBeneficiaries, claimant: usize) -> Result<(), Error> {
+    let authorized = if claimant < co_beneficiaries.count {
+        roles.co_beneficiaries.get(claimant).copied().unwrap_or(false)
+    } else {
+        roles.beneficiary
+    };
+    if !authorized {
+        return Err(Error::Unauthorized);
+    }
+
+    Ok(())
+}
+
+/// Validates that the claimant has claimed no more than its share of the vested amount.
+/// The aggregate claim is bounded separately, so the shares of the other beneficiaries stay available to them.
+fn validate_beneficiary_share(
+    co_beneficiaries: &CoBeneficiaries,
+    output_state: &VestingState,
+    claimant: usize,
+    vested_amount: u64,
+) -> Result<(), Error> {
+    if beneficiary_claimed(co_beneficiaries, output_state, claimant)
+        > beneficiary_vested_share(co_beneficiaries, claimant, vested_amount)
+    {
+        return Err(Error::InsufficientVestedShare);
+    }
+
+    Ok(())
+}
+
+/// Validates the continuation of a claim on a cell shared with co-beneficiaries.
+/// The cell continues for the other beneficiaries until a claim settles it, which must consume it.
+fn validate_shared_claim_output(has_output: bool, output_state: &VestingState) -> Result<(), Error> {
+    let settled = is_settled(output_state);
+    if has_output && settled {
+        return Err(Error::BeneficiaryFullClaimHasOutput);
+    }
+    if !has_output && !settled {
+        return Err(Error::BeneficiaryPartialClaimMissingOutput);
+    }
+
+    Ok(())
+}
+
+/// Finds the highest block number seen across all input cells.
+/// Used for preventing temporal attacks with stale headers.
+fn get_highest_block_from_inputs(script_hash: &[u8; 32], vesting_config: &VestingConfig) -> Result<u64, Error> {
+    let mut highest_block = 0;
+
+    for (index, lock_hash) in QueryIter::new(load_cell_lock_hash, Source::Input).enumerate() {
+        if &lock_hash == script_hash {
+            let data_offset = state_data_offset(vesting_config, index, Source::Input)?;
+            let data = load_state_data(index, Source::Input, data_offset, Error::InputDataWrongLength)?;
+            let state = parse_vesting_state(&data)?;
+            if state.highest_block_seen > highest_block {
+                highest_block = state.highest_block_seen;
+            }
+        }
+    }
+
+    Ok(highest_block)
+}
+
+/// Finds the highest block number plus the vesting epoch point and block across all header dependencies in one pass.
+/// Vesting uses the highest epoch point and block, or the lowest ones when a minimum of distinct headers is required.
+fn get_highest_block_and_epoch_from_headers(min_header_deps: usize) -> Result<(u64, u64, u64), Error> {
+    let mut highest_block = 0;
+    let mut lowest_block = u64::MAX;
+    let mut highest_epoch = 0;
+    let mut lowest_epoch = u64::MAX;
+    let mut header_count = 0;
+    let mut distinct_blocks = [0u64; MAX_HEADER_DEPS];
+    let mut distinct_count = 0;
+
+    // Reject oversized header dep lists up front without loading the extra headers.
+    match syscalls::load_header(&mut [], 0, MAX_HEADER_DEPS, Source::HeaderDep) {
+        Err(SysError::IndexOutOfBound) => {}
+        _ => return Err(Error::TooManyHeaderDependencies),
+    }
+
+    // Outputs record the highest block, while vesting uses the epoch or, for block schedules, the block number.
+    for header in QueryIter::new(load_header, Source::HeaderDep) {
+        let raw = header.raw();
+        let block_number: u64 = raw.number().unpack();
+        let epoch = epoch_schedule_point(raw.epoch().unpack());
+        if block_number > highest_block {
+            highest_block = block_number;
+        }
+        if block_number < lowest_block {
+            lowest_block = block_number;
+        }
+        if epoch > highest_epoch {
+            highest_epoch = epoch;
+        }
+        if epoch < lowest_epoch {
+            lowest_epoch = epoch;
+        }
+
+        // Headers only count as independent when they come from different blocks.
+        if !distinct_blocks[..distinct_count].contains(&block_number) {
+            if let Some(slot) = distinct_blocks.get_mut(distinct_count) {
+                *slot = block_number;
+                distinct_count += 1;
+            }
+        }
+        header_count += 1;
+    }
+
+    // Headers are required for epoch and block number validation.
+    if header_count == 0 {
+        return Err(Error::NoHeaderDependencies);
+    }
+
+    // Legacy cells trust the most recent header for vesting math.
+    if min_header_deps == 0 {
+        return Ok((highest_block, highest_epoch, highest_block));
+    }
+
+    // A single early or manipulated header can only lower the vested amount.
+    if distinct_count < min_header_deps {
+        return Err(Error::InsufficientHeaderDependencies);
+    }
+
+    Ok((highest_block, lowest_epoch, lowest_block))
+}
+
+/// Converts a packed header epoch into a schedule point, including the fraction of the epoch in progress.
+/// Fractions with an index beyond the length cannot come from a real chain, so they count as the start of the epoch.
+fn epoch_schedule_point(packed_epoch: u64) -> u64 {
+    let number = packed_epoch & EPOCH_NUMBER_MASK;
+    let index = (packed_epoch >> EPOCH_INDEX_SHIFT) & EPOCH_FRACTION_MASK;
+    let length = (packed_epoch >> EPOCH_LENGTH_SHIFT) & EPOCH_FRACTION_MASK;
+
+    let fraction = if index < length { index * SCHEDULE_POINT_SCALE / length } else { 0 };
+    number * SCHEDULE_POINT_SCALE + fraction
+}
+
+/// Converts a whole epoch or block number from the args or cell data into a schedule point.
+/// Saturates for values no real chain reaches, which then all lie at the end of the timeline.
+fn schedule_point(whole: u64) -> u64 {
+    whole.saturating_mul(SCHEDULE_POINT_SCALE)
+}
+
+/// Loads the schedule point of the vesting input's since, which must be an absolute epoch.
+/// The chain commits the transaction no earlier than that epoch, so vesting measured there is never ahead of time.
+fn load_since_point() -> Result<u64, Error> {
+    let since = load_input_since(0, Source::GroupInput)?;
+    if since >> SINCE_FLAGS_SHIFT != SINCE_ABSOLUTE_EPOCH_FLAGS {
+        return Err(Error::InvalidSince);
+    }
+    Ok(epoch_schedule_point(since & SINCE_VALUE_MASK))
+}
+
+/// Validates that the since does not go back before the point recorded by the previous transition.
+/// Since-timed cells record that point in highest_block_seen, and anonymous updates move it forward like a header refresh.
+fn validate_since_freshness(recorded_point: u64, since_point: u64) -> Result<(), Error> {
+    if since_point < recorded_point {
+        return Err(Error::StaleSince);
+    }
+    Ok(())
+}
+
+/// Validates that headers are fresher than input cells.
+/// Prevents stale header attacks by ensuring headers have higher block numbers.
+fn validate_header_freshness(
+    highest_block_from_inputs: u64,
+    highest_block_from_headers: u64,
+) -> Result<(), Error> {
+    if highest_block_from_headers <= highest_block_from_inputs {
+        return Err(Error::StaleHeader);
+    }
+    Ok(())
+}
+
+/// Validates that the headers do not move the cell further ahead than the configured bound.
+/// Rejects absurd header values instead of accepting any jump that happens to be fresh.
+fn validate_block_advance(
+    highest_block_from_inputs: u64,
+    highest_block_from_headers: u64,
+    max_block_advance: Option<u64>,
+) -> Result<(), Error> {
+    if let Some(max_block_advance) = max_block_advance {
+        if highest_block_from_headers.saturating_sub(highest_block_from_inputs) > max_block_advance {
+            return Err(Error::BlockAdvanceTooLarge);
+        }
+    }
+    Ok(())
+}
+
+/// Validates that the highest block number update is correct.
+/// Ensures monotonic progression and exact matching with header data.
+fn validate_highest_block_update(
+    input_state: &VestingState,
+    output_state: &VestingState,
+    highest_block_from_headers: u64,
+) -> Result<(), Error> {
+    // Enforce monotonic block number progression.
+    if output_state.highest_block_seen < input_state.highest_block_seen {
+        return Err(Error::BlockNumberDecrease);
+    }
+
+    // Require exact match between output and header block numbers.
+    if output_state.highest_block_seen != highest_block_from_headers {
+        return Err(Error::BlockNumberMismatch);
+    }
+
+    Ok(())
+}
+
+/// Validates a beneficiary claim operation.
+/// Checks vesting schedule, termination status, and claim amounts.
+fn validate_beneficiary_claim(
+    config: &VestingConfig,
+    input_state: &VestingState,
+    output_state: &VestingState,
+    highest_epoch: u64,
+) -> Result<(), Error> {
+    // Calculate vested amount using current epoch.
+    let vested_amount = calculate_total_vested(config, input_state, highest_epoch);
+
+    // Determine available claim amount.
+    let available_to_claim = vested_amount.saturating_sub(input_state.beneficiary_claimed);
+    let claimed_amount = output_state
+        .beneficiary_claimed
+        .saturating_sub(input_state.beneficiary_claimed);
+
+    // Ensure claim does not exceed vested amount.
+    if claimed_amount > available_to_claim {
+        return Err(Error::InsufficientVested);
+    }
+
+    // Verify state consistency after claim.
+    validate_state_consistency(input_state, output_state, claimed_amount, 0)?;
+
+    // Verify the claim is spread over parts that have vested it.
+    validate_tranche_claims(config, input_state, output_state, highest_epoch)?;
+
+    Ok(())
+}
+
+/// Validates a creator termination operation.
+/// Enforces all-or-nothing unvested amount claiming.
+fn validate_creator_termination(
+    config: &VestingConfig,
+    input_state: &VestingState,
+    output_state: &VestingState,
+    highest_epoch: u64,
+) -> Result<(), Error> {
+    // Prevent multiple terminations.
+    if input_state.creator_claimed > 0 {
+        return Err(Error::AlreadyTerminated);
+    }
+
+    // Calculate vested amount for termination, including any accelerated share.
+    let vested_amount = calculate_vested_at_termination(config, input_state, highest_epoch);
+
+    // Enforce all-or-nothing termination policy.
+    let unvested_amount = input_state.total_amount.saturating_sub(vested_amount);
+    let creator_claimed = output_state
+        .creator_claimed
+        .saturating_sub(input_state.creator_claimed);
+
+    if creator_claimed != unvested_amount {
+        return Err(Error::InvalidAmount);
+    }
+
+    // Verify state consistency after termination.
+    validate_state_consistency(input_state, output_state, 0, creator_claimed)?;
+
+    // Termination is recorded in the aggregate only.
+    if !tranches_unchanged(input_state, output_state) {
+        return Err(Error::TrancheChanged);
+    }
+
+    Ok(())
+}
+
+/// Checks whether every unit of the grant has been claimed by one of the parties.
+/// Zero-amount cells are settled from the start.
+fn is_settled(state: &VestingState) -> bool {
+    state.beneficiary_claimed.saturating_add(state.creator_claimed) >= state.total_amount
+}
+
+/// Validates that a sweep carries no payout redirection, operation or event.
+/// Nobody claims or terminates in a sweep, so every witness field would be meaningless.
+fn validate_sweep_witness() -> Result<(), Error> {
+    if load_vesting_witness_args()?.is_some_and(|witness_args| {
+        witness_args.lock().to_opt().is_some()
+            || witness_args.input_type().to_opt().is_some()
+            || witness_args.output_type().to_opt().is_some()
+    }) {
+        return Err(Error::InvalidWitness);
+    }
+
+    Ok(())
+}
+
+/// Validates that a settled cell is consumed with its capacity sent to the sweep lock.
+/// Anyone may sweep; there is no separate sweep fee, so the keeper tip also bounds the capacity the sweeper keeps for the fee.
+/// The tip never covers tokens, which a UDT cell must hand over in full.
+fn validate_dust_sweep(vesting_config: &VestingConfig) -> Result<(), Error> {
+    // Only cells that name a sweep lock can be swept, so capacity never goes anywhere by default.
+    let sweep_lock_hash = vesting_config.sweep_lock_hash.ok_or(Error::MissingSweepLock)?;
+
+    // The sweep lock's own inputs are subtracted, so its change does not count as swept capacity.
+    let residual_capacity = load_cell_capacity(0, Source::GroupInput)?;
+    let swept_capacity = sum_capacity_by_lock(&sweep_lock_hash, Source::Output)?
+        .saturating_sub(sum_capacity_by_lock(&sweep_lock_hash, Source::Input)?);
+    if swept_capacity < residual_capacity.saturating_sub(vesting_config.keeper_tip) {
+        return Err(Error::InsufficientSweep);
+    }
+
+    // UDT cells also hand any tokens left in the cell to the sweep lock.
+    if let Some(udt_type_hash) = &vesting_config.udt_type_hash {
+        let swept_amount = sum_udt_by_lock(&sweep_lock_hash, udt_type_hash, Source::Output)?
+            .saturating_sub(sum_udt_by_lock(&sweep_lock_hash, udt_type_hash, Source::Input)?);
+        if swept_amount < load_udt_amount(0, Source::GroupInput)? {
+            return Err(Error::InsufficientSweep);
+        }
+    }
+
+    validate_payout_output_data(&sweep_lock_hash, vesting_config)
+}
+
+/// Validates that an anonymous update takes at most the configured keeper tip from the cell.
+/// Rewards whoever keeps highest_block_seen fresh while bounding the capacity they can remove.
+fn validate_keeper_tip(script_hash: &[u8; 32], vesting_config: &VestingConfig, input_state: &VestingState) -> Result<(), Error> {
+    let input_capacity = load_cell_capacity(0, Source::GroupInput)?;
+    let output_index = find_matching_output_index(script_hash)?;
+    let output_capacity = load_cell_capacity(output_index, Source::Output)?;
+
+    if output_capacity < input_capacity.saturating_sub(vesting_config.keeper_tip) {
+        return Err(Error::KeeperTipExceeded);
+    }
+
+    // A tip may never dig into the cell's occupied capacity or the capacity backing unclaimed funds.
+    // Basis-point cells keep their reserve instead, and UDT cells only the capacity vesting alongside their tokens.
+    if output_capacity < input_capacity {
+        let occupied_capacity = load_cell_occupied_capacity(output_index, Source::Output)?;
+        let kept_capacity = match (vesting_config.bps_reserve, &vesting_config.udt_type_hash) {
+            (Some(reserve), _) => reserve.max(occupied_capacity),
+            (None, Some(_)) => vested_capacity(
+                input_state
+                    .total_amount
+                    .saturating_sub(input_state.beneficiary_claimed)
+                    .saturating_sub(input_state.creator_claimed),
+                vesting_config.capacity_per_unit,
+            )
+            .saturating_add(occupied_capacity),
+            (None, None) => input_state
+                .total_amount
+                .saturating_sub(input_state.beneficiary_claimed)
+                .saturating_sub(input_state.creator_claimed)
+                .saturating_add(occupied_capacity),
+        };
+        if output_capacity < kept_capacity {
+            return Err(Error::KeeperTipExceeded);
+        }
+    }
+
+    Ok(())
+}
+
+/// Converts basis points claimed from a basis-point cell into the capacity they release.
+/// The backing is the input capacity above the reserve, shared by the basis points not yet claimed.
+fn bps_released_capacity(reserve: u64, input_state: &VestingState, claimed_bps: u64) -> Result<u64, Error> {
+    let backing = load_cell_capacity(0, Source::GroupInput)?
+        .checked_sub(reserve)
+        .ok_or(Error::InvalidBasisPointBacking)?;
+    let unclaimed_bps = input_state
+        .total_amount
+        .saturating_sub(input_state.beneficiary_claimed)
+        .saturating_sub(input_state.creator_claimed);
+    if unclaimed_bps == 0 {
+        return Ok(0);
+    }
+
+    // Claims never exceed the unclaimed basis points, so the result fits the backing.
+    let released = backing as u128 * claimed_bps.min(unclaimed_bps) as u128 / unclaimed_bps as u128;
+    Ok(released as u64)
+}
+
+/// Validates that a claim or termination of a basis-point cell releases no more capacity than it claimed.
+/// The continuation cell keeps the reserve plus the backing of every basis point still unclaimed.
+fn validate_bps_backing(
+    script_hash: &[u8; 32],
+    reserve: u64,
+    input_state: &VestingState,
+    output_state: &VestingState,
+) -> Result<(), Error> {
+    let claimed_bps = output_state
+        .beneficiary_claimed
+        .saturating_add(output_state.creator_claimed)
+        .saturating_sub(input_state.beneficiary_claimed.saturating_add(input_state.creator_claimed));
+    let released = bps_released_capacity(reserve, input_state, claimed_bps)?;
+
+    let input_capacity = load_cell_capacity(0, Source::GroupInput)?;
+    let output_index = find_matching_output_index(script_hash)?;
+    let output_capacity = load_cell_capacity(output_index, Source::Output)?;
+
+    if output_capacity < input_capacity.saturating_sub(released) {
+        return Err(Error::InvalidBasisPointBacking);
+    }
+
+    Ok(())
+}
+
+/// Validates that an anonymous update advances highest_block_seen by at least the minimum interval.
+/// Stops griefers from churning the cell every block and invalidating pending claims.
+fn validate_update_interval(
+    input_state: &VestingState,
+    output_state: &VestingState,
+    min_update_interval: u64,
+) -> Result<(), Error> {
+    let advance = output_state
+        .highest_block_seen
+        .saturating_sub(input_state.highest_block_seen);
+    if advance < min_update_interval {
+        return Err(Error::UpdateTooFrequent);
+    }
+    Ok(())
+}
+
+/// Validates that only the highest block number was updated.
+/// Used for anyone-can-update security maintenance operations.
+fn validate_block_update_only(
+    input_state: &VestingState,
+    output_state: &VestingState,
+) -> Result<(), Error> {
+    // Ensure only block tracking changed.
+    if output_state.total_amount != input_state.total_amount
+        || output_state.beneficiary_claimed != input_state.beneficiary_claimed
+        || output_state.creator_claimed != input_state.creator_claimed
+        || !tranches_unchanged(input_state, output_state)
+    {
+        return Err(Error::InvalidStateChange);
+    }
+
+    Ok(())
+}
+
+/// Validates state transition consistency.
+/// Ensures proper accounting for claim deltas and invariants.
+fn validate_state_consistency(
+    input_state: &VestingState,
+    output_state: &VestingState,
+    beneficiary_claimed_delta: u64,
+    creator_claimed_delta: u64,
+) -> Result<(), Error> {
+    // Enforce total amount immutability.
+    if output_state.total_amount != input_state.total_amount {
+        return Err(Error::TotalAmountChanged);
+    }
+
+    // Verify beneficiary claim delta accuracy.
+    if output_state.beneficiary_claimed
+        != input_state
+            .beneficiary_claimed
+            .saturating_add(beneficiary_claimed_delta)
+    {
+        return Err(Error::InvalidBeneficiaryClaimedDelta);
+    }
+
+    // Verify creator claim delta accuracy.
+    if output_state.creator_claimed
+        != input_state
+            .creator_claimed
+            .saturating_add(creator_claimed_delta)
+    {
+        return Err(Error::InvalidCreatorClaimedDelta);
+    }
+
+    Ok(())
+}
+
+/// Calculates the vested amount at a schedule point, so linear vesting also accrues within an epoch.
+/// Dispatches on the schedule type between linear, optionally in equal steps, and milestone vesting, all with cliff period support.
+fn calculate_vested_amount(params: &ScheduleParams, current_epoch: u64, total_amount: u64, creator_claimed: u64) -> u64 {
+    // Post-termination: everything not claimed by creator is vested.
+    if creator_claimed > 0 {
+        return total_amount.saturating_sub(creator_claimed);
+    }
+
+    let start_point = schedule_point(params.start_epoch);
+    let end_point = schedule_point(params.end_epoch);
+
+    // Nothing vests before start epoch.
+    if current_epoch < start_point {
+        return 0;
+    }
+
+    // Handle start >= end: instant vest at start.
+    if start_point >= end_point {
+        return total_amount;
+    }
+
+    // Effective cliff cannot exceed end epoch.
+    let effective_cliff = schedule_point(params.cliff_epoch).min(end_point);
+    if current_epoch < effective_cliff {
+        return 0;
+    }
+
+    // Past end epoch = fully vested.
+    if current_epoch >= end_point {
+        return total_amount;
+    }
+
+    if let Schedule::Milestones(milestones) = params.schedule {
+        return calculate_milestone_vested(milestones, current_epoch, total_amount);
+    }
+
+    let elapsed = current_epoch - start_point;
+    let duration = end_point - start_point;
+
+    // Stepped vesting only counts whole steps; u128 math cannot overflow for u64 inputs.
+    if let Some(steps) = params.granularity {
+        let completed_steps = elapsed as u128 * steps as u128 / duration as u128;
+        return (total_amount as u128 * completed_steps / steps as u128) as u64;
+    }
+
+    // Elapsed points are below the duration, so the result never exceeds the total.
+    (elapsed as u128 * total_amount as u128 / duration as u128) as u64
+}
+
+/// Calculates the vested amount of a milestone schedule at the given epoch.
+/// Every milestone reached releases its share of the total, rounded down on the cumulative share.
+fn calculate_milestone_vested(milestones: &Milestones, current_epoch: u64, total_amount: u64) -> u64 {
+    let reached_bps = milestones
+        .epochs
+        .iter()
+        .zip(milestones.shares_bps.iter())
+        .take(milestones.count)
+        .filter(|(epoch, _)| schedule_point(**epoch) <= current_epoch)
+        .fold(0u128, |sum, (_, share_bps)| sum + *share_bps as u128);
+
+    (total_amount as u128 * reached_bps / BPS_DENOMINATOR) as u64
+}
+
+/// Loads the WitnessArgs of the vesting input.
+/// Returns None when the witness is missing or empty.
+fn load_vesting_witness_args() -> Result<Option<WitnessArgs>, Error> {
+    // Missing and empty witnesses carry no redirection or event.
+    match syscalls::load_witness(&mut [], 0, 0, Source::GroupInput) {
+        Err(SysError::IndexOutOfBound) | Ok(0) => return Ok(None),
+        _ => {}
+    }
+
+    let witness_args = load_witness_args(0, Source::GroupInput).map_err(|_| Error::InvalidWitness)?;
+    Ok(Some(witness_args))
+}
+
+/// Parses the optional payout lock hash from the lock field of the vesting input's witness.
+/// Returns None when there is no witness or the witness carries no lock field.
+fn parse_payout_lock_hash(witness_args: Option<&WitnessArgs>, script_hash: &[u8; 32]) -> Result<Option<[u8; 32]>, Error> {
+    let lock: Bytes = match witness_args.and_then(|witness_args| witness_args.lock().to_opt()) {
+        Some(lock) => lock.raw_data(),
+        None => return Ok(None),
+    };
+
+    // The payout lock must be a full lock hash and cannot be the vesting lock itself.
+    if lock.len() != 32 {
+        return Err(Error::InvalidWitness);
+    }
+    let payout_lock_hash = read_hash(&lock, 0);
+    if &payout_lock_hash == script_hash {
+        return Err(Error::InvalidWitness);
+    }
+
+    Ok(Some(payout_lock_hash))
+}
+
+/// Validates the optional event in the output_type field of the vesting input's witness.
+/// When present, the event must describe exactly the operation and state delta being validated.
+fn validate_event(
+    witness_args: Option<&WitnessArgs>,
+    auth_type: AuthorizationType,
+    input_state: &VestingState,
+    output_state: &VestingState,
+    highest_epoch: u64,
+    highest_block_from_headers: u64,
+) -> Result<(), Error> {
+    let event: Bytes = match witness_args.and_then(|witness_args| witness_args.output_type().to_opt()) {
+        Some(event) => event.raw_data(),
+        None => return Ok(()),
+    };
+
+    if event.len() != EVENT_LEN || event.get(EVENT_VERSION_OFFSET) != Some(&EVENT_VERSION) {
+        return Err(Error::InvalidEvent);
+    }
+
+    // Derive the operation and amount the event must report from the state delta.
+    let (operation, amount) = match auth_type {
+        AuthorizationType::Creator => (
+            EVENT_OPERATION_TERMINATION,
+            output_state.creator_claimed.saturating_sub(input_state.creator_claimed),
+        ),
+        AuthorizationType::Beneficiary => (
+            EVENT_OPERATION_CLAIM,
+            output_state.beneficiary_claimed.saturating_sub(input_state.beneficiary_claimed),
+        ),
+        AuthorizationType::None => (EVENT_OPERATION_BLOCK_UPDATE, 0),
+    };
+
+    if event.get(EVENT_OPERATION_OFFSET) != Some(&operation)
+        || read_u64(&event, EVENT_AMOUNT_OFFSET) != amount
+        || read_u64(&event, EVENT_EPOCH_OFFSET) != highest_epoch
+        || read_u64(&event, EVENT_BLOCK_NUMBER_OFFSET) != highest_block_from_headers
+    {
+        return Err(Error::InvalidEvent);
+    }
+
+    Ok(())
+}
+
+/// Validates that the payout lock nets at least the claimed amount across the transaction.
+/// Used when the beneficiary redirects a claim to an alternate lock through the witness; UDT cells pay in tokens.
+/// The payout lock's own inputs are subtracted, so its change does not count as payment.
+fn validate_payout_outputs(
+    payout_lock_hash: &[u8; 32],
+    claimant_lock_hash: &[u8; 32],
+    vesting_config: &VestingConfig,
+    claimed_amount: u64,
+) -> Result<(), Error> {
+    let (paid_amount, required_amount) = match &vesting_config.udt_type_hash {
+        Some(udt_type_hash) => (
+            net_udt_received(payout_lock_hash, claimant_lock_hash, udt_type_hash, vesting_config.cheque_lock.as_ref())?,
+            udt_tokens(claimed_amount, vesting_config.udt_unit),
+        ),
+        None => (
+            (sum_capacity_by_lock(payout_lock_hash, Source::Output)? as u128)
+                .saturating_sub(sum_capacity_by_lock(payout_lock_hash, Source::Input)? as u128),
+            claimed_amount as u128,
+        ),
+    };
+
+    if paid_amount < required_amount {
+        return Err(Error::InsufficientPayout);
+    }
+
+    // Dual-token cells also pay the capacity that vested alongside the claimed tokens.
+    if vesting_config.udt_type_hash.is_some() {
+        let paid_capacity = sum_capacity_by_lock(payout_lock_hash, Source::Output)?
+            .saturating_sub(sum_capacity_by_lock(payout_lock_hash, Source::Input)?);
+        if paid_capacity < vested_capacity(claimed_amount, vesting_config.capacity_per_unit) {
+            return Err(Error::InsufficientPayout);
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates that outputs paid to the given lock carry no state of their own.
+/// Every claim, termination, buyout and sweep checks its payout lock here, so funds leaving the cell always land in plain, spendable cells.
+/// Untyped outputs must have empty data; typed outputs may hold at most a UDT amount, plus extension data for the vested xUDT.
+fn validate_payout_output_data(payout_lock_hash: &[u8; 32], vesting_config: &VestingConfig) -> Result<(), Error> {
+    for (index, lock_hash) in QueryIter::new(load_cell_lock_hash, Source::Output).enumerate() {
+        if &lock_hash != payout_lock_hash {
+            continue;
+        }
+
+        // Only the length is needed, so an empty buffer is enough.
+        let data_len = match syscalls::load_cell_data(&mut [], 0, index, Source::Output) {
+            Ok(len) | Err(SysError::LengthNotEnough(len)) => len,
+            Err(_) => return Err(Error::LoadCellDataFailed),
+        };
+        let max_data_len = match load_cell_type_hash(index, Source::Output)? {
+            // The xUDT script validates its own XudtData table after the amount.
+            Some(type_hash) if vesting_config.xudt && vesting_config.udt_type_hash == Some(type_hash) => usize::MAX,
+            Some(_) => MAX_TYPED_PAYOUT_DATA_LEN,
+            None => 0,
+        };
+
+        if data_len > max_data_len {
+            return Err(Error::PayoutOutputHasData);
+        }
+    }
+
+    Ok(())
+}
+
+/// A role rotation requested through the input_type field of the vesting input's witness.
+#[derive(Clone, Copy)]
+struct Rotation {
+    operation: u8,
+    output_index: usize,
+}
+
+/// Sums the capacity of all cells with the given lock hash in the given source.
+/// Saturates instead of overflowing, which no real transaction can reach.
+fn sum_capacity_by_lock(lock_hash: &[u8; 32], source: Source) -> Result<u64, Error> {
+    let mut total: u64 = 0;
+    for (index, cell_lock_hash) in QueryIter::new(load_cell_lock_hash, source).enumerate() {
+        if &cell_lock_hash == lock_hash {
+            total = total.saturating_add(load_cell_capacity(index, source)?);
+        }
+    }
+    Ok(total)
+}
+
+/// Loads the 128-bit UDT amount at the start of a cell's data.
+/// Data too short to hold an amount is not a valid UDT cell.
+fn load_udt_amount(index: usize, source: Source) -> Result<u128, Error> {
+    let mut amount = [0u8; UDT_AMOUNT_LEN];
+    match syscalls::load_cell_data(&mut amount, UDT_AMOUNT_OFFSET, index, source) {
+        Ok(len) | Err(SysError::LengthNotEnough(len)) if len >= UDT_AMOUNT_LEN => Ok(u128::from_le_bytes(amount)),
+        Ok(_) | Err(SysError::LengthNotEnough(_)) => Err(Error::InvalidUdtCell),
+        Err(_) => Err(Error::LoadCellDataFailed),
+    }
+}
+
+/// Sums the UDT amounts of cells with the given lock hash and UDT type in the given source.
+/// Cells of other types hold none of the vested token and are skipped.
+fn sum_udt_by_lock(lock_hash: &[u8; 32], udt_type_hash: &[u8; 32], source: Source) -> Result<u128, Error> {
+    let mut total: u128 = 0;
+    for (index, cell_lock_hash) in QueryIter::new(load_cell_lock_hash, source).enumerate() {
+        if &cell_lock_hash == lock_hash && load_cell_type_hash(index, source)?.as_ref() == Some(udt_type_hash) {
+            total = total.saturating_add(load_udt_amount(index, source)?);
+        }
+    }
+    Ok(total)
+}
+
+/// Sums the UDT amounts of cheque cells from the sender to the receiver with the given UDT type in the given source.
+/// Cheque args hold the first 20 bytes of the receiver's lock hash followed by those of the sender's.
+fn sum_udt_by_cheque(
+    cheque_lock: &ChequeLock,
+    receiver_lock_hash: &[u8; 32],
+    sender_lock_hash: &[u8; 32],
+    udt_type_hash: &[u8; 32],
+    source: Source,
+) -> Result<u128, Error> {
+    let mut total: u128 = 0;
+    for (index, lock) in QueryIter::new(load_cell_lock, source).enumerate() {
+        let code_hash: [u8; 32] = lock.code_hash().unpack();
+        let hash_type: u8 = lock.hash_type().into();
+        let args = lock.args().raw_data();
+        let is_cheque = code_hash == cheque_lock.code_hash
+            && hash_type == cheque_lock.hash_type
+            && args.get(..CHEQUE_LOCK_HASH_PREFIX_LEN) == receiver_lock_hash.get(..CHEQUE_LOCK_HASH_PREFIX_LEN)
+            && args.get(CHEQUE_LOCK_HASH_PREFIX_LEN..) == sender_lock_hash.get(..CHEQUE_LOCK_HASH_PREFIX_LEN);
+        if is_cheque && load_cell_type_hash(index, source)?.as_ref() == Some(udt_type_hash) {
+            total = total.saturating_add(load_udt_amount(index, source)?);
+        }
+    }
+    Ok(total)
+}
+
+/// Returns the tokens a lock nets across the transaction, including cheques the claimant sends it.
+/// Its own token inputs and matching cheque inputs are subtracted, so passing tokens through does not count.
+fn net_udt_received(
+    lock_hash: &[u8; 32],
+    claimant_lock_hash: &[u8; 32],
+    udt_type_hash: &[u8; 32],
+    cheque_lock: Option<&ChequeLock>,
+) -> Result<u128, Error> {
+    let mut received = sum_udt_by_lock(lock_hash, udt_type_hash, Source::Output)?
+        .saturating_sub(sum_udt_by_lock(lock_hash, udt_type_hash, Source::Input)?);
+    if let Some(cheque_lock) = cheque_lock {
+        let cheque_amount = sum_udt_by_cheque(cheque_lock, lock_hash, claimant_lock_hash, udt_type_hash, Source::Output)?
+            .saturating_sub(sum_udt_by_cheque(cheque_lock, lock_hash, claimant_lock_hash, udt_type_hash, Source::Input)?);
+        received = received.saturating_add(cheque_amount);
+    }
+    Ok(received)
+}
+
+/// Validates that a vesting cell carries the configured UDT type script.
+/// The type script hash commits to the token, so a different type would vest a different token.
+fn validate_udt_cell(udt_type_hash: &[u8; 32], index: usize, source: Source) -> Result<(), Error> {
+    if load_cell_type_hash(index, source)?.as_ref() != Some(udt_type_hash) {
+        return Err(Error::InvalidUdtCell);
+    }
+
+    Ok(())
+}
+
+/// Converts an amount from the cell's state into the UDT tokens it stands for.
+/// Without a UDT unit each unit of the state is one token.
+fn udt_tokens(amount: u64, udt_unit: Option<u64>) -> u128 {
+    amount as u128 * udt_unit.unwrap_or(1) as u128
+}
+
+/// Returns the capacity that vests alongside an amount of a UDT cell.
+/// Without the vested capacity option a UDT cell vests tokens only.
+fn vested_capacity(amount: u64, capacity_per_unit: Option<u64>) -> u64 {
+    amount.saturating_mul(capacity_per_unit.unwrap_or(0))
+}
+
+/// Validates that the beneficiary lock nets at least the tokens claimed from a UDT cell.
+/// Cheques the beneficiary sends itself count as delivered, so a beneficiary without a token cell can still claim.
+fn validate_udt_claim_delivery(
+    beneficiary_lock_hash: &[u8; 32],
+    udt_type_hash: &[u8; 32],
+    cheque_lock: Option<&ChequeLock>,
+    claimed_tokens: u128,
+) -> Result<(), Error> {
+    let delivered_amount = net_udt_received(beneficiary_lock_hash, beneficiary_lock_hash, udt_type_hash, cheque_lock)?;
+    if delivered_amount < claimed_tokens {
+        return Err(Error::InsufficientPayout);
+    }
+
+    Ok(())
+}
+
+/// Validates that a transition of a UDT cell releases no more tokens than it claimed.
+/// The continuation cell keeps the UDT type, every token not claimed in this transition and its capacity less the keeper tip
+/// and the capacity vesting alongside the claimed amount.
+fn validate_udt_backing(
+    script_hash: &[u8; 32],
+    udt_type_hash: &[u8; 32],
+    vesting_config: &VestingConfig,
+    input_state: &VestingState,
+    output_state: &VestingState,
+) -> Result<(), Error> {
+    let claimed_amount = output_state
+        .beneficiary_claimed
+        .saturating_add(output_state.creator_claimed)
+        .saturating_sub(input_state.beneficiary_claimed.saturating_add(input_state.creator_claimed));
+
+    let output_index = find_matching_output_index(script_hash)?;
+    validate_udt_cell(udt_type_hash, output_index, Source::Output)?;
+
+    let input_amount = load_udt_amount(0, Source::GroupInput)?;
+    if load_udt_amount(output_index, Source::Output)? < input_amount.saturating_sub(udt_tokens(claimed_amount, vesting_config.udt_unit)) {
+        return Err(Error::InvalidUdtBacking);
+    }
+
+    // The amounts count tokens, so the cell's capacity is ballast that no transition may spend beyond the tip,
+    // apart from the capacity that vests alongside the claimed tokens.
+    let released_capacity =
+        vested_capacity(claimed_amount, vesting_config.capacity_per_unit).saturating_add(vesting_config.keeper_tip);
+    let input_capacity = load_cell_capacity(0, Source::GroupInput)?;
+    if load_cell_capacity(output_index, Source::Output)? < input_capacity.saturating_sub(released_capacity) {
+        return Err(Error::UdtCapacityNotConserved);
+    }
+
+    Ok(())
+}
+
+/// Validates that a beneficiary claim is a whole multiple of the configured claim unit.
+/// Only claims that leave a continuation cell are checked.
+fn validate_claim_unit(claim_unit: u64, input_state: &VestingState, output_state: &VestingState) -> Result<(), Error> {
+    let claimed_amount = output_state
+        .beneficiary_claimed
+        .saturating_sub(input_state.beneficiary_claimed);
+    if !claimed_amount.is_multiple_of(claim_unit) {
+        return Err(Error::InvalidClaimUnit);
+    }
+
+    Ok(())
+}
+
+/// Validates that the reclaimed amount is split among the creator and co-grantors per their shares.
+/// Each grantor's lock must net at least its share, rounded down, and receive plain cells.
+fn validate_co_grantor_split(
+    vesting_config: &VestingConfig,
+    co_grantors: &CoGrantors,
+    input_state: &VestingState,
+    reclaimed_amount: u64,
+) -> Result<(), Error> {
+    let reclaimed_capacity = match vesting_config.bps_reserve {
+        Some(reserve) => bps_released_capacity(reserve, input_state, reclaimed_amount)?,
+        None => reclaimed_amount,
+    };
+
+    // The primary creator is owed whatever share the co-grantors leave.
+    let co_grantor_share: u128 = co_grantors
+        .shares_bps
+        .iter()
+        .take(co_grantors.count)
+        .map(|share_bps| *share_bps as u128)
+        .sum();
+    let grantors = co_grantors
+        .lock_hashes
+        .iter()
+        .zip(co_grantors.shares_bps.iter().map(|share_bps| *share_bps as u128))
+        .take(co_grantors.count)
+        .chain(core::iter::once((&vesting_config.creator_lock_hash, BPS_DENOMINATOR.saturating_sub(co_grantor_share))));
+
+    for (lock_hash, share_bps) in grantors {
+        let owed = (reclaimed_capacity as u128 * share_bps / BPS_DENOMINATOR) as u64;
+
+        // A grantor's own inputs are subtracted, so returning its change does not count as its share.
+        let received = sum_capacity_by_lock(lock_hash, Source::Output)?
+            .saturating_sub(sum_capacity_by_lock(lock_hash, Source::Input)?);
+        if received < owed {
+            return Err(Error::InsufficientGrantorShare);
+        }
+
+        validate_payout_output_data(lock_hash, vesting_config)?;
+    }
+
+    Ok(())
+}
+
+/// Parses the optional creator buyout amount from the input_type field of the vesting input's witness.
+/// Returns None unless the field starts with the buyout operation, leaving other values to role rotation.
+fn parse_buyout(witness_args: Option<&WitnessArgs>) -> Result<Option<u64>, Error> {
+    let buyout: Bytes = match witness_args.and_then(|witness_args| witness_args.input_type().to_opt()) {
+        Some(buyout) => buyout.raw_data(),
+        None => return Ok(None),
+    };
+
+    if buyout.get(BUYOUT_OPERATION_OFFSET) != Some(&OPERATION_CREATOR_BUYOUT) {
+        return Ok(None);
+    }
+    if buyout.len() != BUYOUT_LEN {
+        return Err(Error::InvalidBuyout);
+    }
+
+    Ok(Some(read_u64(&buyout, BUYOUT_AMOUNT_OFFSET)))
+}
+
+/// Validates a creator buyout that cancels the remaining schedule for an immediate payment.
+/// Both parties must sign, the cell must be consumed and the agreed amount must cover what has vested.
+fn validate_creator_buyout(
+    script_hash: &[u8; 32],
+    vesting_config: &VestingConfig,
+    roles: AuthorizedRoles,
+    input_state: &VestingState,
+    vesting_epoch: u64,
+    buyout_amount: u64,
+) -> Result<(), Error> {
+    // A buyout changes the agreement, so it needs both parties.
+    if !(roles.creator && roles.beneficiary) {
+        return Err(Error::Unauthorized);
+    }
+
+    // Buyouts are paid in capacity, which cannot price a UDT grant.
+    if vesting_config.udt_type_hash.is_some() {
+        return Err(Error::InvalidBuyout);
+    }
+
+    // A buyout pays a single beneficiary, so it cannot settle a grant shared with co-beneficiaries.
+    if vesting_config.co_beneficiaries.is_some() {
+        return Err(Error::InvalidBuyout);
+    }
+
+    // The schedule ends here, so no continuation cell may remain.
+    if QueryIter::new(load_cell_lock_hash, Source::Output).any(|lock_hash| &lock_hash == script_hash) {
+        return Err(Error::InvalidBuyout);
+    }
+
+    // The beneficiary is never bought out for less than what has already vested.
+    let vested_unclaimed = calculate_total_vested(vesting_config, input_state, vesting_epoch)
+        .saturating_sub(input_state.beneficiary_claimed);
+    let minimum_payment = match vesting_config.bps_reserve {
+        Some(reserve) => bps_released_capacity(reserve, input_state, vested_unclaimed)?,
+        None => vested_unclaimed,
+    };
+    if buyout_amount < minimum_payment {
+        return Err(Error::InvalidBuyout);
+    }
+
+    Ok(())
+}
+
+/// Validates that the payout lock nets at least the agreed buyout amount.
+/// The payout lock's own inputs are subtracted, so returning its change does not count as payment.
+fn validate_buyout_payment(vesting_config: &VestingConfig, payout_lock_hash: &[u8; 32], buyout_amount: u64) -> Result<(), Error> {
+    let paid_amount = sum_capacity_by_lock(payout_lock_hash, Source::Output)?
+        .saturating_sub(sum_capacity_by_lock(payout_lock_hash, Source::Input)?);
+    if paid_amount < buyout_amount {
+        return Err(Error::InsufficientPayout);
+    }
+
+    validate_payout_output_data(payout_lock_hash, vesting_config)
+}
+
+/// A claim of one merkle leaf, proven by the sibling hashes on the path from the leaf to the root.
+/// Holds the whole input_type field, whose siblings follow the leaf.
+struct MerkleClaim {
+    index: u64,
+    leaf: [u8; LEAF_LEN],
+    data: Bytes,
+}
+
+/// Parses the optional merkle claim from the input_type field of the vesting input's witness.
+/// Returns None unless the field starts with the merkle claim operation, leaving other values to buyouts and rotations.
+fn parse_merkle_claim(witness_args: Option<&WitnessArgs>, merkle_beneficiaries: &MerkleBeneficiaries) -> Result<Option<MerkleClaim>, Error> {
+    let claim: Bytes = match witness_args.and_then(|witness_args| witness_args.input_type().to_opt()) {
+        Some(claim) => claim.raw_data(),
+        None => return Ok(None),
+    };
+
+    if claim.get(MERKLE_CLAIM_OPERATION_OFFSET) != Some(&OPERATION_MERKLE_CLAIM) {
+        return Ok(None);
+    }
+    if claim.len() != MERKLE_CLAIM_SIBLINGS_OFFSET + merkle_beneficiaries.depth * 32 {
+        return Err(Error::InvalidWitness);
+    }
+
+    let mut index = [0u8; 4];
+    if let Some(slice) = claim.get(MERKLE_CLAIM_INDEX_OFFSET..MERKLE_CLAIM_LEAF_OFFSET) {
+        index.copy_from_slice(slice);
+    }
+    let mut leaf = [0u8; LEAF_LEN];
+    if let Some(slice) = claim.get(MERKLE_CLAIM_LEAF_OFFSET..MERKLE_CLAIM_SIBLINGS_OFFSET) {
+        leaf.copy_from_slice(slice);
+    }
+    let index = u32::from_le_bytes(index) as u64;
+
+    // The index must address a leaf of the tree, whose schedule follows the same ordering rules as the base schedule.
+    let schedule = merkle_leaf_schedule(&leaf);
+    if index >> merkle_beneficiaries.depth != 0
+        || schedule.start_epoch >= schedule.end_epoch
+        || schedule.cliff_epoch < schedule.start_epoch
+        || schedule.cliff_epoch > schedule.end_epoch
+        || schedule.claimed > schedule.total_amount
+    {
+        return Err(Error::InvalidWitness);
+    }
+
+    Ok(Some(MerkleClaim { index, leaf, data: claim }))
+}
+
+/// Views a merkle leaf as a tranche, since each leaf vests its amount linearly on its own schedule.
+/// Leaf epochs are whole epochs, or blocks for block schedules, just like tranche records.
+fn merkle_leaf_schedule(leaf: &[u8]) -> Tranche {
+    Tranche {
+        total_amount: read_u64(leaf, LEAF_AMOUNT_OFFSET),
+        claimed: read_u64(leaf, LEAF_CLAIMED_OFFSET),
+        start_epoch: read_u64(leaf, LEAF_START_EPOCH_OFFSET),
+        end_epoch: read_u64(leaf, LEAF_END_EPOCH_OFFSET),
+        cliff_epoch: read_u64(leaf, LEAF_CLIFF_EPOCH_OFFSET),
+    }
+}
+
+/// Hashes the concatenated parts with blake2b-256 and CKB's personalization.
+/// Matches CKB's default hash, so SDKs can build trees and proofs with their usual hasher.
+fn blake2b_256(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = new_blake2b();
+    for part in parts {
+        hasher.update(part);
+    }
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    hash
+}
+
+/// Computes the merkle root from the claimed leaf, with the given claimed amount, and the claim's sibling hashes.
+/// Leaves hash 72 bytes and nodes 64, so a node can never be passed off as a leaf.
+fn merkle_root(claim: &MerkleClaim, claimed: u64) -> [u8; 32] {
+    let mut leaf = claim.leaf;
+    if let Some(slot) = leaf.get_mut(LEAF_CLAIMED_OFFSET..LEAF_LEN) {
+        slot.copy_from_slice(&claimed.to_le_bytes());
+    }
+
+    // Each index bit, starting at the leaves, tells whether the node is the right child on its level.
+    let mut node = blake2b_256(&[&leaf]);
+    let mut level = 0;
+    while let Some(sibling) = claim
+        .data
+        .get(MERKLE_CLAIM_SIBLINGS_OFFSET + level * 32..MERKLE_CLAIM_SIBLINGS_OFFSET + (level + 1) * 32)
+    {
+        node = if (claim.index >> level) & 1 == 0 {
+            blake2b_256(&[&node, sibling])
+        } else {
+            blake2b_256(&[sibling, &node])
+        };
+        level += 1;
+    }
+    node
+}
+
+/// Reads the current merkle root from a merkle cell's extension area.
+/// The extension is validated to hold the root before it is read.
+fn merkle_extension_root(state: &VestingState) -> [u8; 32] {
+    read_hash(&state.extension, MERKLE_EXTENSION_ROOT_OFFSET)
+}
+
+/// Validates that the claim proves its leaf against the cell's current root and that the leaf's beneficiary authorized it.
+/// The proof commits to the leaf's claimed amount before this claim.
+fn validate_merkle_claimant(claim: &MerkleClaim, root: &[u8; 32]) -> Result<(), Error> {
+    if &merkle_root(claim, merkle_leaf_schedule(&claim.leaf).claimed) != root {
+        return Err(Error::InvalidMerkleProof);
+    }
+
+    let lock_hash = read_hash(&claim.leaf, LEAF_LOCK_HASH_OFFSET);
+    if !QueryIter::new(load_cell_lock_hash, Source::Input).any(|input_lock_hash| input_lock_hash == lock_hash) {
+        return Err(Error::Unauthorized);
+    }
+
+    Ok(())
+}
+
+/// Validates that a leaf claims no more than it has vested and that the aggregate stays within the total.
+/// The creator declares the total, so leaves summing to more than it cannot all be paid.
+fn validate_leaf_claim(
+    vesting_config: &VestingConfig,
+    leaf: &Tranche,
+    claimed_after: u64,
+    output_state: &VestingState,
+    current_epoch: u64,
+) -> Result<(), Error> {
+    if claimed_after > calculate_tranche_vested(vesting_config, leaf, current_epoch)
+        || output_state.beneficiary_claimed.saturating_add(output_state.creator_claimed) > output_state.total_amount
+    {
+        return Err(Error::InsufficientVested);
+    }
+
+    Ok(())
+}
+
+/// Validates that the continuation records the claim in its merkle root.
+/// Only the claimed leaf changes, so the new root follows from the same sibling hashes.
+fn validate_merkle_root_update(claim: &MerkleClaim, claimed_after: u64, output_state: &VestingState) -> Result<(), Error> {
+    if merkle_root(claim, claimed_after) != merkle_extension_root(output_state) {
+        return Err(Error::MerkleRootMismatch);
+    }
+
+    Ok(())
+}
+
+/// Processes a merkle claim, which pays one proven leaf instead of running a role's operation.
+/// The cell continues under the updated root until a claim settles it.
+fn process_merkle_claim(
+    script_hash: &[u8; 32],
+    vesting_config: &VestingConfig,
+    input_state: &VestingState,
+    witness_args: Option<&WitnessArgs>,
+    claim: &MerkleClaim,
+    vesting_epoch: u64,
+    highest_block_from_headers: u64,
+) -> Result<(), Error> {
+    // Merkle claims pay the leaf's lock directly and carry no event.
+    if witness_args.is_some_and(|witness_args| {
+        witness_args.lock().to_opt().is_some() || witness_args.output_type().to_opt().is_some()
+    }) {
+        return Err(Error::InvalidWitness);
+    }
+
+    validate_merkle_claimant(claim, &merkle_extension_root(input_state))?;
+
+    let (output_state, has_output) = match find_matching_output_data(script_hash, vesting_config, Error::WrongDataLength) {
+        Ok(output_data) => (parse_vesting_state(&output_data)?, true),
+        Err(Error::NoMatchingOutputCell) => {
+            // A claim without a continuation takes everything not yet claimed.
+            (VestingState {
+                total_amount: input_state.total_amount,
+                beneficiary_claimed: input_state.total_amount.saturating_sub(input_state.creator_claimed),
+                creator_claimed: input_state.creator_claimed,
+                highest_block_seen: input_state.highest_block_seen,
+                tranches: input_state.tranches,
+                tranche_count: input_state.tranche_count,
+                extension: input_state.extension,
+                extension_len: input_state.extension_len,
+            }, false)
+        }
+        Err(err) => return Err(err),
+    };
+
+    if has_output {
+        validate_merkle_extension(vesting_config, &output_state)?;
+        validate_highest_block_update(input_state, &output_state, highest_block_from_headers)?;
+    }
+
+    // The aggregate counts every leaf's claims, so it grows by exactly the leaf's claim.
+    let claimed_amount = output_state
+        .beneficiary_claimed
+        .saturating_sub(input_state.beneficiary_claimed);
+    validate_state_consistency(input_state, &output_state, claimed_amount, 0)?;
+
+    let leaf = merkle_leaf_schedule(&claim.leaf);
+    let claimed_after = leaf.claimed.saturating_add(claimed_amount);
+    validate_leaf_claim(vesting_config, &leaf, claimed_after, &output_state, vesting_epoch)?;
+    validate_shared_claim_output(has_output, &output_state)?;
+    if has_output {
+        validate_merkle_root_update(claim, claimed_after, &output_state)?;
+    }
+
+    // Basis-point and UDT cells release only what was claimed, and UDT claims deliver the tokens to the leaf's lock.
+    let lock_hash = read_hash(&claim.leaf, LEAF_LOCK_HASH_OFFSET);
+    if let Some(reserve) = vesting_config.bps_reserve.filter(|_| has_output) {
+        validate_bps_backing(script_hash, reserve, input_state, &output_state)?;
+    }
+    if let Some(udt_type_hash) = &vesting_config.udt_type_hash {
+        if has_output {
+            validate_udt_backing(script_hash, udt_type_hash, vesting_config, input_state, &output_state)?;
+        }
+        let claimed_tokens = udt_tokens(claimed_amount, vesting_config.udt_unit);
+        validate_udt_claim_delivery(&lock_hash, udt_type_hash, vesting_config.cheque_lock.as_ref(), claimed_tokens)?;
+    }
+
+    validate_payout_output_data(&lock_hash, vesting_config)
+}
+
+/// Parses the optional role rotation from the input_type field of the vesting input's witness.
+/// Returns None when there is no witness or the witness carries no input_type field.
+fn parse_rotation(witness_args: Option<&WitnessArgs>) -> Result<Option<Rotation>, Error> {
+    let rotation: Bytes = match witness_args.and_then(|witness_args| witness_args.input_type().to_opt()) {
+        Some(rotation) => rotation.raw_data(),
+        None => return Ok(None),
+    };
+
+    if rotation.len() != ROTATION_LEN {
+        return Err(Error::InvalidRotation);
+    }
+    let mut output_index = [0u8; 4];
+    if let Some(slice) = rotation.get(ROTATION_OUTPUT_INDEX_OFFSET..ROTATION_LEN) {
+        output_index.copy_from_slice(slice);
+    }
+
+    Ok(Some(Rotation {
+        operation: rotation.get(ROTATION_OPERATION_OFFSET).copied().unwrap_or(0),
+        output_index: u32::from_le_bytes(output_index) as usize,
+    }))
+}
+
+/// Counts the distinct guardians or creator signers whose lock hashes appear among the transaction inputs.
+/// Each lock counts once, however many of its cells the transaction spends.
+fn count_approvals(lock_hashes: &[[u8; 32]]) -> usize {
+    let mut approved: u8 = 0;
+    for lock_hash in QueryIter::new(load_cell_lock_hash, Source::Input) {
+        for (index, approver) in lock_hashes.iter().enumerate() {
+            if &lock_hash == approver {
+                approved |= 1 << index;
+            }
+        }
+    }
+    approved.count_ones() as usize
+}
+
+/// Derives the args config the relocked cell must carry for a rotation operation.
+/// Also checks the operation's authorization and timing against the current config.
+fn expected_rotation_config(
+    vesting_config: &VestingConfig,
+    output_config: &VestingConfig,
+    roles: AuthorizedRoles,
+    highest_block_from_headers: u64,
+    operation: u8,
+) -> Result<VestingConfig, Error> {
+    let mut expected_config = *vesting_config;
+
+    match operation {
+        ROTATION_INITIATE_CREATOR_MIGRATION => {
+            if !roles.creator {
+                return Err(Error::Unauthorized);
+            }
+
+            // Migration is opt-in and only one may be pending at a time.
+            let delay = vesting_config.creator_migration_delay.ok_or(Error::InvalidRotation)?;
+            if vesting_config.pending_creator_migration.is_some() {
+                return Err(Error::InvalidRotation);
+            }
 
-    // Ensure claim does not exceed vested amount.
-    if claimed_amount > available_to_claim {
-        return Err(Error::InsufficientVested);
-    }
+            // The creator chooses the new lock hash, the delay fixes the effective block.
+            let new_lock_hash = output_config
+                .pending_creator_migration
+                .ok_or(Error::InvalidRotation)?
+                .new_lock_hash;
+            if new_lock_hash == vesting_config.creator_lock_hash {
+                return Err(Error::InvalidRotation);
+            }
+            let effective_block = highest_block_from_headers
+                .checked_add(delay)
+                .ok_or(Error::InvalidRotation)?;
+
+            expected_config.pending_creator_migration = Some(PendingMigration {
+                new_lock_hash,
+                effective_block,
+            });
+        }
+        ROTATION_VETO_CREATOR_MIGRATION => {
+            if !roles.beneficiary {
+                return Err(Error::Unauthorized);
+            }
+            if vesting_config.pending_creator_migration.is_none() {
+                return Err(Error::InvalidRotation);
+            }
 
-    // Verify state consistency after claim.
-    validate_state_consistency(input_state, output_state, claimed_amount, 0)?;
+            expected_config.pending_creator_migration = None;
+        }
+        ROTATION_FINALIZE_CREATOR_MIGRATION => {
+            // Anyone may finalize once the veto window has passed.
+            let pending = vesting_config.pending_creator_migration.ok_or(Error::InvalidRotation)?;
+            if highest_block_from_headers < pending.effective_block {
+                return Err(Error::RotationNotReady);
+            }
 
-    Ok(())
-}
+            // The old creator's auth policy and signers must not outlive the migration.
+            expected_config.creator_lock_hash = pending.new_lock_hash;
+            expected_config.creator_auth_lock = None;
+            expected_config.creator_signers = None;
+            expected_config.pending_creator_migration = None;
+        }
+        ROTATION_INITIATE_BENEFICIARY_RECOVERY => {
+            let guardians = vesting_config.guardians.ok_or(Error::InvalidRotation)?;
+            if count_approvals(guardians.lock_hashes.get(..guardians.count).unwrap_or(&[])) < guardians.threshold {
+                return Err(Error::Unauthorized);
+            }
+            if vesting_config.pending_beneficiary_recovery.is_some() {
+                return Err(Error::InvalidRotation);
+            }
 
-/// Validates a creator termination operation.
-/// Enforces all-or-nothing unvested amount claiming.
-fn validate_creator_termination(
-    config: &VestingConfig,
-    input_state: &VestingState,
-    output_state: &VestingState,
-    highest_epoch: u64,
-) -> Result<(), Error> {
-    // Prevent multiple terminations.
-    if input_state.creator_claimed > 0 {
-        return Err(Error::AlreadyTerminated);
-    }
+            // The guardians choose the new lock hash, the recovery delay fixes the effective block.
+            let new_lock_hash = output_config
+                .pending_beneficiary_recovery
+                .ok_or(Error::InvalidRotation)?
+                .new_lock_hash;
+            if new_lock_hash == vesting_config.beneficiary_lock_hash {
+                return Err(Error::InvalidRotation);
+            }
+            let effective_block = highest_block_from_headers
+                .checked_add(guardians.recovery_delay)
+                .ok_or(Error::InvalidRotation)?;
+
+            expected_config.pending_beneficiary_recovery = Some(PendingMigration {
+                new_lock_hash,
+                effective_block,
+            });
+        }
+        ROTATION_OBJECT_BENEFICIARY_RECOVERY => {
+            // A beneficiary who still holds their key can stop the recovery.
+            if !roles.beneficiary {
+                return Err(Error::Unauthorized);
+            }
+            if vesting_config.pending_beneficiary_recovery.is_none() {
+                return Err(Error::InvalidRotation);
+            }
 
-    // Calculate current vested amount for termination.
-    let vested_amount = calculate_vested_amount(
-        highest_epoch,
-        config.start_epoch,
-        config.end_epoch,
-        config.cliff_epoch,
-        input_state.total_amount,
-        input_state.creator_claimed,
-    );
+            expected_config.pending_beneficiary_recovery = None;
+        }
+        ROTATION_FINALIZE_BENEFICIARY_RECOVERY => {
+            // Anyone may finalize once the objection window has passed.
+            let pending = vesting_config.pending_beneficiary_recovery.ok_or(Error::InvalidRotation)?;
+            if highest_block_from_headers < pending.effective_block {
+                return Err(Error::RotationNotReady);
+            }
 
-    // Enforce all-or-nothing termination policy.
-    let unvested_amount = input_state.total_amount.saturating_sub(vested_amount);
-    let creator_claimed = output_state
-        .creator_claimed
-        .saturating_sub(input_state.creator_claimed);
+            // The lost key's auth policy must not outlive the recovery.
+            expected_config.beneficiary_lock_hash = pending.new_lock_hash;
+            expected_config.beneficiary_auth_lock = None;
+            expected_config.pending_beneficiary_recovery = None;
+        }
+        ROTATION_SET_PERFORMANCE_MULTIPLIER => {
+            let mut performance_oracle = vesting_config.performance_oracle.ok_or(Error::InvalidRotation)?;
+            if !QueryIter::new(load_cell_lock_hash, Source::Input).any(|lock_hash| lock_hash == performance_oracle.lock_hash) {
+                return Err(Error::Unauthorized);
+            }
 
-    if creator_claimed != unvested_amount {
-        return Err(Error::InvalidAmount);
-    }
+            // Only the current multiplier may change, and only within the creator's bounds.
+            let current_bps = output_config
+                .performance_oracle
+                .ok_or(Error::InvalidRotation)?
+                .current_bps;
+            if current_bps < performance_oracle.min_bps || current_bps > performance_oracle.max_bps {
+                return Err(Error::InvalidRotation);
+            }
+            performance_oracle.current_bps = current_bps;
 
-    // Verify state consistency after termination.
-    validate_state_consistency(input_state, output_state, 0, creator_claimed)?;
+            expected_config.performance_oracle = Some(performance_oracle);
+        }
+        ROTATION_TRANSFER_BENEFICIARY => {
+            if !roles.beneficiary {
+                return Err(Error::Unauthorized);
+            }
 
-    Ok(())
-}
+            // The beneficiary chooses the new lock hash, which takes effect at once.
+            if output_config.beneficiary_lock_hash == vesting_config.beneficiary_lock_hash {
+                return Err(Error::InvalidRotation);
+            }
 
-/// Validates that only the highest block number was updated.
-/// Used for anyone-can-update security maintenance operations.
-fn validate_block_update_only(
-    input_state: &VestingState,
-    output_state: &VestingState,
-) -> Result<(), Error> {
-    // Ensure only block tracking changed.
-    if output_state.total_amount != input_state.total_amount
-        || output_state.beneficiary_claimed != input_state.beneficiary_claimed
-        || output_state.creator_claimed != input_state.creator_claimed
-    {
-        return Err(Error::InvalidStateChange);
+            // The old lock's auth policy and any recovery of it must not outlive the transfer.
+            expected_config.beneficiary_lock_hash = output_config.beneficiary_lock_hash;
+            expected_config.beneficiary_auth_lock = None;
+            expected_config.pending_beneficiary_recovery = None;
+        }
+        _ => return Err(Error::InvalidRotation),
     }
 
-    Ok(())
+    Ok(expected_config)
 }
 
-/// Validates state transition consistency.
-/// Ensures proper accounting for claim deltas and invariants.
-fn validate_state_consistency(
-    input_state: &VestingState,
-    output_state: &VestingState,
-    beneficiary_claimed_delta: u64,
-    creator_claimed_delta: u64,
+/// Validates a role rotation, which moves the cell to the same code under rotated args.
+/// The cell data and capacity carry over unchanged apart from the tracked block number.
+fn validate_role_rotation(
+    script: &Script,
+    script_hash: &[u8; 32],
+    vesting_config: &VestingConfig,
+    roles: AuthorizedRoles,
+    input_data: &StateData,
+    highest_block_from_headers: u64,
+    rotation: Rotation,
 ) -> Result<(), Error> {
-    // Enforce total amount immutability.
-    if output_state.total_amount != input_state.total_amount {
-        return Err(Error::TotalAmountChanged);
-    }
-
-    // Verify beneficiary claim delta accuracy.
-    if output_state.beneficiary_claimed
-        != input_state
-            .beneficiary_claimed
-            .saturating_add(beneficiary_claimed_delta)
-    {
-        return Err(Error::InvalidBeneficiaryClaimedDelta);
+    // The cell is relocked as a whole, so no continuation under the current args may exist.
+    if QueryIter::new(load_cell_lock_hash, Source::Output).any(|lock_hash| &lock_hash == script_hash) {
+        return Err(Error::InvalidRotation);
     }
 
-    // Verify creator claim delta accuracy.
-    if output_state.creator_claimed
-        != input_state
-            .creator_claimed
-            .saturating_add(creator_claimed_delta)
+    // The relocked output must run this same code.
+    let output_lock = load_cell_lock(rotation.output_index, Source::Output).map_err(|_| Error::InvalidRotation)?;
+    if output_lock.code_hash().as_slice() != script.code_hash().as_slice()
+        || output_lock.hash_type().as_slice() != script.hash_type().as_slice()
     {
-        return Err(Error::InvalidCreatorClaimedDelta);
+        return Err(Error::InvalidRotation);
     }
 
-    Ok(())
-}
-
-/// Calculates the vested amount based on epoch progression.
-/// Implements linear vesting with cliff period support.
-fn calculate_vested_amount(
-    current_epoch: u64,
-    start_epoch: u64,
-    end_epoch: u64,
-    cliff_epoch: u64,
-    total_amount: u64,
-    creator_claimed: u64,
-) -> u64 {
-    // Post-termination: everything not claimed by creator is vested.
-    if creator_claimed > 0 {
-        return total_amount.saturating_sub(creator_claimed);
+    // Compare parsed configs so equivalent option encodings are accepted.
+    let output_args: Bytes = output_lock.args().unpack();
+    validate_args_length(&output_args)?;
+    let output_config = parse_vesting_config(&output_args)?;
+    let expected_config = expected_rotation_config(
+        vesting_config,
+        &output_config,
+        roles,
+        highest_block_from_headers,
+        rotation.operation,
+    )?;
+    if output_config != expected_config {
+        return Err(Error::InvalidRotation);
     }
 
-    // Nothing vests before start epoch.
-    if current_epoch < start_epoch {
-        return 0;
+    // Balances, claims and tranches carry over, only the tracked block advances.
+    let output_data = load_state_data(
+        rotation.output_index,
+        Source::Output,
+        state_data_offset(vesting_config, rotation.output_index, Source::Output)?,
+        Error::InvalidRotation,
+    )?;
+    let input_bytes = input_data.bytes.get(..input_data.len).unwrap_or(&[]);
+    let output_bytes = output_data.bytes.get(..output_data.len).unwrap_or(&[]);
+    if input_bytes.len() != output_bytes.len()
+        || input_bytes.get(..HIGHEST_BLOCK_SEEN_OFFSET) != output_bytes.get(..HIGHEST_BLOCK_SEEN_OFFSET)
+        || input_bytes.get(DATA_LEN..) != output_bytes.get(DATA_LEN..)
+        || read_u64(output_bytes, HIGHEST_BLOCK_SEEN_OFFSET) != highest_block_from_headers
+    {
+        return Err(Error::InvalidRotation);
     }
 
-    // Handle start >= end: instant vest at start.
-    if start_epoch >= end_epoch {
-        return total_amount;
+    // The relocked cell keeps its full capacity.
+    if load_cell_capacity(rotation.output_index, Source::Output)? < load_cell_capacity(0, Source::GroupInput)? {
+        return Err(Error::InvalidRotation);
     }
 
-    // Effective cliff cannot exceed end epoch.
-    let effective_cliff = cliff_epoch.min(end_epoch);
-    if current_epoch < effective_cliff {
-        return 0;
+    // The relocked cell keeps its type script, so a rotation cannot attach or swap one.
+    if load_cell_type_hash(rotation.output_index, Source::Output)? != load_cell_type_hash(0, Source::GroupInput)? {
+        return Err(Error::InvalidRotation);
     }
 
-    // Past end epoch = fully vested.
-    if current_epoch >= end_epoch {
-        return total_amount;
+    // UDT cells also keep their token type and every token.
+    if let Some(udt_type_hash) = &vesting_config.udt_type_hash {
+        if load_cell_type_hash(rotation.output_index, Source::Output)?.as_ref() != Some(udt_type_hash)
+            || load_udt_amount(rotation.output_index, Source::Output)? < load_udt_amount(0, Source::GroupInput)?
+        {
+            return Err(Error::InvalidRotation);
+        }
     }
 
-    let elapsed = current_epoch - start_epoch;
-    let duration = end_epoch - start_epoch;
-
-    // Prevent overflow in vesting calculations.
-    if let Some(product) = elapsed.checked_mul(total_amount) {
-        product / duration
-    } else {
-        // Fallback to full vesting on overflow.
-        total_amount
-    }
+    Ok(())
 }
 
-/// Validates that script arguments have the correct length.
-/// Ensures 88-byte argument structure.
+/// Validates that script arguments have at least the base length.
+/// Ensures the 88-byte argument structure; any extension is validated while parsing.
 fn validate_args_length(args: &Bytes) -> Result<(), Error> {
-    if args.len() != ARGS_LEN {
+    if args.len() < ARGS_LEN {
         return Err(Error::InvalidArgs);
     }
     Ok(())
 }
 
-/// Validates that input cell data has the correct length.
-/// Ensures 32-byte data structure.
-fn validate_input_data_length(data: &Bytes) -> Result<(), Error> {
-    if data.len() != DATA_LEN {
-        return Err(Error::WrongDataLength);
+/// Scans inputs once and records every role whose lock hash or auth lock pattern is present.
+/// New roles only need a field here and a comparison in the loop.
+fn collect_authorized_roles(script_hash: &[u8; 32], vesting_config: &VestingConfig) -> Result<AuthorizedRoles, Error> {
+    let mut roles = AuthorizedRoles::default();
+
+    for (index, lock_hash) in QueryIter::new(load_cell_lock_hash, Source::Input).enumerate() {
+        if lock_hash == vesting_config.creator_lock_hash && vesting_config.creator_signers.is_none() {
+            roles.creator = true;
+        }
+        if vesting_config.co_grantors.is_some_and(|co_grantors| {
+            co_grantors.lock_hashes.iter().take(co_grantors.count).any(|co_grantor| co_grantor == &lock_hash)
+        }) {
+            roles.co_grantor = true;
+        }
+        if let Some(co_beneficiaries) = &vesting_config.co_beneficiaries {
+            for (index, co_beneficiary) in co_beneficiaries.lock_hashes.iter().take(co_beneficiaries.count).enumerate() {
+                if co_beneficiary == &lock_hash {
+                    if let Some(role) = roles.co_beneficiaries.get_mut(index) {
+                        *role = true;
+                    }
+                }
+            }
+        }
+        if lock_hash == vesting_config.beneficiary_lock_hash {
+            roles.beneficiary = true;
+        }
+
+        // Pattern matching needs the full lock script, so it is only loaded when a pattern could still match.
+        let creator_pattern = vesting_config.creator_auth_lock.filter(|_| !roles.creator);
+        let beneficiary_pattern = vesting_config.beneficiary_auth_lock.filter(|_| !roles.beneficiary);
+        if (creator_pattern.is_some() || beneficiary_pattern.is_some()) && &lock_hash != script_hash {
+            let lock = load_cell_lock(index, Source::Input)?;
+            if creator_pattern.is_some_and(|pattern| lock_matches_pattern(&lock, &pattern)) {
+                roles.creator = true;
+            }
+            if beneficiary_pattern.is_some_and(|pattern| lock_matches_pattern(&lock, &pattern)) {
+                roles.beneficiary = true;
+            }
+        }
+
+        // Remaining inputs cannot change the outcome once every role is found.
+        if roles.creator && roles.beneficiary {
+            break;
+        }
     }
-    Ok(())
-}
 
-/// Determines authorization type using proxy lock pattern.
-/// Checks input cells for creator or beneficiary authorization.
-fn determine_authorization_type(vesting_config: &VestingConfig) -> Result<AuthorizationType, Error> {
-    let creator_authorized = QueryIter::new(load_cell_lock_hash, Source::Input)
-        .any(|lock_hash| lock_hash == vesting_config.creator_lock_hash);
+    // Creator signers replace the creator lock, so the role needs enough distinct signers instead.
+    if let Some(creator_signers) = &vesting_config.creator_signers {
+        roles.creator = count_approvals(creator_signers.lock_hashes.get(..creator_signers.count).unwrap_or(&[]))
+            >= creator_signers.threshold;
+    }
 
-    let beneficiary_authorized = QueryIter::new(load_cell_lock_hash, Source::Input)
-        .any(|lock_hash| lock_hash == vesting_config.beneficiary_lock_hash);
+    Ok(roles)
+}
 
+/// Determines authorization type using proxy lock pattern.
+/// The creator or a co-grantor takes precedence when both roles authorized the transaction.
+fn determine_authorization_type(roles: AuthorizedRoles) -> AuthorizationType {
     // Classify authorization based on input lock hashes.
-    let auth_type = if creator_authorized {
+    if roles.creator || roles.co_grantor {
         AuthorizationType::Creator
-    } else if beneficiary_authorized {
+    } else if roles.beneficiary || roles.co_beneficiaries.contains(&true) {
         AuthorizationType::Beneficiary
     } else {
         AuthorizationType::None
-    };
-
-    Ok(auth_type)
+    }
 }
 
 /// Validates that exactly one input cell matches the current script.
 /// Ensures single-cell processing for vesting contracts.
-fn validate_single_input_cell() -> Result<(), Error> {
-    let current_script = load_script()?;
-    let current_script_hash = current_script.calc_script_hash();
-    let mut input_count = 0;
-    let mut index = 0;
-
-    while let Ok(input_cell) = load_cell(index, Source::Input) {
-        if input_cell.lock().calc_script_hash() == current_script_hash {
-            input_count += 1;
-        }
-        index += 1;
-    }
+fn validate_single_input_cell(script_hash: &[u8; 32]) -> Result<(), Error> {
+    let input_count = QueryIter::new(load_cell_lock_hash, Source::Input)
+        .filter(|lock_hash| lock_hash == script_hash)
+        .count();
 
     if input_count != 1 {
         return Err(Error::MultipleInputsNotAllowed);
@@ -518,6 +3186,7 @@ fn validate_single_input_cell() -> Result<(), Error> {
 /// Loads and validates output cell data based on authorization type.
 /// Returns the output state and whether an output cell exists.
 fn load_output_state(
+    script_hash: &[u8; 32],
     auth_type: AuthorizationType,
     vesting_config: &VestingConfig,
     input_state: &VestingState,
@@ -525,82 +3194,115 @@ fn load_output_state(
 ) -> Result<(VestingState, bool), Error> {
     match auth_type {
         AuthorizationType::Creator => {
-            // Creator operations may terminate the cell if nothing is vested.
-            let vested_amount = calculate_vested_amount(
-                highest_epoch,
-                vesting_config.start_epoch,
-                vesting_config.end_epoch,
-                vesting_config.cliff_epoch,
-                input_state.total_amount,
-                input_state.creator_claimed,
-            );
+            // Creator operations may terminate the cell if nothing is vested, even after acceleration.
+            let vested_amount = calculate_vested_at_termination(vesting_config, input_state, highest_epoch);
 
             // If nothing is vested, creator terminates entire cell (no output).
             if vested_amount == 0 {
-                match find_matching_output_data() {
-                    Ok(_) => {
-                        // Output exists when it shouldn't for full termination.
-                        return Err(Error::CreatorFullTerminationHasOutput);
-                    }
-                    Err(_) => {
+                match find_matching_output_data(script_hash, vesting_config, Error::OutputDataWrongLength) {
+                    Err(Error::NoMatchingOutputCell) => {
                         // No output - correct for full termination.
                         Ok((VestingState {
                             total_amount: input_state.total_amount,
                             beneficiary_claimed: input_state.beneficiary_claimed,
                             creator_claimed: input_state.total_amount, // Claimed everything
                             highest_block_seen: input_state.highest_block_seen,
+                            tranches: input_state.tranches,
+                            tranche_count: input_state.tranche_count,
+                            extension: input_state.extension,
+                            extension_len: input_state.extension_len,
                         }, false))
                     }
+                    _ => {
+                        // Output exists when it shouldn't for full termination.
+                        Err(Error::CreatorFullTerminationHasOutput)
+                    }
                 }
             } else {
                 // Partial termination requires output cell.
-                let output_data = find_matching_output_data()?;
-                if output_data.len() != DATA_LEN {
-                    return Err(Error::OutputDataWrongLength);
-                }
+                let output_data = find_matching_output_data(script_hash, vesting_config, Error::OutputDataWrongLength)?;
                 Ok((parse_vesting_state(&output_data)?, true))
             }
         }
         AuthorizationType::None => {
             // Anonymous operations require cell continuation.
-            let output_data = find_matching_output_data()?;
-            if output_data.len() != DATA_LEN {
-                return Err(Error::OutputDataWrongLength);
-            }
+            let output_data = find_matching_output_data(script_hash, vesting_config, Error::OutputDataWrongLength)?;
             Ok((parse_vesting_state(&output_data)?, true))
         }
         AuthorizationType::Beneficiary => {
             // Beneficiary operations may continue or consume the cell.
-            match find_matching_output_data() {
-                Ok(output_data) => {
-                    if output_data.len() != DATA_LEN {
-                        return Err(Error::WrongDataLength);
-                    }
-                    Ok((parse_vesting_state(&output_data)?, true))
-                }
-                Err(_) => {
+            match find_matching_output_data(script_hash, vesting_config, Error::WrongDataLength) {
+                Ok(output_data) => Ok((parse_vesting_state(&output_data)?, true)),
+                Err(Error::NoMatchingOutputCell) => {
                     // Handle full cell consumption by beneficiary.
-                    let vested_amount = calculate_vested_amount(
-                        highest_epoch,
-                        vesting_config.start_epoch,
-                        vesting_config.end_epoch,
-                        vesting_config.cliff_epoch,
-                        input_state.total_amount,
-                        input_state.creator_claimed,
-                    );
+                    let vested_amount = calculate_total_vested(vesting_config, input_state, highest_epoch);
                     let available_to_claim = vested_amount.saturating_sub(input_state.beneficiary_claimed);
 
+                    // Before termination every tranche is claimed up to its vested amount.
+                    let mut tranches = input_state.tranches;
+                    if input_state.creator_claimed == 0 {
+                        for tranche in tranches.iter_mut().take(input_state.tranche_count) {
+                            tranche.claimed = calculate_tranche_vested(vesting_config, tranche, highest_epoch).max(tranche.claimed);
+                        }
+                    }
+
+                    // Shared cells are consumed with every co-beneficiary claimed up to its share.
+                    let mut extension = input_state.extension;
+                    if let Some(co_beneficiaries) = &vesting_config.co_beneficiaries {
+                        for index in 0..co_beneficiaries.count {
+                            let claimed = beneficiary_vested_share(co_beneficiaries, index, vested_amount)
+                                .max(beneficiary_claimed(co_beneficiaries, input_state, index));
+                            let offset = CO_BENEFICIARY_CLAIMED_OFFSET + index * CO_BENEFICIARY_CLAIMED_LEN;
+                            if let Some(counter) = extension.get_mut(offset..offset + CO_BENEFICIARY_CLAIMED_LEN) {
+                                counter.copy_from_slice(&claimed.to_le_bytes());
+                            }
+                        }
+                    }
+
                     // Create virtual state for consumption validation.
                     Ok((VestingState {
                         total_amount: input_state.total_amount,
                         beneficiary_claimed: input_state.beneficiary_claimed.saturating_add(available_to_claim),
                         creator_claimed: input_state.creator_claimed,
                         highest_block_seen: input_state.highest_block_seen,
+                        tranches,
+                        tranche_count: input_state.tranche_count,
+                        extension,
+                        extension_len: input_state.extension_len,
                     }, false))
                 }
+                Err(err) => Err(err),
+            }
+        }
+    }
+}
+
+/// Validates that the operation has something left to terminate or claim.
+/// Runs before the output requirements, which assume a non-empty operation.
+fn validate_remaining_amount(
+    auth_type: AuthorizationType,
+    vested_amount: u64,
+    total_amount: u64,
+    creator_claimed: u64,
+    beneficiary_claimed: u64,
+) -> Result<(), Error> {
+    match auth_type {
+        AuthorizationType::Creator => {
+            // Fully vested - nothing left to terminate.
+            if vested_amount > 0 && vested_amount >= total_amount {
+                return Err(Error::NothingToTerminate);
+            }
+        }
+        AuthorizationType::Beneficiary => {
+            // After termination the beneficiary may only claim what the creator left.
+            if creator_claimed > 0 && total_amount.saturating_sub(creator_claimed) <= beneficiary_claimed {
+                return Err(Error::InsufficientVested);
             }
         }
+        AuthorizationType::None => {}
     }
+
+    Ok(())
 }
 
 /// Validates output requirements based on authorization and vesting state.
@@ -611,7 +3313,6 @@ fn validate_output_requirements(
     vested_amount: u64,
     total_amount: u64,
     creator_claimed: u64,
-    beneficiary_claimed: u64,
 ) -> Result<(), Error> {
     match auth_type {
         AuthorizationType::Creator => {
@@ -625,25 +3326,14 @@ fn validate_output_requirements(
                 if !has_output {
                     return Err(Error::CreatorOperationMissingOutput);
                 }
-            } else {
-                // Fully vested - nothing left to terminate.
-                return Err(Error::NothingToTerminate);
             }
         }
         AuthorizationType::Beneficiary => {
             // In post-termination scenarios, beneficiary can claim everything not taken by creator.
             if creator_claimed > 0 {
-                let remaining_amount = total_amount.saturating_sub(creator_claimed);
-                let claimable_amount = remaining_amount.saturating_sub(beneficiary_claimed);
-
-                if claimable_amount == 0 {
-                    // Nothing left to claim - should not reach here with valid transaction.
-                    return Err(Error::InsufficientVested);
-                } else {
-                    // Beneficiary can claim remaining amount and consume cell.
-                    if has_output {
-                        return Err(Error::BeneficiaryFullClaimHasOutput);
-                    }
+                // Beneficiary can claim remaining amount and consume cell.
+                if has_output {
+                    return Err(Error::BeneficiaryFullClaimHasOutput);
                 }
             } else {
                 // Normal vesting scenario - check based on vested amount.
@@ -678,79 +3368,286 @@ pub fn main() -> Result<(), Error> {
     let script = load_script()?;
     let args: Bytes = script.args().unpack();
 
+    // Compute the script hash once for locating this script's cells.
+    let script_hash: [u8; 32] = script.calc_script_hash().unpack();
+
     validate_args_length(&args)?;
 
     // Parse vesting configuration from arguments.
     let vesting_config = parse_vesting_config(&args)?;
 
     // Determine authorization type using proxy lock pattern.
-    let auth_type = determine_authorization_type(&vesting_config)?;
+    let roles = collect_authorized_roles(&script_hash, &vesting_config)?;
+    let auth_type = determine_authorization_type(roles);
 
     // Validate single input cell requirement.
-    validate_single_input_cell()?;
+    validate_single_input_cell(&script_hash)?;
 
     // Load and validate input cell state.
-    let input_data = find_matching_input_data()?;
-    validate_input_data_length(&input_data)?;
+    let input_data = find_matching_input_data(&script_hash, &vesting_config)?;
     let input_state = parse_vesting_state(&input_data)?;
 
+    // Shared cells carry a claimed counter for every co-beneficiary, and merkle cells the root of their tree.
+    validate_co_beneficiary_extension(&vesting_config, &input_state)?;
+    validate_merkle_extension(&vesting_config, &input_state)?;
+
+    // UDT cells must carry the configured token type.
+    if let Some(udt_type_hash) = &vesting_config.udt_type_hash {
+        validate_udt_cell(udt_type_hash, 0, Source::GroupInput)?;
+    }
+
+    // Basis-point cells count every amount in basis points of their backing capacity.
+    if vesting_config.bps_reserve.is_some() && input_state.total_amount as u128 != BPS_DENOMINATOR {
+        return Err(Error::InvalidBasisPointBacking);
+    }
+
+    // A settled cell has nothing left to vest or reclaim, so anyone may sweep it without headers.
+    if is_settled(&input_state)
+        && !QueryIter::new(load_cell_lock_hash, Source::Output).any(|lock_hash| lock_hash == script_hash)
+    {
+        validate_sweep_witness()?;
+        return validate_dust_sweep(&vesting_config);
+    }
+
     // Collect block and epoch data from transaction.
-    let highest_block_from_inputs = get_highest_block_from_inputs()?;
-    let highest_block_from_headers = get_highest_block_from_headers()?;
-    let highest_epoch = get_highest_epoch_from_headers()?;
-
-    // Validate header dependencies and freshness.
-    validate_headers_exist()?;
-    validate_header_freshness(highest_block_from_inputs, highest_block_from_headers)?;
-
-    // Calculate vested amount for validation logic.
-    let vested_amount = calculate_vested_amount(
-        highest_epoch,
-        vesting_config.start_epoch,
-        vesting_config.end_epoch,
-        vesting_config.cliff_epoch,
-        input_state.total_amount,
-        input_state.creator_claimed,
-    );
+    let highest_block_from_inputs = get_highest_block_from_inputs(&script_hash, &vesting_config)?;
+    let (highest_block_from_headers, header_epoch, vesting_epoch) = if vesting_config.since_timing {
+        // Since-timed cells read the epoch from the input's since and record its point instead of a block.
+        let since_point = load_since_point()?;
+        validate_since_freshness(highest_block_from_inputs, since_point)?;
+        (since_point, since_point, since_point)
+    } else {
+        let (highest_block_from_headers, header_epoch, header_block) =
+            get_highest_block_and_epoch_from_headers(vesting_config.min_header_deps)?;
+
+        // Validate header freshness.
+        validate_header_freshness(highest_block_from_inputs, highest_block_from_headers)?;
+
+        // Bound how far a single transition may advance the tracked block.
+        validate_block_advance(highest_block_from_inputs, highest_block_from_headers, vesting_config.max_block_advance)?;
+
+        // Block-scheduled cells measure their schedule in block numbers instead of epochs.
+        let vesting_epoch = if vesting_config.schedule_in_blocks { schedule_point(header_block) } else { header_epoch };
+        (highest_block_from_headers, header_epoch, vesting_epoch)
+    };
+
+    let witness_args = load_vesting_witness_args()?;
+
+    // Merkle cells pay out claims proven against their tree; otherwise only anonymous updates apply, as nothing can be terminated.
+    if let Some(merkle_beneficiaries) = &vesting_config.merkle_beneficiaries {
+        if let Some(claim) = parse_merkle_claim(witness_args.as_ref(), merkle_beneficiaries)? {
+            return process_merkle_claim(
+                &script_hash,
+                &vesting_config,
+                &input_state,
+                witness_args.as_ref(),
+                &claim,
+                vesting_epoch,
+                highest_block_from_headers,
+            );
+        }
+        if !matches!(auth_type, AuthorizationType::None) {
+            return Err(Error::Unauthorized);
+        }
+    }
+
+    // A creator buyout ends the schedule for an agreed payment to the beneficiary.
+    if let Some(buyout_amount) = parse_buyout(witness_args.as_ref())? {
+        // Buyouts emit no claim or termination event.
+        if witness_args
+            .as_ref()
+            .is_some_and(|witness_args| witness_args.output_type().to_opt().is_some())
+        {
+            return Err(Error::InvalidWitness);
+        }
+        let payout_lock_hash = parse_payout_lock_hash(witness_args.as_ref(), &script_hash)?;
+        validate_creator_buyout(&script_hash, &vesting_config, roles, &input_state, vesting_epoch, buyout_amount)?;
+        return validate_buyout_payment(
+            &vesting_config,
+            &payout_lock_hash.unwrap_or(vesting_config.beneficiary_lock_hash),
+            buyout_amount,
+        );
+    }
+
+    // Role rotations relock the cell under new args instead of continuing it.
+    if let Some(rotation) = parse_rotation(witness_args.as_ref())? {
+        // Rotations pay nothing out and emit no claim or termination event.
+        if witness_args.as_ref().is_some_and(|witness_args| {
+            witness_args.lock().to_opt().is_some() || witness_args.output_type().to_opt().is_some()
+        }) {
+            return Err(Error::InvalidWitness);
+        }
+        return validate_role_rotation(
+            &script,
+            &script_hash,
+            &vesting_config,
+            roles,
+            &input_data,
+            highest_block_from_headers,
+            rotation,
+        );
+    }
+
+    // Calculate vested amount for validation logic; terminations count the accelerated share.
+    let vested_amount = match auth_type {
+        AuthorizationType::Creator => calculate_vested_at_termination(&vesting_config, &input_state, vesting_epoch),
+        _ => calculate_total_vested(&vesting_config, &input_state, vesting_epoch),
+    };
 
     // Load and validate output cell data based on operation type.
     let (output_state, has_output) = load_output_state(
+        &script_hash,
         auth_type,
         &vesting_config,
         &input_state,
-        highest_epoch,
+        vesting_epoch,
     )?;
 
+    // A single transition is either a claim or a termination, never both.
+    if output_state.beneficiary_claimed > input_state.beneficiary_claimed
+        && output_state.creator_claimed > input_state.creator_claimed
+    {
+        return Err(Error::SimultaneousClaimAndTermination);
+    }
+
+    // Only claims on shared cells update the extension area, whose counters the claim validation checks.
+    let claim_updates_extension =
+        vesting_config.co_beneficiaries.is_some() && matches!(auth_type, AuthorizationType::Beneficiary);
+    if has_output {
+        validate_co_beneficiary_extension(&vesting_config, &output_state)?;
+    }
+    if has_output
+        && !claim_updates_extension
+        && (output_state.extension_len != input_state.extension_len || output_state.extension != input_state.extension)
+    {
+        return Err(Error::InvalidDataExtension);
+    }
+
     // Validate block number progression and consistency only when there's an actual output.
     if has_output {
         validate_highest_block_update(&input_state, &output_state, highest_block_from_headers)?;
     }
 
-    // Validate output requirements based on authorization and vesting state.
-    validate_output_requirements(
+    // Reject operations with nothing left to terminate or claim.
+    validate_remaining_amount(
         auth_type,
-        has_output,
         vested_amount,
         input_state.total_amount,
         input_state.creator_claimed,
         input_state.beneficiary_claimed,
     )?;
 
+    // Validate output requirements based on authorization and vesting state; shared cells continue until settled.
+    if claim_updates_extension {
+        validate_shared_claim_output(has_output, &output_state)?;
+    } else {
+        validate_output_requirements(
+            auth_type,
+            has_output,
+            vested_amount,
+            input_state.total_amount,
+            input_state.creator_claimed,
+        )?;
+    }
+
+    // Payout redirection is only meaningful for beneficiary claims.
+    let payout_lock_hash = parse_payout_lock_hash(witness_args.as_ref(), &script_hash)?;
+    if payout_lock_hash.is_some() && !matches!(auth_type, AuthorizationType::Beneficiary) {
+        return Err(Error::InvalidWitness);
+    }
+
     // Execute authorization-specific validation logic.
     match auth_type {
         AuthorizationType::Creator => {
-            // Validate creator termination operation.
-            validate_creator_termination(&vesting_config, &input_state, &output_state, highest_epoch)?;
+            // Validate creator termination operation and where the reclaimed funds go.
+            validate_creator_termination(&vesting_config, &input_state, &output_state, vesting_epoch)?;
+            validate_payout_output_data(&vesting_config.creator_lock_hash, &vesting_config)?;
+
+            // Jointly funded grants split the reclaimed amount among the grantors.
+            if let Some(co_grantors) = vesting_config.co_grantors {
+                let reclaimed_amount = output_state
+                    .creator_claimed
+                    .saturating_sub(input_state.creator_claimed);
+                validate_co_grantor_split(&vesting_config, &co_grantors, &input_state, reclaimed_amount)?;
+            }
         }
         AuthorizationType::Beneficiary => {
             // Validate beneficiary claim operation.
-            validate_beneficiary_claim(&vesting_config, &input_state, &output_state, highest_epoch)?;
+            validate_beneficiary_claim(&vesting_config, &input_state, &output_state, vesting_epoch)?;
+
+            // Shared cells track each beneficiary's claims, and a claimant may only take its own share.
+            let mut claimant_lock_hash = vesting_config.beneficiary_lock_hash;
+            if let Some(co_beneficiaries) = &vesting_config.co_beneficiaries {
+                if let Some(claimant) = validate_co_beneficiary_counters(co_beneficiaries, &input_state, &output_state)? {
+                    validate_claimant_authorized(roles, co_beneficiaries, claimant)?;
+                    validate_beneficiary_share(co_beneficiaries, &output_state, claimant, vested_amount)?;
+                    claimant_lock_hash = beneficiary_lock_hash(&vesting_config, co_beneficiaries, claimant);
+                }
+            }
+
+            // Claims come in whole units; the final claim consumes the cell and may take any remainder.
+            if let Some(claim_unit) = vesting_config.claim_unit.filter(|_| has_output) {
+                validate_claim_unit(claim_unit, &input_state, &output_state)?;
+            }
+
+            // Redirected claims must actually pay the claimed amount to the payout lock.
+            if let Some(payout_lock_hash) = payout_lock_hash {
+                let claimed_amount = output_state
+                    .beneficiary_claimed
+                    .saturating_sub(input_state.beneficiary_claimed);
+                let payout_amount = match vesting_config.bps_reserve {
+                    Some(reserve) => bps_released_capacity(reserve, &input_state, claimed_amount)?,
+                    None => claimed_amount,
+                };
+                validate_payout_outputs(&payout_lock_hash, &claimant_lock_hash, &vesting_config, payout_amount)?;
+            }
+
+            // Unredirected UDT claims must deliver the claimed tokens to the claimant's lock.
+            if let (Some(udt_type_hash), None) = (&vesting_config.udt_type_hash, payout_lock_hash) {
+                let claimed_amount = output_state
+                    .beneficiary_claimed
+                    .saturating_sub(input_state.beneficiary_claimed);
+                let claimed_tokens = udt_tokens(claimed_amount, vesting_config.udt_unit);
+                validate_udt_claim_delivery(&claimant_lock_hash, udt_type_hash, vesting_config.cheque_lock.as_ref(), claimed_tokens)?;
+            }
+
+            validate_payout_output_data(&payout_lock_hash.unwrap_or(claimant_lock_hash), &vesting_config)?;
         }
         AuthorizationType::None => {
             // Validate anonymous block update operation.
             validate_block_update_only(&input_state, &output_state)?;
+
+            // Updates must be spaced out by the configured interval.
+            validate_update_interval(&input_state, &output_state, vesting_config.min_update_interval)?;
+
+            // Only the keeper tip may leave the cell during an update.
+            validate_keeper_tip(&script_hash, &vesting_config, &input_state)?;
+        }
+    }
+
+    // Basis-point cells release capacity in proportion to the basis points claimed.
+    if let Some(reserve) = vesting_config.bps_reserve {
+        if has_output && !matches!(auth_type, AuthorizationType::None) {
+            validate_bps_backing(&script_hash, reserve, &input_state, &output_state)?;
+        }
+    }
+
+    // UDT cells release tokens only as they are claimed.
+    if let Some(udt_type_hash) = &vesting_config.udt_type_hash {
+        if has_output {
+            validate_udt_backing(&script_hash, udt_type_hash, &vesting_config, &input_state, &output_state)?;
         }
     }
 
+    // Validate the optional indexer event against the validated transition.
+    validate_event(
+        witness_args.as_ref(),
+        auth_type,
+        &input_state,
+        &output_state,
+        header_epoch / SCHEDULE_POINT_SCALE,
+        highest_block_from_headers,
+    )?;
+
     Ok(())
 }
@@ -8,7 +8,7 @@ use ckb_testtool::{
 };
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 #[cfg(test)]
@@ -90,6 +90,30 @@ impl Loader {
     }
 }
 
+// Writes a transaction to the failed_txs folder as mock transaction JSON and
+// returns the file path. Tests call it from an assertion message, so a dump is
+// only written when the assertion actually fails.
+pub fn dump_mock_tx(context: &Context, tx: &TransactionView) -> PathBuf {
+    let mut path = env::current_dir().expect("current dir");
+    path.push("failed_txs");
+    std::fs::create_dir_all(&path).expect("create failed_txs dir");
+    let mock_tx = context.dump_tx(tx).expect("dump failed tx");
+    let json = serde_json::to_string_pretty(&mock_tx).expect("json");
+    path.push(format!("0x{:x}.json", tx.hash()));
+    std::fs::write(&path, json).expect("write");
+    path
+}
+
+// Returns the ckb-debugger command that replays the lock script group of the
+// first input in a dumped mock transaction. Tests put the vesting cell first,
+// so this is the vesting lock.
+pub fn debugger_command(tx_file: &Path) -> String {
+    format!(
+        "ckb-debugger --tx-file {} --cell-index 0 --cell-type input --script-group-type lock",
+        tx_file.display()
+    )
+}
+
 // This helper method runs Context::verify_tx, but in case error happens,
 // it also dumps current transaction to failed_txs folder.
 pub fn verify_and_dump_failed_tx(
@@ -99,14 +123,8 @@ pub fn verify_and_dump_failed_tx(
 ) -> Result<Cycle, Error> {
     let result = context.verify_tx(tx, max_cycles);
     if result.is_err() {
-        let mut path = env::current_dir().expect("current dir");
-        path.push("failed_txs");
-        std::fs::create_dir_all(&path).expect("create failed_txs dir");
-        let mock_tx = context.dump_tx(tx).expect("dump failed tx");
-        let json = serde_json::to_string_pretty(&mock_tx).expect("json");
-        path.push(format!("0x{:x}.json", tx.hash()));
+        let path = dump_mock_tx(context, tx);
         println!("Failed tx written to {path:?}");
-        std::fs::write(path, json).expect("write");
     }
     result
 }
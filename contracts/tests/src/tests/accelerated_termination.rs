@@ -0,0 +1,199 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const OPTION_TERMINATION_ACCELERATION: u8 = 0x0e;
+
+/// Party that authorizes the transaction.
+#[derive(Clone, Copy)]
+enum Signer {
+    Beneficiary,
+    Creator,
+}
+
+/// Builds and verifies a transition of a 10000 grant over epochs 100 to 300 with a cliff at 120.
+/// The acceleration option value is passed raw; output_data None consumes the cell.
+fn verify_with_acceleration(
+    acceleration: Vec<u8>,
+    signer: Signer,
+    epoch: u64,
+    input_data: Bytes,
+    output_data: Option<Bytes>,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            creator_hash,
+            beneficiary_hash,
+            100, // start_epoch
+            300, // end_epoch
+            120, // cliff_epoch
+        ),
+        &[(OPTION_TERMINATION_ACCELERATION, acceleration)],
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, epoch);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        input_data,
+    );
+
+    let auth_lock = match signer {
+        Signer::Beneficiary => beneficiary_lock,
+        Signer::Creator => creator_lock,
+    };
+    let auth_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(auth_lock)
+            .build(),
+        Bytes::new(),
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(auth_input_out_point).build())
+        .header_dep(header_hash);
+
+    if let Some(output_data) = output_data {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(10161u64.pack())
+                .lock(lock_script)
+                .build())
+            .output_data(output_data.pack());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that termination vests the accelerated share of the unvested amount.
+/// At epoch 200 with 50% acceleration: unvested = 5000, accelerated = 2500, reclaimed = 2500.
+#[test]
+fn test_termination_reclaims_unaccelerated_share() {
+    let result = verify_with_acceleration(
+        5000u16.to_le_bytes().to_vec(),
+        Signer::Creator,
+        200,
+        create_vesting_data(10000, 0, 0, 200),
+        Some(create_vesting_data(10000, 0, 2500, 201)),
+    );
+    assert!(result.is_ok(), "Should succeed - creator reclaims the unaccelerated share, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the creator cannot reclaim the accelerated share.
+/// Reclaiming the whole unvested 5000 ignores the agreed acceleration.
+#[test]
+fn test_termination_reclaiming_accelerated_share_rejected() {
+    let result = verify_with_acceleration(
+        5000u16.to_le_bytes().to_vec(),
+        Signer::Creator,
+        200,
+        create_vesting_data(10000, 0, 0, 200),
+        Some(create_vesting_data(10000, 0, 5000, 201)),
+    );
+    assert!(result.is_err(), "Should fail - creator reclaimed the accelerated share");
+    assert_eq!(extract_error_code(&result), Some(20)); // Error::InvalidAmount
+}
+
+/// Tests that the accelerated share is rounded down, leaving the remainder to the creator.
+/// At epoch 200 with 33.33% acceleration: accelerated = 5000 * 3333 / 10000 = 1666, reclaimed = 3334.
+#[test]
+fn test_accelerated_share_rounds_down() {
+    let result = verify_with_acceleration(
+        3333u16.to_le_bytes().to_vec(),
+        Signer::Creator,
+        200,
+        create_vesting_data(10000, 0, 0, 200),
+        Some(create_vesting_data(10000, 0, 3334, 201)),
+    );
+    assert!(result.is_ok(), "Should succeed - accelerated share rounded down, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that termination before the cliff keeps the accelerated share in a continuation cell.
+/// At epoch 110 nothing has vested, yet 50% acceleration leaves 5000 for the beneficiary.
+#[test]
+fn test_termination_before_cliff_requires_continuation() {
+    let result = verify_with_acceleration(
+        5000u16.to_le_bytes().to_vec(),
+        Signer::Creator,
+        110,
+        create_vesting_data(10000, 0, 0, 200),
+        Some(create_vesting_data(10000, 0, 5000, 201)),
+    );
+    assert!(result.is_ok(), "Should succeed - accelerated share stays in the cell, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_with_acceleration(
+        5000u16.to_le_bytes().to_vec(),
+        Signer::Creator,
+        110,
+        create_vesting_data(10000, 0, 0, 200),
+        None,
+    );
+    assert!(result.is_err(), "Should fail - full termination would take the accelerated share");
+    assert_eq!(extract_error_code(&result), Some(37)); // Error::CreatorOperationMissingOutput
+}
+
+/// Tests that the beneficiary claims the accelerated share right after termination.
+/// After a 2500 reclaim everything else, including the accelerated 2500, is vested.
+#[test]
+fn test_beneficiary_claims_accelerated_share() {
+    let result = verify_with_acceleration(
+        5000u16.to_le_bytes().to_vec(),
+        Signer::Beneficiary,
+        200,
+        create_vesting_data(10000, 0, 2500, 200),
+        None,
+    );
+    assert!(result.is_ok(), "Should succeed - beneficiary claims the remaining 7500, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that acceleration does not change the vested amount before termination.
+/// At epoch 200 the beneficiary still cannot claim more than the linear 5000.
+#[test]
+fn test_acceleration_does_not_affect_claims_before_termination() {
+    let result = verify_with_acceleration(
+        5000u16.to_le_bytes().to_vec(),
+        Signer::Beneficiary,
+        200,
+        create_vesting_data(10000, 0, 0, 200),
+        Some(create_vesting_data(10000, 5001, 0, 201)),
+    );
+    assert!(result.is_err(), "Should fail - acceleration only applies on termination");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that invalid acceleration option values are rejected.
+/// The share must be two bytes between 1 and 9999 bps.
+#[test]
+fn test_invalid_acceleration_rejected() {
+    let cases: [(&str, Vec<u8>); 3] = [
+        ("zero share", 0u16.to_le_bytes().to_vec()),
+        ("full share", 10000u16.to_le_bytes().to_vec()),
+        ("wrong length", vec![0x88]),
+    ];
+
+    for (label, acceleration) in cases {
+        let result = verify_with_acceleration(
+            acceleration,
+            Signer::Creator,
+            200,
+            create_vesting_data(10000, 0, 0, 200),
+            Some(create_vesting_data(10000, 0, 2500, 201)),
+        );
+        assert!(result.is_err(), "Case {} should fail", label);
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS), "Case {} should fail with invalid args", label);
+    }
+}
\ No newline at end of file
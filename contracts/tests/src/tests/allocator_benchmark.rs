@@ -0,0 +1,237 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::{TransactionBuilder, TransactionView}, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+// The bump allocator variant is built separately with:
+// make run CONTRACT=vesting_lock TASK=build-bump
+// The benchmarks are ignored by default and run with:
+// cargo test allocator_benchmark -- --ignored --nocapture
+const DEFAULT_BINARY: &str = "vesting_lock";
+const BUMP_BINARY: &str = "vesting_lock_bump";
+
+// Total heap available to either allocator (4 KiB fixed blocks + 64 KiB buddy heap).
+const HEAP_SIZE: usize = 68 * 1024;
+// The bump build never reuses memory, but the buddy allocator can fragment, so the
+// measured peak must leave half of the heap spare before the heap sizes are trusted.
+const HEAP_BUDGET: usize = HEAP_SIZE / 2;
+
+/// Builds a partial beneficiary claim against the given contract binary.
+/// At epoch 200 half of the grant is vested and the beneficiary claims all of it.
+fn build_claim(context: &mut Context, binary: &str) -> TransactionView {
+    let contract_bin: Bytes = Loader::default().load_binary(binary);
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(5161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 5000, 0, 201).pack())
+        .output(CellOutput::new_builder()
+            .capacity(5000u64.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(header_hash)
+        .build();
+    context.complete_tx(tx)
+}
+
+/// Builds an anonymous block update against the given contract binary.
+/// Only highest_block_seen advances, as a keeper would submit it.
+fn build_update(context: &mut Context, binary: &str) -> TransactionView {
+    let contract_bin: Bytes = Loader::default().load_binary(binary);
+    let out_point = context.deploy_cell(contract_bin);
+
+    let args = create_vesting_args(
+        create_dummy_lock_hash(1),
+        create_dummy_lock_hash(2),
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(context, 251, 250);
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 251).pack())
+        .header_dep(header_hash)
+        .build();
+    context.complete_tx(tx)
+}
+
+/// Builds a beneficiary claim with many header dependencies and large unrelated inputs.
+/// This is the heaviest transaction shape the heap has to absorb, shared with the edge case tests.
+pub fn build_heavy_claim(context: &mut Context, binary: &str) -> TransactionView {
+    let contract_bin: Bytes = Loader::default().load_binary(binary);
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build());
+
+    // Add unrelated inputs carrying large data alongside the beneficiary authorization.
+    for index in 0..16u8 {
+        let (other_lock, _other_hash) = create_always_success_lock_with_args(context, vec![100u8, index]);
+        let other_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(6100000000u64.pack())
+                .lock(other_lock)
+                .build(),
+            Bytes::from(vec![index; 2048]),
+        );
+        builder = builder.input(CellInput::new_builder().previous_output(other_out_point).build());
+    }
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+    builder = builder.input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build());
+
+    // Add many header dependencies, the last one being the freshest.
+    for offset in 0..24u64 {
+        let header_hash = setup_header_with_block_and_epoch(context, 178 + offset, 177 + offset);
+        builder = builder.header_dep(header_hash);
+    }
+
+    // Highest header: block 201, epoch 200. Vested = (200-100)/(300-100) * 10000 = 5000.
+    let tx = builder
+        .output(CellOutput::new_builder()
+            .capacity(5161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 5000, 0, 201).pack())
+        .output(CellOutput::new_builder()
+            .capacity(5000u64.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .build();
+    context.complete_tx(tx)
+}
+
+/// Verifies a scenario with both allocator builds and prints cycles and peak heap side by side.
+/// Returns the bump build's reported peak heap so callers can assert on it.
+fn compare_allocators(name: &str, build: fn(&mut Context, &str) -> TransactionView) -> usize {
+    let mut default_context = Context::default();
+    let default_tx = build(&mut default_context, DEFAULT_BINARY);
+    let default_cycles = default_context.verify_tx(&default_tx, MAX_CYCLES).expect("default allocator build should pass");
+
+    let mut bump_context = Context::default();
+    bump_context.set_capture_debug(true);
+    let bump_tx = build(&mut bump_context, BUMP_BINARY);
+    let bump_cycles = bump_context.verify_tx(&bump_tx, MAX_CYCLES).expect("bump allocator build should pass");
+
+    // Only the vesting lock emits this line; the proxy authorization locks stay silent.
+    let peak_heap = bump_context
+        .captured_messages()
+        .iter()
+        .find_map(|message| {
+            message.message
+                .strip_prefix("heap peak: ")
+                .and_then(|rest| rest.strip_suffix(" bytes"))
+                .and_then(|bytes| bytes.parse::<usize>().ok())
+        })
+        .expect("bump allocator build should report its peak heap");
+
+    println!(
+        "{}: default_alloc {} cycles, bump {} cycles, peak heap {} of {} bytes",
+        name, default_cycles, bump_cycles, peak_heap, HEAP_SIZE
+    );
+    peak_heap
+}
+
+/// Benchmarks a partial beneficiary claim under both allocator builds.
+/// The peak heap must fit the configured heap, otherwise shrinking it would break claims.
+#[test]
+#[ignore]
+fn test_allocator_benchmark_claim() {
+    let peak_heap = compare_allocators("claim", build_claim);
+    assert!(peak_heap <= HEAP_BUDGET, "Claim peak heap {} exceeds the {} byte budget", peak_heap, HEAP_BUDGET);
+}
+
+/// Benchmarks an anonymous block update under both allocator builds.
+/// Keeper updates are the most frequent transactions, so their heap use matters most.
+#[test]
+#[ignore]
+fn test_allocator_benchmark_update() {
+    let peak_heap = compare_allocators("update", build_update);
+    assert!(peak_heap <= HEAP_BUDGET, "Update peak heap {} exceeds the {} byte budget", peak_heap, HEAP_BUDGET);
+}
+
+/// Benchmarks the heavy claim from the edge case tests under both allocator builds.
+/// It loads the most headers and inputs, so it sets the peak the heap sizes are chosen from.
+#[test]
+#[ignore]
+fn test_allocator_benchmark_heavy_claim() {
+    let peak_heap = compare_allocators("heavy claim", build_heavy_claim);
+    assert!(peak_heap <= HEAP_BUDGET, "Heavy claim peak heap {} exceeds the {} byte budget", peak_heap, HEAP_BUDGET);
+}
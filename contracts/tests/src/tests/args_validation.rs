@@ -2,6 +2,13 @@ use super::helpers::*;
 use crate::Loader;
 use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
 use ckb_testtool::context::Context;
+use serde_json::{json, Value};
+
+// Size of the stripped contract binary as last recorded from the built binary.
+const CONTRACT_BINARY_SIZE: &str = "binary_size.json";
+
+// Growth allowed above the recorded size before the size check fails.
+const CONTRACT_BINARY_SIZE_MARGIN_PERCENT: usize = 10;
 
 /// Tests that the vesting lock script properly rejects transactions with invalid argument lengths.
 /// The lock script expects exactly 88 bytes of arguments (creator hash + beneficiary hash + epochs).
@@ -157,6 +164,100 @@ fn test_invalid_cell_data_length() {
     );
 }
 
+/// Tests that oversized input cell data is rejected without being loaded in full.
+/// Trailing bytes after the 32-byte state must not be accepted.
+#[test]
+fn test_oversized_input_cell_data_rejected() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let args = create_vesting_args(
+        create_dummy_lock_hash(1),
+        create_dummy_lock_hash(2),
+        100, // start_epoch
+        200, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 151, 150);
+
+    // Valid state followed by a large attacker-attached payload.
+    let mut oversized_data = create_vesting_data(1000, 0, 0, 100).to_vec();
+    oversized_data.extend_from_slice(&[0xffu8; 4096]);
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        Bytes::from(oversized_data),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(1000, 0, 0, 151).pack())
+        .header_dep(header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - oversized input cell data");
+    assert_eq!(extract_error_code(&result), Some(32)); // Error::WrongDataLength
+}
+
+/// Tests that a stray trailing byte in output cell data is rejected for anonymous updates.
+/// A byte after the 32-byte state is read as a tranche count, which must introduce records or an extension area.
+#[test]
+fn test_oversized_output_cell_data_rejected() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let args = create_vesting_args(
+        create_dummy_lock_hash(1),
+        create_dummy_lock_hash(2),
+        100, // start_epoch
+        200, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 151, 150);
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(1000, 0, 0, 100),
+    );
+
+    // Valid updated state followed by one extra byte.
+    let mut oversized_data = create_vesting_data(1000, 0, 0, 151).to_vec();
+    oversized_data.push(0);
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(Bytes::from(oversized_data).pack())
+        .header_dep(header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - oversized output cell data");
+    assert_eq!(extract_error_code(&result), Some(47)); // Error::InvalidTrancheData
+}
+
 /// Tests that transactions without header dependencies are rejected.
 /// Headers are required for epoch and block number validation.
 #[test]
@@ -227,4 +328,44 @@ fn test_contract_basic_functionality() {
     let _out_point = context.deploy_cell(contract_bin);
 
     assert!(contract_bin_len > 0, "Contract binary should not be empty");
-}
\ No newline at end of file
+}
+
+/// Returns the size cap of the stripped contract binary: its recorded size plus the margin.
+fn contract_binary_size_cap() -> usize {
+    let path = fixture_path(CONTRACT_BINARY_SIZE);
+    let file = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!("Binary size {:?} is missing; record it with `cargo test -p tests record_contract_binary_size -- --ignored`", path)
+    });
+    let recorded: Value = serde_json::from_str(&file).expect("binary size should be valid JSON");
+    let bytes = recorded["bytes"].as_u64().expect("bytes") as usize;
+    bytes + bytes * CONTRACT_BINARY_SIZE_MARGIN_PERCENT / 100
+}
+
+/// Records the size of the stripped contract binary.
+/// A measuring tool rather than a check: run with --ignored after `make build` whenever a change is meant to grow the binary.
+#[test]
+#[ignore]
+fn record_contract_binary_size() {
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let recorded = json!({
+        "bytes": contract_bin.len(),
+        "margin_percent": CONTRACT_BINARY_SIZE_MARGIN_PERCENT,
+    });
+
+    let json = serde_json::to_string_pretty(&recorded).expect("json");
+    std::fs::write(fixture_path(CONTRACT_BINARY_SIZE), json + "\n").expect("write binary size");
+}
+
+/// Tests that the stripped contract binary stays within its recorded size plus the margin.
+/// Guards the size reduction from regressing as features are added.
+#[test]
+fn test_contract_binary_size() {
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let size_cap = contract_binary_size_cap();
+    assert!(
+        contract_bin.len() <= size_cap,
+        "Contract binary is {} bytes, limit is {} bytes",
+        contract_bin.len(),
+        size_cap
+    );
+}
@@ -0,0 +1,287 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+// JoyID-style args: a 2-byte mode followed by a 20-byte key identifier and rotating credential bytes.
+const KEY_PREFIX: [u8; 22] = [0, 1, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7];
+
+// A named malformed-args case: builds the offending args from the creator lock.
+type ArgsCase = (&'static str, fn(&Script) -> Bytes);
+
+// A named malformed-policy case: builds the offending auth policy value from the creator lock.
+type PolicyCase = (&'static str, fn(&Script) -> Vec<u8>);
+
+/// Builds a JoyID-style lock script args value from the key prefix and a credential suffix.
+/// Rotating the credential changes the suffix and therefore the lock hash.
+fn joyid_style_args(credential: u8) -> Vec<u8> {
+    let mut args = KEY_PREFIX.to_vec();
+    args.extend_from_slice(&[credential; 8]);
+    args
+}
+
+/// Builds and verifies a partial beneficiary claim of 5000 at epoch 200.
+/// The beneficiary authorizes with an always-success lock using the given args, and the
+/// vesting args are built from that lock script by the given closure.
+fn verify_claim_with_beneficiary_lock(
+    beneficiary_lock_args: Vec<u8>,
+    build_args: impl Fn(&Script, [u8; 32]) -> Bytes,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash) = create_always_success_lock_with_args(&mut context, beneficiary_lock_args);
+
+    let args = build_args(&beneficiary_lock, beneficiary_hash);
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    // At epoch 200: vested = (200-100)/(300-100) * 10000 = 5000.
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(5161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 5000, 0, 201).pack())
+        .output(CellOutput::new_builder()
+            .capacity(5000u64.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Creates base vesting args with dummy role hashes and the standard 100-300 schedule.
+/// Authorization in these tests comes from the args extension unless stated otherwise.
+fn base_args_with_beneficiary_hash(beneficiary_hash: [u8; 32]) -> Bytes {
+    create_vesting_args(
+        create_dummy_lock_hash(1),
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    )
+}
+
+/// Tests that a beneficiary authorizes through a pattern after rotating credentials.
+/// The lock hash differs from the one the grant was created for, but the key prefix matches.
+#[test]
+fn test_beneficiary_claim_with_rotated_credential() {
+    let result = verify_claim_with_beneficiary_lock(joyid_style_args(0xbb), |beneficiary_lock, _| {
+        create_vesting_args_with_options(
+            base_args_with_beneficiary_hash(create_dummy_lock_hash(2)),
+            &[(0x02, create_auth_lock_pattern(beneficiary_lock, &KEY_PREFIX))],
+        )
+    });
+    assert!(result.is_ok(), "Should succeed - rotated credential matches the pattern, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a lock with the right code but a different args prefix is not authorized.
+/// The transaction is treated as an anonymous update, which cannot change claimed amounts.
+#[test]
+fn test_pattern_prefix_mismatch_not_authorized() {
+    let mut other_key_args = joyid_style_args(0xbb);
+    other_key_args[2] = 8;
+
+    let result = verify_claim_with_beneficiary_lock(other_key_args, |beneficiary_lock, _| {
+        create_vesting_args_with_options(
+            base_args_with_beneficiary_hash(create_dummy_lock_hash(2)),
+            &[(0x02, create_auth_lock_pattern(beneficiary_lock, &KEY_PREFIX))],
+        )
+    });
+    assert!(result.is_err(), "Should fail - args prefix does not match");
+    assert_eq!(extract_error_code(&result), Some(17)); // Error::InvalidStateChange
+}
+
+/// Tests that the exact beneficiary lock hash is still accepted when a pattern is configured.
+/// Patterns add an authorization path rather than replacing the base args.
+#[test]
+fn test_exact_hash_still_accepted_with_pattern() {
+    let result = verify_claim_with_beneficiary_lock(joyid_style_args(0xbb), |beneficiary_lock, beneficiary_hash| {
+        create_vesting_args_with_options(
+            base_args_with_beneficiary_hash(beneficiary_hash),
+            &[(0x02, create_auth_lock_pattern(beneficiary_lock, &[9u8; 4]))],
+        )
+    });
+    assert!(result.is_ok(), "Should succeed - exact lock hash still authorizes, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the creator can terminate through an auth lock pattern.
+/// At epoch 200 the creator must claim exactly the unvested 5000.
+#[test]
+fn test_creator_termination_with_pattern() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (creator_lock, _creator_hash) = create_always_success_lock_with_args(&mut context, joyid_style_args(0xcc));
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            create_dummy_lock_hash(1),
+            create_dummy_lock_hash(2),
+            100, // start_epoch
+            300, // end_epoch
+            120, // cliff_epoch
+        ),
+        &[(0x01, create_auth_lock_pattern(&creator_lock, &KEY_PREFIX))],
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let creator_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(creator_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(creator_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(5161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 5000, 201).pack())
+        .output(CellOutput::new_builder()
+            .capacity(5000u64.pack())
+            .lock(creator_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_ok(), "Should succeed - creator authorized by pattern, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that malformed args extensions are rejected as invalid arguments.
+/// Covers unknown versions, unknown and repeated tags, truncated records and oversized prefixes.
+#[test]
+fn test_malformed_args_extensions_rejected() {
+    let cases: [ArgsCase; 5] = [
+        ("unknown version", |_| {
+            let mut args = base_args_with_beneficiary_hash(create_dummy_lock_hash(2)).to_vec();
+            args.push(2);
+            Bytes::from(args)
+        }),
+        ("unknown tag", |_| {
+            create_vesting_args_with_options(base_args_with_beneficiary_hash(create_dummy_lock_hash(2)), &[(0x3f, vec![0u8; 4])])
+        }),
+        ("repeated tag", |lock| {
+            create_vesting_args_with_options(
+                base_args_with_beneficiary_hash(create_dummy_lock_hash(2)),
+                &[(0x02, create_auth_lock_pattern(lock, &KEY_PREFIX)), (0x02, create_auth_lock_pattern(lock, &KEY_PREFIX))],
+            )
+        }),
+        ("truncated record", |lock| {
+            let mut args = create_vesting_args_with_options(
+                base_args_with_beneficiary_hash(create_dummy_lock_hash(2)),
+                &[(0x02, create_auth_lock_pattern(lock, &KEY_PREFIX))],
+            )
+            .to_vec();
+            args.pop();
+            Bytes::from(args)
+        }),
+        ("oversized prefix", |lock| {
+            create_vesting_args_with_options(
+                base_args_with_beneficiary_hash(create_dummy_lock_hash(2)),
+                &[(0x02, create_auth_lock_pattern(lock, &[7u8; 33]))],
+            )
+        }),
+    ];
+
+    for (label, build_args) in cases.iter() {
+        let result = verify_claim_with_beneficiary_lock(joyid_style_args(0xbb), |lock, _| build_args(lock));
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS), "Case {} should fail with invalid args", label);
+    }
+}
+
+/// Tests that the code hash policy authorizes any lock with the configured code.
+/// Suits shared lock deployments such as ACP or Omnilock where args differ per user.
+#[test]
+fn test_code_hash_policy_authorizes_any_args() {
+    let result = verify_claim_with_beneficiary_lock(vec![0x55u8; 20], |beneficiary_lock, _| {
+        create_vesting_args_with_options(
+            base_args_with_beneficiary_hash(create_dummy_lock_hash(2)),
+            &[(0x02, create_code_hash_auth_policy(beneficiary_lock))],
+        )
+    });
+    assert!(result.is_ok(), "Should succeed - code hash policy ignores args, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the explicit exact hash policy behaves like having no policy at all.
+/// A lock with matching code but a different hash is not authorized.
+#[test]
+fn test_exact_hash_policy_requires_exact_hash() {
+    let exact = verify_claim_with_beneficiary_lock(joyid_style_args(0xbb), |_, beneficiary_hash| {
+        create_vesting_args_with_options(base_args_with_beneficiary_hash(beneficiary_hash), &[(0x02, vec![0u8])])
+    });
+    assert!(exact.is_ok(), "Should succeed - exact lock hash matches, got error code: {:?}", extract_error_code(&exact));
+
+    let rotated = verify_claim_with_beneficiary_lock(joyid_style_args(0xbb), |_, _| {
+        create_vesting_args_with_options(base_args_with_beneficiary_hash(create_dummy_lock_hash(2)), &[(0x02, vec![0u8])])
+    });
+    assert_eq!(extract_error_code(&rotated), Some(17)); // Error::InvalidStateChange
+}
+
+/// Tests that malformed auth policy values are rejected as invalid arguments.
+/// Covers unknown policies and policies with unexpected trailing bytes.
+#[test]
+fn test_malformed_auth_policies_rejected() {
+    let cases: [PolicyCase; 3] = [
+        ("unknown policy", |lock| {
+            let mut value = create_code_hash_auth_policy(lock);
+            value[0] = 3;
+            value
+        }),
+        ("exact hash with fields", |_| vec![0u8, 1]),
+        ("code hash with prefix", |lock| {
+            let mut value = create_code_hash_auth_policy(lock);
+            value.push(0);
+            value
+        }),
+    ];
+
+    for (label, build_value) in cases.iter() {
+        let result = verify_claim_with_beneficiary_lock(joyid_style_args(0xbb), |lock, _| {
+            create_vesting_args_with_options(base_args_with_beneficiary_hash(create_dummy_lock_hash(2)), &[(0x02, build_value(lock))])
+        });
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS), "Case {} should fail with invalid args", label);
+    }
+}
@@ -779,7 +779,7 @@ fn test_capacity_conservation() {
     // Create beneficiary authorization input cell.
     let beneficiary_input_out_point = context.create_cell(
         CellOutput::new_builder()
-            .capacity(6100000000u64.pack()) // minimum capacity
+            .capacity(AUTH_INPUT_CAPACITY.pack()) // minimum capacity
             .lock(beneficiary_lock.clone())
             .build(),
         Bytes::new(),
@@ -790,7 +790,7 @@ fn test_capacity_conservation() {
 
     let output = CellOutput::new_builder()
         .capacity(claimed_amount.pack())
-        .lock(beneficiary_lock)
+        .lock(beneficiary_lock.clone())
         .build();
 
     let remaining_output = CellOutput::new_builder()
@@ -798,18 +798,19 @@ fn test_capacity_conservation() {
         .lock(lock_script)
         .build();
 
-    let tx = TransactionBuilder::default()
+    let builder = TransactionBuilder::default()
         .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
         .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
         .output(output)
         .output_data(Bytes::new().pack())
         .output(remaining_output)
         .output_data(create_vesting_data(8000, 4000, 0, 201).pack())
-        .header_dep(header_hash)
-        .build();
-    let tx = context.complete_tx(tx);
+        .header_dep(header_hash);
 
-    let result = context.verify_tx(&tx, MAX_CYCLES);
+    // The authorization input comes back as change, less the fee.
+    let tx = context.complete_tx(with_change_output(builder, beneficiary_lock, AUTH_INPUT_CAPACITY - TX_FEE).build());
+
+    let result = verify_with_fee(&context, &tx, TX_FEE, MAX_CYCLES);
     assert!(result.is_ok(), "Should succeed - capacity is conserved across transaction, got error code: {:?}", extract_error_code(&result));
 }
 
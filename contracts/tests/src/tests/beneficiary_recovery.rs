@@ -0,0 +1,277 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const ROTATION_INITIATE_RECOVERY: u8 = 4;
+const ROTATION_OBJECT_RECOVERY: u8 = 5;
+const ROTATION_FINALIZE_RECOVERY: u8 = 6;
+
+const OPTION_GUARDIANS: u8 = 0x09;
+const OPTION_PENDING_RECOVERY: u8 = 0x0a;
+const RECOVERY_DELAY: u64 = 100;
+const THRESHOLD: u8 = 2;
+
+/// Shape of a recovery transaction.
+struct Recovery {
+    operation: u8,
+    header_block: u64,
+    /// Guardian indexes whose cells are spent; an index may repeat.
+    guardian_inputs: Vec<usize>,
+    beneficiary_signs: bool,
+    /// Effective block of the pending recovery in the input and output args.
+    input_pending: Option<u64>,
+    output_pending: Option<u64>,
+    /// Whether the output args carry the recovered beneficiary hash.
+    output_recovered: bool,
+    /// Whether the guardian list repeats its first guardian.
+    duplicate_guardian: bool,
+    /// Whether the relocked output gets a type script the input does not have.
+    foreign_output_type: bool,
+}
+
+impl Default for Recovery {
+    fn default() -> Self {
+        Recovery {
+            operation: ROTATION_INITIATE_RECOVERY,
+            header_block: 201,
+            guardian_inputs: vec![],
+            beneficiary_signs: false,
+            input_pending: None,
+            output_pending: None,
+            output_recovered: false,
+            duplicate_guardian: false,
+            foreign_output_type: false,
+        }
+    }
+}
+
+/// Builds a guardians option record for the given guardian lock hashes.
+fn guardians_option(guardian_hashes: &[[u8; 32]]) -> (u8, Vec<u8>) {
+    let mut value = vec![THRESHOLD];
+    value.extend_from_slice(&RECOVERY_DELAY.to_le_bytes());
+    for guardian_hash in guardian_hashes.iter() {
+        value.extend_from_slice(guardian_hash);
+    }
+    (OPTION_GUARDIANS, value)
+}
+
+/// Builds a pending recovery option record for the given new lock hash and effective block.
+fn pending_option(new_lock_hash: [u8; 32], effective_block: u64) -> (u8, Vec<u8>) {
+    let mut value = new_lock_hash.to_vec();
+    value.extend_from_slice(&effective_block.to_le_bytes());
+    (OPTION_PENDING_RECOVERY, value)
+}
+
+/// Builds and verifies a recovery rotation for a cell guarded by three guardians with a threshold of two.
+/// The input tracks block 200 and the recovered beneficiary hash is create_dummy_lock_hash(9).
+fn verify_recovery(recovery: Recovery) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+    let guardians: Vec<_> = (0..3u8)
+        .map(|index| create_always_success_lock_with_args(&mut context, vec![10 + index]))
+        .collect();
+    let mut guardian_hashes: Vec<[u8; 32]> = guardians.iter().map(|(_, hash)| *hash).collect();
+    if recovery.duplicate_guardian {
+        guardian_hashes[2] = guardian_hashes[0];
+    }
+    let new_beneficiary_hash = create_dummy_lock_hash(9);
+    let output_type = if recovery.foreign_output_type {
+        Some(create_always_success_lock_with_args(&mut context, vec![0x66u8]).0)
+    } else {
+        None
+    };
+
+    let mut input_options = vec![guardians_option(&guardian_hashes)];
+    if let Some(effective_block) = recovery.input_pending {
+        input_options.push(pending_option(new_beneficiary_hash, effective_block));
+    }
+    let mut output_options = vec![guardians_option(&guardian_hashes)];
+    if let Some(effective_block) = recovery.output_pending {
+        output_options.push(pending_option(new_beneficiary_hash, effective_block));
+    }
+    let output_beneficiary_hash = if recovery.output_recovered { new_beneficiary_hash } else { beneficiary_hash };
+
+    let input_args = create_vesting_args_with_options(
+        create_vesting_args(creator_hash, beneficiary_hash, 100, 300, 120),
+        &input_options,
+    );
+    let output_args = create_vesting_args_with_options(
+        create_vesting_args(creator_hash, output_beneficiary_hash, 100, 300, 120),
+        &output_options,
+    );
+
+    let input_lock_script = context.build_script(&out_point, input_args).expect("script");
+    let output_lock_script = context.build_script(&out_point, output_args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, recovery.header_block, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(input_lock_script)
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(output_lock_script)
+            .type_(output_type.pack())
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, recovery.header_block).pack())
+        .header_dep(header_hash)
+        .witness(create_rotation_witness(recovery.operation, 0).pack());
+
+    let mut auth_locks: Vec<Script> = recovery
+        .guardian_inputs
+        .iter()
+        .map(|index| guardians[*index].0.clone())
+        .collect();
+    if recovery.beneficiary_signs {
+        auth_locks.push(beneficiary_lock);
+    }
+    for auth_lock in auth_locks {
+        let auth_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(auth_lock)
+                .build(),
+            Bytes::new(),
+        );
+        builder = builder.input(CellInput::new_builder().previous_output(auth_input_out_point).build());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that a threshold of guardians can initiate a recovery.
+/// Header block 201 plus the 100-block delay gives effective block 301.
+#[test]
+fn test_guardians_initiate_recovery() {
+    let result = verify_recovery(Recovery {
+        guardian_inputs: vec![0, 2],
+        output_pending: Some(301),
+        ..Recovery::default()
+    });
+    assert!(result.is_ok(), "Should succeed - two of three guardians approved, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that fewer guardians than the threshold cannot initiate a recovery.
+/// A single compromised guardian must not be able to start a takeover.
+#[test]
+fn test_single_guardian_cannot_initiate_recovery() {
+    let result = verify_recovery(Recovery {
+        guardian_inputs: vec![1],
+        output_pending: Some(301),
+        ..Recovery::default()
+    });
+    assert!(result.is_err(), "Should fail - below the guardian threshold");
+    assert_eq!(extract_error_code(&result), Some(25)); // Error::Unauthorized
+}
+
+/// Tests that spending several cells of one guardian counts as a single approval.
+/// Approvals are counted per guardian, not per input.
+#[test]
+fn test_repeated_guardian_counts_once() {
+    let result = verify_recovery(Recovery {
+        guardian_inputs: vec![1, 1],
+        output_pending: Some(301),
+        ..Recovery::default()
+    });
+    assert!(result.is_err(), "Should fail - one guardian spent two cells");
+    assert_eq!(extract_error_code(&result), Some(25)); // Error::Unauthorized
+}
+
+/// Tests that a guardian set listing the same lock hash twice is rejected.
+/// Such a set would let one guardian satisfy the threshold alone.
+#[test]
+fn test_duplicate_guardian_hash_rejected() {
+    let result = verify_recovery(Recovery {
+        guardian_inputs: vec![0],
+        output_pending: Some(301),
+        duplicate_guardian: true,
+        ..Recovery::default()
+    });
+    assert!(result.is_err(), "Should fail - guardian set repeats a lock hash");
+    assert_eq!(extract_error_code(&result), Some(10)); // Error::InvalidArgs
+}
+
+/// Tests that a beneficiary who still holds their key can object to a recovery.
+/// The relocked cell drops the pending record and keeps the original beneficiary.
+#[test]
+fn test_beneficiary_objects_to_recovery() {
+    let result = verify_recovery(Recovery {
+        operation: ROTATION_OBJECT_RECOVERY,
+        header_block: 250,
+        beneficiary_signs: true,
+        input_pending: Some(301),
+        ..Recovery::default()
+    });
+    assert!(result.is_ok(), "Should succeed - beneficiary objected, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that guardians cannot clear a pending recovery through an objection.
+/// Only the beneficiary can object.
+#[test]
+fn test_guardians_cannot_object() {
+    let result = verify_recovery(Recovery {
+        operation: ROTATION_OBJECT_RECOVERY,
+        header_block: 250,
+        guardian_inputs: vec![0, 1],
+        input_pending: Some(301),
+        ..Recovery::default()
+    });
+    assert!(result.is_err(), "Should fail - guardians objected");
+    assert_eq!(extract_error_code(&result), Some(25)); // Error::Unauthorized
+}
+
+/// Tests that a recovery cannot be finalized before its effective block.
+/// The beneficiary keeps the full waiting period to object.
+#[test]
+fn test_finalize_recovery_before_effective_block_rejected() {
+    let result = verify_recovery(Recovery {
+        operation: ROTATION_FINALIZE_RECOVERY,
+        header_block: 300,
+        input_pending: Some(301),
+        output_recovered: true,
+        ..Recovery::default()
+    });
+    assert!(result.is_err(), "Should fail - recovery not yet effective");
+    assert_eq!(extract_error_code(&result), Some(57)); // Error::RotationNotReady
+}
+
+/// Tests that anyone can finalize a recovery at its effective block.
+/// The relocked cell carries the recovered beneficiary hash and no pending record.
+#[test]
+fn test_finalize_recovery_at_effective_block() {
+    let result = verify_recovery(Recovery {
+        operation: ROTATION_FINALIZE_RECOVERY,
+        header_block: 301,
+        input_pending: Some(301),
+        output_recovered: true,
+        ..Recovery::default()
+    });
+    assert!(result.is_ok(), "Should succeed - recovery finalized, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a third party finalizing a recovery cannot attach a foreign type script to the relocked cell.
+/// Anyone may finalize, so the output must keep the input's type script exactly.
+#[test]
+fn test_finalize_recovery_with_foreign_type_script_rejected() {
+    let result = verify_recovery(Recovery {
+        operation: ROTATION_FINALIZE_RECOVERY,
+        header_block: 301,
+        input_pending: Some(301),
+        output_recovered: true,
+        foreign_output_type: true,
+        ..Recovery::default()
+    });
+    assert!(result.is_err(), "Should fail - the relocked cell gained a type script");
+    assert_eq!(extract_error_code(&result), Some(56)); // Error::InvalidRotation
+}
@@ -0,0 +1,191 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const ROTATION_TRANSFER_BENEFICIARY: u8 = 10;
+
+const OPTION_BENEFICIARY_AUTH_POLICY: u8 = 0x02;
+const OPTION_GUARDIANS: u8 = 0x09;
+const OPTION_PENDING_RECOVERY: u8 = 0x0a;
+
+// Args of the always-success locks standing in for each party
+const BENEFICIARY: u8 = 1;
+const CREATOR: u8 = 2;
+const NEW_WALLET: u8 = 3;
+const GUARDIAN: u8 = 4;
+
+/// Shape of a beneficiary transfer transaction.
+struct Transfer {
+    signer: u8,
+    input_options: Vec<(u8, Vec<u8>)>,
+    output_options: Vec<(u8, Vec<u8>)>,
+    /// Party whose lock hash the output args name as the beneficiary.
+    output_beneficiary: u8,
+    /// Args of an always-success type script attached to the relocked output.
+    output_type: Option<u8>,
+}
+
+impl Default for Transfer {
+    fn default() -> Self {
+        Transfer {
+            signer: BENEFICIARY,
+            input_options: Vec::new(),
+            output_options: Vec::new(),
+            output_beneficiary: NEW_WALLET,
+            output_type: None,
+        }
+    }
+}
+
+/// Builds the guardians option with a single guardian, a threshold of one and a 100-block delay.
+fn guardians_option(guardian_hash: [u8; 32]) -> (u8, Vec<u8>) {
+    let mut value = vec![1u8];
+    value.extend_from_slice(&100u64.to_le_bytes());
+    value.extend_from_slice(&guardian_hash);
+    (OPTION_GUARDIANS, value)
+}
+
+/// Builds and verifies a transfer of the beneficiary role of a 10000 grant at header block 201.
+/// The relocked output keeps the data apart from the tracked block and runs under args naming the output beneficiary.
+fn verify_transfer(transfer: Transfer) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (_beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+    let (signer_lock, _) = create_always_success_lock_with_args(&mut context, vec![transfer.signer]);
+    let (_, output_beneficiary_hash) = create_always_success_lock_with_args(&mut context, vec![transfer.output_beneficiary]);
+    let output_type = transfer
+        .output_type
+        .map(|type_args| create_always_success_lock_with_args(&mut context, vec![type_args]).0);
+
+    let input_args = create_vesting_args_with_options(
+        create_vesting_args(creator_hash, beneficiary_hash, 100, 300, 120),
+        &transfer.input_options,
+    );
+    let output_args = create_vesting_args_with_options(
+        create_vesting_args(creator_hash, output_beneficiary_hash, 100, 300, 120),
+        &transfer.output_options,
+    );
+
+    let input_lock_script = context.build_script(&out_point, input_args).expect("script");
+    let output_lock_script = context.build_script(&out_point, output_args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(input_lock_script)
+            .build(),
+        create_vesting_data(10000, 2000, 0, 200),
+    );
+
+    let signer_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(signer_lock)
+            .build(),
+        Bytes::new(),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(signer_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(output_lock_script)
+            .type_(output_type.pack())
+            .build())
+        .output_data(create_vesting_data(10000, 2000, 0, 201).pack())
+        .header_dep(header_hash)
+        .witness(create_rotation_witness(ROTATION_TRANSFER_BENEFICIARY, 0).pack())
+        .build();
+    let tx = context.complete_tx(tx);
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that the beneficiary can move the role to a new wallet.
+/// The relocked cell names the new lock and carries the claimed amount over unchanged.
+#[test]
+fn test_beneficiary_transfers_role() {
+    let result = verify_transfer(Transfer::default());
+    assert!(result.is_ok(), "Should succeed - beneficiary moves to a new wallet, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that only the current beneficiary can transfer the role.
+/// Neither the creator nor the wallet receiving the role can take it.
+#[test]
+fn test_only_beneficiary_can_transfer() {
+    for signer in [CREATOR, NEW_WALLET] {
+        let result = verify_transfer(Transfer { signer, ..Transfer::default() });
+        assert!(result.is_err(), "Signer {} should not transfer the role", signer);
+        assert_eq!(extract_error_code(&result), Some(25)); // Error::Unauthorized
+    }
+}
+
+/// Tests that a transfer must actually change the beneficiary lock hash.
+/// Relocking under the same args would be a rotation that does nothing.
+#[test]
+fn test_transfer_to_same_lock_rejected() {
+    let result = verify_transfer(Transfer { output_beneficiary: BENEFICIARY, ..Transfer::default() });
+    assert!(result.is_err(), "Should fail - the beneficiary lock is unchanged");
+    assert_eq!(extract_error_code(&result), Some(56)); // Error::InvalidRotation
+}
+
+/// Tests that a transfer drops the old beneficiary's auth policy.
+/// The policy matched the old wallet, so keeping it would let that wallet claim for the new one.
+#[test]
+fn test_transfer_drops_auth_policy() {
+    let mut context = Context::default();
+    let (beneficiary_lock, _) = create_always_success_lock_with_args(&mut context, vec![BENEFICIARY]);
+    let policy = (OPTION_BENEFICIARY_AUTH_POLICY, create_code_hash_auth_policy(&beneficiary_lock));
+
+    let result = verify_transfer(Transfer {
+        input_options: vec![policy.clone()],
+        output_options: vec![policy.clone()],
+        ..Transfer::default()
+    });
+    assert!(result.is_err(), "Should fail - the old auth policy survives the transfer");
+    assert_eq!(extract_error_code(&result), Some(56)); // Error::InvalidRotation
+
+    let result = verify_transfer(Transfer {
+        input_options: vec![policy],
+        ..Transfer::default()
+    });
+    assert!(result.is_ok(), "Should succeed - transfer drops the auth policy, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a transfer ends a pending recovery of the old beneficiary lock.
+/// The guardians stay, while the recovery they started no longer applies to the new wallet.
+#[test]
+fn test_transfer_drops_pending_recovery() {
+    let mut context = Context::default();
+    let (_, guardian_hash) = create_always_success_lock_with_args(&mut context, vec![GUARDIAN]);
+    let mut pending = create_dummy_lock_hash(9).to_vec();
+    pending.extend_from_slice(&301u64.to_le_bytes());
+
+    let result = verify_transfer(Transfer {
+        input_options: vec![guardians_option(guardian_hash), (OPTION_PENDING_RECOVERY, pending.clone())],
+        output_options: vec![guardians_option(guardian_hash), (OPTION_PENDING_RECOVERY, pending.clone())],
+        ..Transfer::default()
+    });
+    assert!(result.is_err(), "Should fail - the pending recovery survives the transfer");
+    assert_eq!(extract_error_code(&result), Some(56)); // Error::InvalidRotation
+
+    let result = verify_transfer(Transfer {
+        input_options: vec![guardians_option(guardian_hash), (OPTION_PENDING_RECOVERY, pending)],
+        output_options: vec![guardians_option(guardian_hash)],
+        ..Transfer::default()
+    });
+    assert!(result.is_ok(), "Should succeed - transfer drops the pending recovery, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a transfer cannot attach a type script the vesting cell does not carry.
+/// The relocked cell must keep the input's type script, here none at all.
+#[test]
+fn test_transfer_with_foreign_type_script_rejected() {
+    let result = verify_transfer(Transfer { output_type: Some(0x66), ..Transfer::default() });
+    assert!(result.is_err(), "Should fail - the relocked cell gained a type script");
+    assert_eq!(extract_error_code(&result), Some(56)); // Error::InvalidRotation
+}
\ No newline at end of file
@@ -0,0 +1,133 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+/// Builds and verifies an anonymous update from the given input block to the given header block.
+/// The args bound each transition to an advance of at most 1000 blocks.
+fn verify_bounded_update(
+    input_block: u64,
+    header_block: u64,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            create_dummy_lock_hash(1),
+            create_dummy_lock_hash(2),
+            100, // start_epoch
+            300, // end_epoch
+            120, // cliff_epoch
+        ),
+        &[(0x06, 1000u64.to_le_bytes().to_vec())],
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, header_block, 200);
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, input_block),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, header_block).pack())
+        .header_dep(header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that an update advancing exactly the maximum is accepted.
+/// Validates the inclusive boundary of the bound.
+#[test]
+fn test_advance_at_maximum_accepted() {
+    let result = verify_bounded_update(200, 1200);
+    assert!(result.is_ok(), "Should succeed - update advances 1000 blocks, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that an update advancing past the maximum is rejected.
+/// Validates that a single transition cannot jump arbitrarily far ahead.
+#[test]
+fn test_advance_above_maximum_rejected() {
+    let result = verify_bounded_update(200, 1201);
+    assert!(result.is_err(), "Should fail - update advances 1001 blocks");
+    assert_eq!(extract_error_code(&result), Some(55)); // Error::BlockAdvanceTooLarge
+}
+
+/// Tests that an absurd u64::MAX header is rejected when the bound is configured.
+/// Without the option such headers are only limited by freshness, see test_maximum_values_edge_cases.
+#[test]
+fn test_maximum_header_value_rejected() {
+    let result = verify_bounded_update(200, u64::MAX);
+    assert!(result.is_err(), "Should fail - u64::MAX header exceeds the bound");
+    assert_eq!(extract_error_code(&result), Some(55)); // Error::BlockAdvanceTooLarge
+}
+
+/// Tests that the bound also applies to full claims that consume the cell.
+/// The transaction has no continuation output, so the header block itself is bounded.
+#[test]
+fn test_advance_bound_applies_to_full_claim() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            creator_hash,
+            beneficiary_hash,
+            100, // start_epoch
+            300, // end_epoch
+            120, // cliff_epoch
+        ),
+        &[(0x06, 1000u64.to_le_bytes().to_vec())],
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 5000, 300);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - header block is 4800 blocks ahead");
+    assert_eq!(extract_error_code(&result), Some(55)); // Error::BlockAdvanceTooLarge
+}
@@ -0,0 +1,141 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const OPTION_MIN_HEADER_DEPS: u8 = 0x05;
+const OPTION_SCHEDULE_UNIT: u8 = 0x17;
+const SCHEDULE_UNIT_EPOCHS: u8 = 0;
+const SCHEDULE_UNIT_BLOCKS: u8 = 1;
+
+/// Builds and verifies a beneficiary claim on a schedule from 1000 to 3000 with a cliff at 1200.
+/// Every header shows epoch 0, so only a block-based schedule can have vested anything.
+fn verify_block_claim(
+    options: &[(u8, Vec<u8>)],
+    header_blocks: &[u64],
+    claimed: u64,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            creator_hash,
+            beneficiary_hash,
+            1000, // start block
+            3000, // end block
+            1200, // cliff block
+        ),
+        options,
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 900),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let highest_block = header_blocks.iter().copied().max().unwrap_or(0);
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity((10161u64 - claimed).pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, claimed, 0, highest_block).pack())
+        .output(CellOutput::new_builder()
+            .capacity(claimed.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack());
+
+    for block_number in header_blocks {
+        let header_hash = setup_header_with_block_and_epoch(&mut context, *block_number, 0);
+        builder = builder.header_dep(header_hash);
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Creates a schedule unit option record for the given unit.
+fn schedule_unit(unit: u8) -> (u8, Vec<u8>) {
+    (OPTION_SCHEDULE_UNIT, vec![unit])
+}
+
+/// Tests that a block schedule vests linearly by block number.
+/// At block 2000: vested = (2000-1000)/(3000-1000) * 10000 = 5000, while 5001 is too much.
+#[test]
+fn test_block_schedule_vests_by_block_number() {
+    let result = verify_block_claim(&[schedule_unit(SCHEDULE_UNIT_BLOCKS)], &[2000], 5000);
+    assert!(result.is_ok(), "Should succeed - half of the blocks have passed, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_block_claim(&[schedule_unit(SCHEDULE_UNIT_BLOCKS)], &[2000], 5001);
+    assert!(result.is_err(), "Should fail - claim exceeds the amount vested at block 2000");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that the cliff of a block schedule is a block number.
+/// Block 1199 is before the cliff, and block 1200 releases the 1000 accrued since block 1000.
+#[test]
+fn test_block_schedule_cliff() {
+    let result = verify_block_claim(&[schedule_unit(SCHEDULE_UNIT_BLOCKS)], &[1199], 1);
+    assert!(result.is_err(), "Should fail - cliff block not reached");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+
+    let result = verify_block_claim(&[schedule_unit(SCHEDULE_UNIT_BLOCKS)], &[1200], 1000);
+    assert!(result.is_ok(), "Should succeed - cliff block reached, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that epoch schedules ignore block numbers, with or without the explicit option.
+/// The headers show epoch 0, which is before the start epoch 1000, so nothing is vested.
+#[test]
+fn test_epoch_schedule_ignores_block_numbers() {
+    for options in [vec![], vec![schedule_unit(SCHEDULE_UNIT_EPOCHS)]] {
+        let result = verify_block_claim(&options, &[2000], 1);
+        assert!(result.is_err(), "Should fail - epoch schedule has not started");
+        assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+    }
+}
+
+/// Tests that a header quorum makes block schedules use the lowest block among the headers.
+/// With headers at blocks 1500 and 2000, only the 2500 vested at block 1500 may be claimed.
+#[test]
+fn test_block_schedule_quorum_uses_lowest_block() {
+    let options = [schedule_unit(SCHEDULE_UNIT_BLOCKS), (OPTION_MIN_HEADER_DEPS, 2u64.to_le_bytes().to_vec())];
+
+    let result = verify_block_claim(&options, &[1500, 2000], 2500);
+    assert!(result.is_ok(), "Should succeed - claim matches the lowest block, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_block_claim(&options, &[1500, 2000], 5000);
+    assert!(result.is_err(), "Should fail - claim uses the highest block");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that unknown units and malformed values are rejected as invalid arguments.
+/// The unit is exactly one byte, either 0 or 1.
+#[test]
+fn test_invalid_schedule_unit_values_rejected() {
+    for unit in [vec![2u8], vec![], vec![SCHEDULE_UNIT_BLOCKS, 0]] {
+        let result = verify_block_claim(&[(OPTION_SCHEDULE_UNIT, unit)], &[2000], 5000);
+        assert!(result.is_err(), "Should fail - invalid schedule unit value");
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,253 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const OPTION_BPS_DENOMINATION: u8 = 0x0d;
+
+// Capacity kept back for the cell itself and the backing shared by the 10000 basis points.
+const RESERVE: u64 = 16100;
+const BACKING: u64 = 1_000_000;
+
+/// Party that authorizes the transaction.
+#[derive(Clone, Copy)]
+enum Signer {
+    Beneficiary,
+    Creator,
+}
+
+/// Builds and verifies a transition of a basis-point cell over epochs 100 to 300 with a cliff at 120.
+/// The continuation is given as (capacity, data); a redirected payout adds an exchange output and witness.
+fn verify_bps_transition(
+    signer: Signer,
+    input_capacity: u64,
+    input_data: Bytes,
+    epoch: u64,
+    continuation: Option<(u64, Bytes)>,
+    redirected_payout: Option<u64>,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+    let (exchange_lock, exchange_hash) = create_always_success_lock_with_args(&mut context, vec![3u8]);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            creator_hash,
+            beneficiary_hash,
+            100, // start_epoch
+            300, // end_epoch
+            120, // cliff_epoch
+        ),
+        &[(OPTION_BPS_DENOMINATION, RESERVE.to_le_bytes().to_vec())],
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, epoch);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(input_capacity.pack())
+            .lock(lock_script.clone())
+            .build(),
+        input_data,
+    );
+
+    let auth_lock = match signer {
+        Signer::Beneficiary => beneficiary_lock,
+        Signer::Creator => creator_lock,
+    };
+    let auth_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(auth_lock)
+            .build(),
+        Bytes::new(),
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(auth_input_out_point).build())
+        .header_dep(header_hash);
+
+    if let Some((capacity, data)) = continuation {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(capacity.pack())
+                .lock(lock_script)
+                .build())
+            .output_data(data.pack());
+    }
+
+    if let Some(payout) = redirected_payout {
+        // The vesting input is at index 0, so its witness is the first one.
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(payout.pack())
+                .lock(exchange_lock)
+                .build())
+            .output_data(Bytes::new().pack())
+            .witness(create_payout_witness(exchange_hash).pack());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that a beneficiary claim releases backing in proportion to the basis points claimed.
+/// At epoch 200 half of the 10000 basis points are vested, releasing half of the backing.
+#[test]
+fn test_bps_claim_releases_proportional_capacity() {
+    let result = verify_bps_transition(
+        Signer::Beneficiary,
+        RESERVE + BACKING,
+        create_vesting_data(10000, 0, 0, 200),
+        200,
+        Some((RESERVE + 500_000, create_vesting_data(10000, 5000, 0, 201))),
+        None,
+    );
+    assert!(result.is_ok(), "Should succeed - proportional release, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a claim releasing more capacity than its basis points allow is rejected.
+/// One shannon over the proportional share is enough to fail.
+#[test]
+fn test_bps_claim_releasing_too_much_rejected() {
+    let result = verify_bps_transition(
+        Signer::Beneficiary,
+        RESERVE + BACKING,
+        create_vesting_data(10000, 0, 0, 200),
+        200,
+        Some((RESERVE + 499_999, create_vesting_data(10000, 5000, 0, 201))),
+        None,
+    );
+    assert!(result.is_err(), "Should fail - released more than the claimed basis points");
+    assert_eq!(extract_error_code(&result), Some(60)); // Error::InvalidBasisPointBacking
+}
+
+/// Tests that later claims are measured against the backing still held by the cell.
+/// After 5000 basis points were claimed, claiming 2500 more releases half of what remains.
+#[test]
+fn test_bps_second_claim_uses_remaining_backing() {
+    let result = verify_bps_transition(
+        Signer::Beneficiary,
+        RESERVE + 500_000,
+        create_vesting_data(10000, 5000, 0, 200),
+        250,
+        Some((RESERVE + 250_000, create_vesting_data(10000, 7500, 0, 201))),
+        None,
+    );
+    assert!(result.is_ok(), "Should succeed - release from the remaining backing, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_bps_transition(
+        Signer::Beneficiary,
+        RESERVE + 500_000,
+        create_vesting_data(10000, 5000, 0, 200),
+        250,
+        Some((RESERVE + 249_999, create_vesting_data(10000, 7500, 0, 201))),
+        None,
+    );
+    assert!(result.is_err(), "Should fail - released more than the remaining backing allows");
+    assert_eq!(extract_error_code(&result), Some(60)); // Error::InvalidBasisPointBacking
+}
+
+/// Tests that a creator termination releases only the backing of the unvested basis points.
+/// The continuation cell keeps the backing of the vested half for the beneficiary.
+#[test]
+fn test_bps_termination_releases_proportional_capacity() {
+    let result = verify_bps_transition(
+        Signer::Creator,
+        RESERVE + BACKING,
+        create_vesting_data(10000, 0, 0, 200),
+        200,
+        Some((RESERVE + 500_000, create_vesting_data(10000, 0, 5000, 201))),
+        None,
+    );
+    assert!(result.is_ok(), "Should succeed - proportional termination, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_bps_transition(
+        Signer::Creator,
+        RESERVE + BACKING,
+        create_vesting_data(10000, 0, 0, 200),
+        200,
+        Some((RESERVE + 400_000, create_vesting_data(10000, 0, 5000, 201))),
+        None,
+    );
+    assert!(result.is_err(), "Should fail - termination took part of the vested backing");
+    assert_eq!(extract_error_code(&result), Some(60)); // Error::InvalidBasisPointBacking
+}
+
+/// Tests that a redirected payout must receive the released capacity, not the basis points.
+/// Paying out 5000 shannons for 5000 basis points would shortchange the beneficiary.
+#[test]
+fn test_bps_redirected_payout_uses_capacity() {
+    let result = verify_bps_transition(
+        Signer::Beneficiary,
+        RESERVE + BACKING,
+        create_vesting_data(10000, 0, 0, 200),
+        200,
+        Some((RESERVE + 500_000, create_vesting_data(10000, 5000, 0, 201))),
+        Some(500_000),
+    );
+    assert!(result.is_ok(), "Should succeed - payout receives the released capacity, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_bps_transition(
+        Signer::Beneficiary,
+        RESERVE + BACKING,
+        create_vesting_data(10000, 0, 0, 200),
+        200,
+        Some((RESERVE + 500_000, create_vesting_data(10000, 5000, 0, 201))),
+        Some(5000),
+    );
+    assert!(result.is_err(), "Should fail - payout only received the basis point count");
+    assert_eq!(extract_error_code(&result), Some(46)); // Error::InsufficientPayout
+}
+
+/// Tests that the beneficiary consumes a fully vested basis-point cell.
+/// Without a continuation cell the whole remaining backing is released.
+#[test]
+fn test_bps_full_claim_consumes_cell() {
+    let result = verify_bps_transition(
+        Signer::Beneficiary,
+        RESERVE + 500_000,
+        create_vesting_data(10000, 5000, 0, 200),
+        350,
+        None,
+        None,
+    );
+    assert!(result.is_ok(), "Should succeed - full claim consumes the cell, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a basis-point cell whose total is not 10000 is rejected.
+/// An absolute total would reintroduce drift between the state and the balance.
+#[test]
+fn test_bps_total_amount_must_be_10000() {
+    let result = verify_bps_transition(
+        Signer::Beneficiary,
+        RESERVE + BACKING,
+        create_vesting_data(20000, 0, 0, 200),
+        200,
+        Some((RESERVE + 500_000, create_vesting_data(20000, 10000, 0, 201))),
+        None,
+    );
+    assert!(result.is_err(), "Should fail - total is not 10000 basis points");
+    assert_eq!(extract_error_code(&result), Some(60)); // Error::InvalidBasisPointBacking
+}
+
+/// Tests that a cell holding less capacity than its reserve cannot be claimed from.
+/// There is no backing to share, so no release amount can be derived.
+#[test]
+fn test_bps_capacity_below_reserve_rejected() {
+    let result = verify_bps_transition(
+        Signer::Beneficiary,
+        RESERVE - 1,
+        create_vesting_data(10000, 0, 0, 200),
+        200,
+        Some((RESERVE - 1, create_vesting_data(10000, 5000, 0, 201))),
+        None,
+    );
+    assert!(result.is_err(), "Should fail - capacity below the reserve");
+    assert_eq!(extract_error_code(&result), Some(60)); // Error::InvalidBasisPointBacking
+}
\ No newline at end of file
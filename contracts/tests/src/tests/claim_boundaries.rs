@@ -0,0 +1,172 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+// Schedule shared by every boundary case.
+const START_EPOCH: u64 = 100;
+const CLIFF_EPOCH: u64 = 150;
+const END_EPOCH: u64 = 300;
+const TOTAL_AMOUNT: u64 = 10000;
+const INPUT_BLOCK: u64 = 500;
+const PRIOR_CLAIMED_FOR_REMAINDER: u64 = 2500;
+
+/// Claim shapes exercised at every boundary epoch.
+#[derive(Debug, Clone, Copy)]
+enum ClaimCase {
+    /// Claims exactly the vested amount (consumes the cell when fully vested).
+    ExactlyVested,
+    /// Claims one less than the vested amount with a continuation output.
+    VestedMinusOne,
+    /// Claims one more than the vested amount with a continuation output.
+    VestedPlusOne,
+    /// Claims nothing while keeping the continuation output (pure update).
+    ZeroWithOutput,
+    /// Consumes the cell to claim the remainder after a prior partial claim.
+    RemainderOnly,
+}
+
+/// Boundary table: (label, epoch, expected vested amount, claim case, expected error code).
+/// An expected error code of None means the transaction must succeed.
+const BOUNDARY_TABLE: &[(&str, u64, u64, ClaimCase, Option<i8>)] = &[
+    // Before start: nothing vested.
+    ("start-1", 99, 0, ClaimCase::ExactlyVested, None),
+    ("start-1", 99, 0, ClaimCase::VestedPlusOne, Some(21)),
+    ("start-1", 99, 0, ClaimCase::ZeroWithOutput, None),
+    ("start-1", 99, 0, ClaimCase::RemainderOnly, Some(43)),
+    // At start: cliff still pending.
+    ("start", 100, 0, ClaimCase::ExactlyVested, None),
+    ("start", 100, 0, ClaimCase::VestedPlusOne, Some(21)),
+    ("start", 100, 0, ClaimCase::ZeroWithOutput, None),
+    ("start", 100, 0, ClaimCase::RemainderOnly, Some(43)),
+    // Just before cliff: nothing vested.
+    ("cliff-1", 149, 0, ClaimCase::ExactlyVested, None),
+    ("cliff-1", 149, 0, ClaimCase::VestedPlusOne, Some(21)),
+    ("cliff-1", 149, 0, ClaimCase::ZeroWithOutput, None),
+    ("cliff-1", 149, 0, ClaimCase::RemainderOnly, Some(43)),
+    // At cliff: accrued linear amount releases.
+    ("cliff", 150, 2500, ClaimCase::ExactlyVested, None),
+    ("cliff", 150, 2500, ClaimCase::VestedMinusOne, None),
+    ("cliff", 150, 2500, ClaimCase::VestedPlusOne, Some(21)),
+    ("cliff", 150, 2500, ClaimCase::ZeroWithOutput, None),
+    ("cliff", 150, 2500, ClaimCase::RemainderOnly, Some(43)),
+    // Midpoint.
+    ("mid", 200, 5000, ClaimCase::ExactlyVested, None),
+    ("mid", 200, 5000, ClaimCase::VestedMinusOne, None),
+    ("mid", 200, 5000, ClaimCase::VestedPlusOne, Some(21)),
+    ("mid", 200, 5000, ClaimCase::ZeroWithOutput, None),
+    ("mid", 200, 5000, ClaimCase::RemainderOnly, Some(43)),
+    // Just before end: still partially vested.
+    ("end-1", 299, 9950, ClaimCase::ExactlyVested, None),
+    ("end-1", 299, 9950, ClaimCase::VestedMinusOne, None),
+    ("end-1", 299, 9950, ClaimCase::VestedPlusOne, Some(21)),
+    ("end-1", 299, 9950, ClaimCase::ZeroWithOutput, None),
+    ("end-1", 299, 9950, ClaimCase::RemainderOnly, Some(43)),
+    // At end: fully vested, the cell must be consumed.
+    ("end", 300, 10000, ClaimCase::ExactlyVested, None),
+    ("end", 300, 10000, ClaimCase::VestedMinusOne, Some(42)),
+    ("end", 300, 10000, ClaimCase::VestedPlusOne, Some(42)),
+    ("end", 300, 10000, ClaimCase::ZeroWithOutput, Some(42)),
+    ("end", 300, 10000, ClaimCase::RemainderOnly, None),
+    // After end: fully vested, the cell must be consumed.
+    ("end+1", 301, 10000, ClaimCase::ExactlyVested, None),
+    ("end+1", 301, 10000, ClaimCase::VestedMinusOne, Some(42)),
+    ("end+1", 301, 10000, ClaimCase::VestedPlusOne, Some(42)),
+    ("end+1", 301, 10000, ClaimCase::ZeroWithOutput, Some(42)),
+    ("end+1", 301, 10000, ClaimCase::RemainderOnly, None),
+];
+
+/// Builds and verifies a beneficiary claim for one boundary table row.
+/// Returns the verification result so the caller can compare it against the expected outcome.
+fn run_boundary_case(
+    epoch: u64,
+    vested: u64,
+    case: ClaimCase,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args(creator_hash, beneficiary_hash, START_EPOCH, END_EPOCH, CLIFF_EPOCH);
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    let header_block = INPUT_BLOCK + 1;
+    let header_hash = setup_header_with_block_and_epoch(&mut context, header_block, epoch);
+
+    let prior_claimed = match case {
+        ClaimCase::RemainderOnly => PRIOR_CLAIMED_FOR_REMAINDER,
+        _ => 0,
+    };
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity((TOTAL_AMOUNT + 161).pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(TOTAL_AMOUNT, prior_claimed, 0, INPUT_BLOCK),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    // Determine the claimed total written to the continuation output, if any.
+    let output_claimed = match case {
+        ClaimCase::ExactlyVested if vested >= TOTAL_AMOUNT => None,
+        ClaimCase::ExactlyVested => Some(vested),
+        ClaimCase::VestedMinusOne => Some(vested - 1),
+        ClaimCase::VestedPlusOne => Some(vested + 1),
+        ClaimCase::ZeroWithOutput => Some(0),
+        ClaimCase::RemainderOnly => None,
+    };
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .header_dep(header_hash);
+
+    if let Some(claimed) = output_claimed {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity((TOTAL_AMOUNT + 161).saturating_sub(claimed).pack())
+                .lock(lock_script)
+                .build())
+            .output_data(create_vesting_data(TOTAL_AMOUNT, claimed, 0, header_block).pack())
+            .output(CellOutput::new_builder()
+                .capacity(claimed.pack())
+                .lock(beneficiary_lock)
+                .build())
+            .output_data(Bytes::new().pack());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests beneficiary claims at every schedule boundary using a single table of expectations.
+/// Each row is verified in a fresh context and reports its label on mismatch.
+#[test]
+fn test_claim_boundary_table() {
+    for (label, epoch, vested, case, expected) in BOUNDARY_TABLE.iter() {
+        let result = run_boundary_case(*epoch, *vested, *case);
+        match expected {
+            None => assert!(
+                result.is_ok(),
+                "Row {} (epoch {}, {:?}) should succeed, got error code: {:?}",
+                label, epoch, case, extract_error_code(&result)
+            ),
+            Some(code) => assert_eq!(
+                extract_error_code(&result),
+                Some(*code),
+                "Row {} (epoch {}, {:?}) should fail with error code {}",
+                label, epoch, case, code
+            ),
+        }
+    }
+}
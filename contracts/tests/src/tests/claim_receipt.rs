@@ -0,0 +1,205 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+/// Creates claim receipt cell data from the given amount and epoch.
+/// The data is packed as 16 bytes: amount (8) + epoch (8).
+fn create_receipt_data(amount: u64, epoch: u64) -> Bytes {
+    let mut data = Vec::with_capacity(16);
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&epoch.to_le_bytes());
+    Bytes::from(data)
+}
+
+/// A claim transaction on a 10000 grant over epochs 100 to 300 and the receipts it creates.
+struct Claim {
+    beneficiary_signs: bool,
+    epoch: u64,
+    beneficiary_claimed: u64,
+    consume: bool,
+    receipts: Vec<Bytes>,
+    receipt_args: Option<Bytes>,
+    held_by_beneficiary: bool,
+}
+
+impl Default for Claim {
+    fn default() -> Self {
+        Claim {
+            beneficiary_signs: true,
+            epoch: 200,
+            beneficiary_claimed: 5000,
+            consume: false,
+            receipts: vec![create_receipt_data(5000, 200)],
+            receipt_args: None,
+            held_by_beneficiary: false,
+        }
+    }
+}
+
+/// Builds and verifies a claim with receipt outputs.
+/// Receipts reference the vesting lock hash unless receipt_args overrides it; without the beneficiary the creator terminates instead.
+fn verify_claim(claim: Claim) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let vesting_out_point = context.deploy_cell(Loader::default().load_binary("vesting_lock"));
+    let receipt_out_point = context.deploy_cell(Loader::default().load_binary("claim_receipt"));
+
+    let (beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+    let (holder_lock, _holder_hash) = create_always_success_lock_with_args(&mut context, vec![4u8]);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let vesting_lock = context.build_script(&vesting_out_point, args).expect("script");
+    let vesting_lock_hash: [u8; 32] = vesting_lock.calc_script_hash().unpack();
+    let receipt_args = claim.receipt_args.unwrap_or_else(|| Bytes::from(vesting_lock_hash.to_vec()));
+    let receipt_type = context.build_script(&receipt_out_point, receipt_args).expect("script");
+
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, claim.epoch);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(vesting_lock.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let signer_lock = if claim.beneficiary_signs { beneficiary_lock.clone() } else { creator_lock };
+    let signer_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(signer_lock)
+            .build(),
+        Bytes::new(),
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(signer_input_out_point).build())
+        .header_dep(header_hash);
+
+    if !claim.consume {
+        let creator_claimed = if claim.beneficiary_signs { 0 } else { 5000 };
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(5161u64.pack())
+                .lock(vesting_lock)
+                .build())
+            .output_data(create_vesting_data(10000, claim.beneficiary_claimed, creator_claimed, 201).pack());
+    }
+
+    let receipt_lock = if claim.held_by_beneficiary { beneficiary_lock } else { holder_lock };
+    for receipt in claim.receipts {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(20000u64.pack())
+                .lock(receipt_lock.clone())
+                .type_(Some(receipt_type.clone()).pack())
+                .build())
+            .output_data(receipt.pack());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that a partial claim can record its amount and epoch in a receipt.
+/// The receipt matches the 5000 claimed at epoch 200.
+#[test]
+fn test_receipt_for_partial_claim() {
+    let result = verify_claim(Claim::default());
+    assert!(result.is_ok(), "Should succeed - receipt matches the claim, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the final claim, which consumes the vesting cell, can still be receipted.
+/// The receipt is the lasting proof once the vesting cell is gone.
+#[test]
+fn test_receipt_for_final_claim() {
+    let result = verify_claim(Claim {
+        epoch: 300,
+        consume: true,
+        receipts: vec![create_receipt_data(10000, 300)],
+        ..Claim::default()
+    });
+    assert!(result.is_ok(), "Should succeed - receipt for the consuming claim, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the beneficiary can hold the receipt under their own lock.
+/// The 16-byte receipt fits the vesting lock's limit for typed payout outputs.
+#[test]
+fn test_receipt_held_by_beneficiary() {
+    let result = verify_claim(Claim { held_by_beneficiary: true, ..Claim::default() });
+    assert!(result.is_ok(), "Should succeed - receipt locked by the beneficiary, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a receipt cannot overstate or understate the claimed amount.
+/// 5000 was claimed, so receipts for 4999 and 5001 are both rejected.
+#[test]
+fn test_receipt_amount_mismatch_rejected() {
+    for amount in [4999, 5001] {
+        let result = verify_claim(Claim { receipts: vec![create_receipt_data(amount, 200)], ..Claim::default() });
+        assert!(result.is_err(), "Should fail - receipt amount {} differs from the claim", amount);
+        assert_eq!(extract_error_code(&result), Some(22)); // Error::AmountMismatch
+    }
+}
+
+/// Tests that the receipt epoch must come from the transaction's header deps.
+/// The claim used a header at epoch 200, so a receipt for epoch 199 is rejected.
+#[test]
+fn test_receipt_epoch_mismatch_rejected() {
+    let result = verify_claim(Claim { receipts: vec![create_receipt_data(5000, 199)], ..Claim::default() });
+    assert!(result.is_err(), "Should fail - receipt epoch is not a header dep epoch");
+    assert_eq!(extract_error_code(&result), Some(23)); // Error::EpochMismatch
+}
+
+/// Tests that one claim cannot be receipted twice and that malformed receipts are rejected.
+/// Duplicate receipts would let a beneficiary present the same payout as two.
+#[test]
+fn test_duplicate_or_malformed_receipts_rejected() {
+    let mut trailing = create_receipt_data(5000, 200).to_vec();
+    trailing.push(0);
+
+    let cases = [
+        ("duplicate receipt", vec![create_receipt_data(5000, 200), create_receipt_data(5000, 200)]),
+        ("trailing bytes", vec![Bytes::from(trailing)]),
+        ("truncated receipt", vec![Bytes::from(vec![0u8; 8])]),
+    ];
+
+    for (label, receipts) in cases {
+        let result = verify_claim(Claim { receipts, ..Claim::default() });
+        assert_eq!(extract_error_code(&result), Some(11), "Case {} should fail with invalid receipt", label); // Error::InvalidReceipt
+    }
+}
+
+/// Tests that a creator termination cannot create a receipt.
+/// Receipts record beneficiary payouts, so the beneficiary must authorize the transaction.
+#[test]
+fn test_receipt_without_beneficiary_rejected() {
+    let result = verify_claim(Claim { beneficiary_signs: false, beneficiary_claimed: 0, ..Claim::default() });
+    assert!(result.is_err(), "Should fail - beneficiary authorization missing");
+    assert_eq!(extract_error_code(&result), Some(20)); // Error::Unauthorized
+}
+
+/// Tests that a receipt must reference the grant being claimed.
+/// A receipt for another grant's lock hash finds no vesting input to check against.
+#[test]
+fn test_receipt_for_other_grant_rejected() {
+    let result = verify_claim(Claim { receipt_args: Some(Bytes::from(create_dummy_lock_hash(9).to_vec())), ..Claim::default() });
+    assert!(result.is_err(), "Should fail - linked vesting cell missing");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::VestingCellNotFound
+}
+
+/// Tests that receipt type args must be exactly one lock hash.
+/// The args are the grant identifier alone, so any other length is rejected.
+#[test]
+fn test_receipt_invalid_args_rejected() {
+    let result = verify_claim(Claim { receipt_args: Some(Bytes::from(vec![0u8; 33])), ..Claim::default() });
+    assert!(result.is_err(), "Should fail - receipt args are not 32 bytes");
+    assert_eq!(extract_error_code(&result), Some(10)); // Error::InvalidArgs
+}
\ No newline at end of file
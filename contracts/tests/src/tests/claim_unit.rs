@@ -0,0 +1,101 @@
+use super::helpers::*;
+use ckb_testtool::ckb_types::bytes::Bytes;
+use ckb_testtool::context::Context;
+
+const OPTION_CLAIM_UNIT: u8 = 0x11;
+const CLAIM_UNIT: u64 = 1000;
+
+/// Builds and verifies a beneficiary claim on a schedule over epochs 100 to 300 with a cliff at 120.
+/// The claim unit option value is passed raw; output_data None consumes the cell.
+fn verify_claim(
+    claim_unit: Vec<u8>,
+    epoch: u64,
+    input_data: Bytes,
+    output_data: Option<Bytes>,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let tx = build_claim_tx(&mut context, &[(OPTION_CLAIM_UNIT, claim_unit)], 10661, epoch, input_data, output_data);
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that claims in whole units are accepted.
+/// At epoch 200 half of the grant is vested and 5000 is five units.
+#[test]
+fn test_whole_unit_claim_accepted() {
+    let result = verify_claim(
+        CLAIM_UNIT.to_le_bytes().to_vec(),
+        200,
+        create_vesting_data(10000, 0, 0, 200),
+        Some(create_vesting_data(10000, 5000, 0, 201)),
+    );
+    assert!(result.is_ok(), "Should succeed - claim of whole units, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a claim of a partial unit is rejected even though it is vested.
+/// 4500 is within the 5000 vested at epoch 200 but not a multiple of 1000.
+#[test]
+fn test_partial_unit_claim_rejected() {
+    let result = verify_claim(
+        CLAIM_UNIT.to_le_bytes().to_vec(),
+        200,
+        create_vesting_data(10000, 0, 0, 200),
+        Some(create_vesting_data(10000, 4500, 0, 201)),
+    );
+    assert!(result.is_err(), "Should fail - claim is not a multiple of the claim unit");
+    assert_eq!(extract_error_code(&result), Some(63)); // Error::InvalidClaimUnit
+}
+
+/// Tests that the unit applies to the claimed delta rather than the running total.
+/// An earlier odd claimed amount does not prevent later whole-unit claims.
+#[test]
+fn test_unit_applies_to_claimed_delta() {
+    let result = verify_claim(
+        CLAIM_UNIT.to_le_bytes().to_vec(),
+        200,
+        create_vesting_data(10000, 1500, 0, 200),
+        Some(create_vesting_data(10000, 4500, 0, 201)),
+    );
+    assert!(result.is_ok(), "Should succeed - delta of three units, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the final claim may take an odd remainder so the total stays reachable.
+/// With 1000 already claimed, the remaining 9500 of a fully vested 10500 grant is claimed by consuming the cell.
+#[test]
+fn test_final_claim_exempt() {
+    let result = verify_claim(
+        CLAIM_UNIT.to_le_bytes().to_vec(),
+        300,
+        create_vesting_data(10500, 1000, 0, 200),
+        None,
+    );
+    assert!(result.is_ok(), "Should succeed - final claim of the remainder, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that after termination the claim of everything the creator left is final.
+/// The creator reclaimed 4500, so the remaining 5500 is claimed by consuming the cell.
+#[test]
+fn test_final_claim_after_termination_exempt() {
+    let result = verify_claim(
+        CLAIM_UNIT.to_le_bytes().to_vec(),
+        200,
+        create_vesting_data(10000, 0, 4500, 200),
+        None,
+    );
+    assert!(result.is_ok(), "Should succeed - final claim after termination, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that zero and malformed claim units are rejected as invalid arguments.
+/// The unit is a non-zero u64 of exactly 8 bytes.
+#[test]
+fn test_invalid_claim_unit_values_rejected() {
+    for claim_unit in [0u64.to_le_bytes().to_vec(), vec![0xe8, 0x03]] {
+        let result = verify_claim(
+            claim_unit,
+            200,
+            create_vesting_data(10000, 0, 0, 200),
+            Some(create_vesting_data(10000, 5000, 0, 201)),
+        );
+        assert!(result.is_err(), "Should fail - invalid claim unit");
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS));
+    }
+}
\ No newline at end of file
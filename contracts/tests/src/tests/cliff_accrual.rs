@@ -0,0 +1,135 @@
+use super::helpers::*;
+use ckb_testtool::ckb_types::bytes::Bytes;
+use ckb_testtool::context::Context;
+
+const OPTION_CLIFF_ACCRUAL: u8 = 0x10;
+const CLIFF_ACCRUAL_RETROACTIVE: u8 = 0;
+const CLIFF_ACCRUAL_FROM_CLIFF: u8 = 1;
+
+/// Builds and verifies a beneficiary claim on a schedule over epochs 100 to 300 with a cliff at 120.
+/// The cliff accrual option value is passed raw; None leaves the option out entirely.
+fn verify_claim(
+    cliff_accrual: Option<Vec<u8>>,
+    epoch: u64,
+    input_data: Bytes,
+    output_data: Bytes,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let options: Vec<(u8, Vec<u8>)> = cliff_accrual.map(|mode| (OPTION_CLIFF_ACCRUAL, mode)).into_iter().collect();
+    let mut context = Context::default();
+    let tx = build_claim_tx(&mut context, &options, 20161, epoch, input_data, Some(output_data));
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that without the option the cliff releases everything accrued since the start epoch.
+/// At epoch 120 one tenth of the schedule has accrued, so 1000 can be claimed right at the cliff.
+#[test]
+fn test_default_accrual_releases_backlog_at_cliff() {
+    let result = verify_claim(
+        None,
+        120,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 1000, 0, 201),
+    );
+    assert!(result.is_ok(), "Should succeed - retroactive backlog at the cliff, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that retroactive accrual can be selected explicitly and matches the default.
+/// At epoch 200 half of the 100 to 300 schedule has vested.
+#[test]
+fn test_explicit_retroactive_accrual() {
+    let result = verify_claim(
+        Some(vec![CLIFF_ACCRUAL_RETROACTIVE]),
+        200,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 5000, 0, 201),
+    );
+    assert!(result.is_ok(), "Should succeed - explicit retroactive accrual, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that accruing from the cliff releases nothing at the cliff epoch itself.
+/// The same claim of 1000 that succeeds by default is rejected.
+#[test]
+fn test_from_cliff_accrual_releases_nothing_at_cliff() {
+    let result = verify_claim(
+        Some(vec![CLIFF_ACCRUAL_FROM_CLIFF]),
+        120,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 1, 0, 201),
+    );
+    assert!(result.is_err(), "Should fail - nothing has accrued at the cliff");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests the linear accrual between cliff and end when accruing from the cliff.
+/// At epoch 200, 80 of 180 epochs have passed: 4444 is vested, rounded down, and 4445 is not.
+#[test]
+fn test_from_cliff_accrual_is_linear_after_cliff() {
+    let result = verify_claim(
+        Some(vec![CLIFF_ACCRUAL_FROM_CLIFF]),
+        200,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 4444, 0, 201),
+    );
+    assert!(result.is_ok(), "Should succeed - claim of the amount accrued since the cliff, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_claim(
+        Some(vec![CLIFF_ACCRUAL_FROM_CLIFF]),
+        200,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 4445, 0, 201),
+    );
+    assert!(result.is_err(), "Should fail - claim exceeds the amount accrued since the cliff");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that accruing from the cliff still vests the full amount at the end epoch.
+/// Only the shape of the curve changes, not the total.
+#[test]
+fn test_from_cliff_accrual_fully_vested_at_end() {
+    let result = verify_claim(
+        Some(vec![CLIFF_ACCRUAL_FROM_CLIFF]),
+        300,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 10000, 0, 201),
+    );
+    assert!(result.is_ok(), "Should succeed - fully vested at the end epoch, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that tranches follow the same accrual mode as the base schedule.
+/// A 4000 tranche over epochs 200 to 400 with a cliff at 250 has vested 1333 at epoch 300 rather than 2000.
+#[test]
+fn test_from_cliff_accrual_applies_to_tranches() {
+    let result = verify_claim(
+        Some(vec![CLIFF_ACCRUAL_FROM_CLIFF]),
+        300,
+        create_vesting_data_with_tranches(14000, 0, 0, 200, &[(4000, 0, 200, 400, 250)]),
+        create_vesting_data_with_tranches(14000, 11333, 0, 201, &[(4000, 1333, 200, 400, 250)]),
+    );
+    assert!(result.is_ok(), "Should succeed - tranche claim accrued since its cliff, got error code: {:?}", extract_error_code(&result));
+
+    // The aggregate stays within the vested total, so only the tranche bound is exceeded.
+    let result = verify_claim(
+        Some(vec![CLIFF_ACCRUAL_FROM_CLIFF]),
+        300,
+        create_vesting_data_with_tranches(14000, 0, 0, 200, &[(4000, 0, 200, 400, 250)]),
+        create_vesting_data_with_tranches(14000, 11333, 0, 201, &[(4000, 1334, 200, 400, 250)]),
+    );
+    assert!(result.is_err(), "Should fail - tranche claim exceeds the amount accrued since its cliff");
+    assert_eq!(extract_error_code(&result), Some(49)); // Error::InvalidTrancheClaim
+}
+
+/// Tests that unknown modes and malformed values are rejected as invalid arguments.
+/// The mode is exactly one byte, either 0 or 1.
+#[test]
+fn test_invalid_cliff_accrual_values_rejected() {
+    for mode in [vec![2u8], vec![], vec![CLIFF_ACCRUAL_FROM_CLIFF, 0]] {
+        let result = verify_claim(
+            Some(mode),
+            200,
+            create_vesting_data(10000, 0, 0, 200),
+            create_vesting_data(10000, 0, 0, 201),
+        );
+        assert!(result.is_err(), "Should fail - invalid cliff accrual value");
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS));
+    }
+}
\ No newline at end of file
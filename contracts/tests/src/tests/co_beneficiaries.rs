@@ -0,0 +1,280 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const OPTION_CO_BENEFICIARIES: u8 = 0x19;
+const DATA_EXTENSION_CLAIMS_VERSION: u8 = 2;
+
+// Args of the always-success locks standing in for each party
+const BENEFICIARY: u8 = 1;
+const CREATOR: u8 = 2;
+const ALICE: u8 = 3;
+const BOB: u8 = 4;
+
+/// The team used by most tests: Alice vests 30% and Bob 20%, leaving 50% to the beneficiary.
+const TEAM: [(u8, u16); 2] = [(ALICE, 3000), (BOB, 2000)];
+
+/// Creates shared cell data from the aggregate state and each co-beneficiary's claimed counter.
+/// The counters follow the cell data in a version 2 extension area.
+fn create_shared_data(total: u64, beneficiary_claimed: u64, creator_claimed: u64, block: u64, counters: &[u64]) -> Bytes {
+    let mut data = with_tranche_count(create_vesting_data(total, beneficiary_claimed, creator_claimed, block)).to_vec();
+    data.push(DATA_EXTENSION_CLAIMS_VERSION);
+    for counter in counters {
+        data.extend_from_slice(&counter.to_le_bytes());
+    }
+    Bytes::from(data)
+}
+
+/// Builds and verifies a transition of a 10000 grant over epochs 100 to 300 with a cliff at 120.
+/// Co-beneficiaries and signers are given by the args of their always-success locks; output_data None consumes the cell.
+fn verify_shared(
+    co_beneficiaries: &[(u8, u16)],
+    signers: &[u8],
+    epoch: u64,
+    input_data: Bytes,
+    output_data: Option<Bytes>,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (_beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let mut option_value = Vec::new();
+    for (party, share_bps) in co_beneficiaries {
+        let (_, lock_hash) = create_always_success_lock_with_args(&mut context, vec![*party]);
+        option_value.extend_from_slice(&lock_hash);
+        option_value.extend_from_slice(&share_bps.to_le_bytes());
+    }
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            creator_hash,
+            beneficiary_hash,
+            100, // start_epoch
+            300, // end_epoch
+            120, // cliff_epoch
+        ),
+        &[(OPTION_CO_BENEFICIARIES, option_value)],
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, epoch);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        input_data,
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .header_dep(header_hash);
+
+    for signer in signers {
+        let (signer_lock, _) = create_always_success_lock_with_args(&mut context, vec![*signer]);
+        let signer_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(6100000000u64.pack())
+                .lock(signer_lock)
+                .build(),
+            Bytes::new(),
+        );
+        builder = builder.input(CellInput::new_builder().previous_output(signer_input_out_point).build());
+    }
+
+    if let Some(output_data) = output_data {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(10161u64.pack())
+                .lock(lock_script)
+                .build())
+            .output_data(output_data.pack());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that a co-beneficiary claims its own share of the vested amount.
+/// At epoch 200 half of the grant has vested, so Alice's 30% share is 1500.
+#[test]
+fn test_co_beneficiary_claims_own_share() {
+    let result = verify_shared(
+        &TEAM,
+        &[ALICE],
+        200,
+        create_shared_data(10000, 0, 0, 200, &[0, 0]),
+        Some(create_shared_data(10000, 1500, 0, 201, &[1500, 0])),
+    );
+    assert!(result.is_ok(), "Should succeed - Alice claims her share, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_shared(
+        &TEAM,
+        &[ALICE],
+        200,
+        create_shared_data(10000, 0, 0, 200, &[0, 0]),
+        Some(create_shared_data(10000, 1501, 0, 201, &[1501, 0])),
+    );
+    assert!(result.is_err(), "Should fail - claim exceeds Alice's share");
+    assert_eq!(extract_error_code(&result), Some(73)); // Error::InsufficientVestedShare
+}
+
+/// Tests that the beneficiary claims the share the co-beneficiaries leave.
+/// The beneficiary's claims are the aggregate beyond the counters, and its 50% share of 5000 is 2500.
+#[test]
+fn test_beneficiary_claims_remaining_share() {
+    let result = verify_shared(
+        &TEAM,
+        &[BENEFICIARY],
+        200,
+        create_shared_data(10000, 1500, 0, 200, &[1500, 0]),
+        Some(create_shared_data(10000, 4000, 0, 201, &[1500, 0])),
+    );
+    assert!(result.is_ok(), "Should succeed - beneficiary claims its share, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_shared(
+        &TEAM,
+        &[BENEFICIARY],
+        200,
+        create_shared_data(10000, 1500, 0, 200, &[1500, 0]),
+        Some(create_shared_data(10000, 4001, 0, 201, &[1500, 0])),
+    );
+    assert!(result.is_err(), "Should fail - claim exceeds the beneficiary's share");
+    assert_eq!(extract_error_code(&result), Some(73)); // Error::InsufficientVestedShare
+}
+
+/// Tests that nobody can claim another beneficiary's share.
+/// Bob's counter may only grow when Bob authorizes the transaction himself.
+#[test]
+fn test_claim_for_another_beneficiary_rejected() {
+    for signer in [ALICE, BENEFICIARY] {
+        let result = verify_shared(
+            &TEAM,
+            &[signer],
+            200,
+            create_shared_data(10000, 0, 0, 200, &[0, 0]),
+            Some(create_shared_data(10000, 1000, 0, 201, &[0, 1000])),
+        );
+        assert!(result.is_err(), "Should fail - party {} claims Bob's share", signer);
+        assert_eq!(extract_error_code(&result), Some(25)); // Error::Unauthorized
+    }
+}
+
+/// Tests that a transition has at most one claimant and that claimed amounts never decrease.
+/// Moving Alice's counter into the beneficiary's implied claims would let her share be claimed twice.
+#[test]
+fn test_invalid_counter_changes_rejected() {
+    let result = verify_shared(
+        &TEAM,
+        &[ALICE, BOB],
+        200,
+        create_shared_data(10000, 0, 0, 200, &[0, 0]),
+        Some(create_shared_data(10000, 2500, 0, 201, &[1500, 1000])),
+    );
+    assert!(result.is_err(), "Should fail - two beneficiaries claim at once");
+    assert_eq!(extract_error_code(&result), Some(105)); // Error::InvalidBeneficiaryClaims
+
+    let result = verify_shared(
+        &TEAM,
+        &[BENEFICIARY],
+        200,
+        create_shared_data(10000, 1500, 0, 200, &[1500, 0]),
+        Some(create_shared_data(10000, 1500, 0, 201, &[0, 0])),
+    );
+    assert!(result.is_err(), "Should fail - Alice's counter decreased");
+    assert_eq!(extract_error_code(&result), Some(105)); // Error::InvalidBeneficiaryClaims
+}
+
+/// Tests that a shared cell continues until the last claimant settles it.
+/// Once fully vested, Alice's claim keeps the cell for Bob, whose final claim consumes it.
+#[test]
+fn test_shared_cell_continues_until_settled() {
+    let result = verify_shared(
+        &TEAM,
+        &[ALICE],
+        300,
+        create_shared_data(10000, 0, 0, 200, &[0, 0]),
+        Some(create_shared_data(10000, 3000, 0, 201, &[3000, 0])),
+    );
+    assert!(result.is_ok(), "Should succeed - Alice's claim continues the cell, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_shared(
+        &TEAM,
+        &[BOB],
+        300,
+        create_shared_data(10000, 8000, 0, 200, &[3000, 0]),
+        None,
+    );
+    assert!(result.is_ok(), "Should succeed - Bob settles the cell, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_shared(
+        &TEAM,
+        &[BENEFICIARY],
+        300,
+        create_shared_data(10000, 3000, 0, 200, &[3000, 0]),
+        None,
+    );
+    assert!(result.is_err(), "Should fail - consuming the cell would claim Bob's share");
+    assert_eq!(extract_error_code(&result), Some(105)); // Error::InvalidBeneficiaryClaims
+}
+
+/// Tests that every beneficiary keeps its share of what a termination left.
+/// After the creator reclaimed 5000 at epoch 200, Alice's share of the remaining 5000 is 1500.
+#[test]
+fn test_shared_claims_after_termination() {
+    let result = verify_shared(
+        &TEAM,
+        &[ALICE],
+        250,
+        create_shared_data(10000, 0, 5000, 200, &[0, 0]),
+        Some(create_shared_data(10000, 1500, 5000, 201, &[1500, 0])),
+    );
+    assert!(result.is_ok(), "Should succeed - Alice claims after termination, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that shared cells must carry exactly one counter per co-beneficiary within the aggregate.
+/// Anonymous updates must also carry the counters forward unchanged.
+#[test]
+fn test_invalid_claim_counters_rejected() {
+    let cases = [
+        ("no extension area", create_vesting_data(10000, 0, 0, 200), create_vesting_data(10000, 0, 0, 201)),
+        ("missing counter", create_shared_data(10000, 0, 0, 200, &[0]), create_shared_data(10000, 0, 0, 201, &[0])),
+        ("counters exceed aggregate", create_shared_data(10000, 1000, 0, 200, &[1000, 1]), create_shared_data(10000, 1000, 0, 201, &[1000, 1])),
+        ("changed by update", create_shared_data(10000, 1000, 0, 200, &[1000, 0]), create_shared_data(10000, 1000, 0, 201, &[0, 1000])),
+    ];
+
+    for (label, input_data, output_data) in cases {
+        let result = verify_shared(&TEAM, &[], 200, input_data, Some(output_data));
+        assert!(result.is_err(), "Case {} should fail", label);
+        assert_eq!(extract_error_code(&result), Some(59), "Case {} should fail with InvalidDataExtension", label);
+    }
+}
+
+/// Tests that malformed co-beneficiary lists are rejected as invalid arguments.
+/// Co-beneficiaries must be distinct from each other and the beneficiary, with non-zero shares totalling at most 10000.
+#[test]
+fn test_invalid_co_beneficiaries_rejected() {
+    let cases: [(&str, Vec<(u8, u16)>); 6] = [
+        ("empty list", vec![]),
+        ("zero share", vec![(ALICE, 0)]),
+        ("shares above 10000", vec![(ALICE, 6000), (BOB, 4001)]),
+        ("repeated co-beneficiary", vec![(ALICE, 3000), (ALICE, 2000)]),
+        ("beneficiary listed", vec![(BENEFICIARY, 3000)]),
+        ("too many co-beneficiaries", vec![(ALICE, 1000), (BOB, 1000), (5, 1000), (6, 1000), (7, 1000)]),
+    ];
+
+    for (label, co_beneficiaries) in cases {
+        let result = verify_shared(
+            &co_beneficiaries,
+            &[CREATOR],
+            200,
+            create_shared_data(10000, 0, 0, 200, &[0, 0]),
+            Some(create_shared_data(10000, 0, 5000, 201, &[0, 0])),
+        );
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS), "Case {} should fail with invalid args", label);
+    }
+}
\ No newline at end of file
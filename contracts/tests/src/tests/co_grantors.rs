@@ -0,0 +1,147 @@
+use super::helpers::*;
+use ckb_testtool::context::Context;
+
+const OPTION_CO_GRANTORS: u8 = 0x0f;
+
+/// A termination of a jointly funded grant and how the reclaimed amount is split.
+struct Termination {
+    co_grantor_signs: bool,
+    co_grantor_share: u16,
+    co_grantor_payout: u64,
+    creator_payout: u64,
+    extra_grantors: Vec<([u8; 32], u16)>,
+    list_creator: bool,
+}
+
+impl Default for Termination {
+    fn default() -> Self {
+        Termination {
+            co_grantor_signs: true,
+            co_grantor_share: 4000,
+            co_grantor_payout: 2000,
+            creator_payout: 3000,
+            extra_grantors: Vec::new(),
+            list_creator: false,
+        }
+    }
+}
+
+/// Encodes co-grantor records as the option value: lock hash followed by share in bps.
+fn co_grantors_option(grantors: &[([u8; 32], u16)]) -> Vec<u8> {
+    let mut value = Vec::new();
+    for (lock_hash, share_bps) in grantors {
+        value.extend_from_slice(lock_hash);
+        value.extend_from_slice(&share_bps.to_le_bytes());
+    }
+    value
+}
+
+/// Builds and verifies a termination of a 10000 grant at epoch 200, reclaiming the unvested 5000.
+/// The signer's output returns its authorization input plus its payout.
+fn verify_termination(termination: Termination) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let (_beneficiary_lock, _beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+    let (co_grantor_lock, co_grantor_hash) = create_always_success_lock_with_args(&mut context, vec![3u8]);
+
+    let mut grantors = vec![(co_grantor_hash, termination.co_grantor_share)];
+    grantors.extend(termination.extra_grantors.iter().copied());
+    if termination.list_creator {
+        grantors.push((creator_hash, 1000));
+    }
+
+    let signer_lock = if termination.co_grantor_signs { co_grantor_lock.clone() } else { creator_lock.clone() };
+    let (co_grantor_change, creator_change) = if termination.co_grantor_signs { (AUTH_INPUT_CAPACITY, 0) } else { (0, AUTH_INPUT_CAPACITY) };
+
+    let tx = build_termination_tx(
+        &mut context,
+        &[(OPTION_CO_GRANTORS, co_grantors_option(&grantors))],
+        &[signer_lock],
+        &[
+            (co_grantor_lock, co_grantor_change + termination.co_grantor_payout),
+            (creator_lock, creator_change + termination.creator_payout),
+        ],
+    );
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that a co-grantor can terminate on its own and the reclaimed amount is split by share.
+/// The co-grantor holds 4000 bps and receives 2000; the creator keeps 6000 bps and receives 3000.
+#[test]
+fn test_co_grantor_terminates_with_split() {
+    let result = verify_termination(Termination::default());
+    assert!(result.is_ok(), "Should succeed - co-grantor termination with split, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the primary creator can still terminate a jointly funded grant.
+/// The co-grantor must receive its share even though it did not sign.
+#[test]
+fn test_creator_terminates_with_split() {
+    let result = verify_termination(Termination { co_grantor_signs: false, ..Termination::default() });
+    assert!(result.is_ok(), "Should succeed - creator termination with split, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a co-grantor receiving less than its share is rejected.
+/// 4000 bps of 5000 is 2000, so 1999 falls short.
+#[test]
+fn test_co_grantor_short_share_rejected() {
+    let result = verify_termination(Termination { co_grantor_payout: 1999, creator_payout: 3001, ..Termination::default() });
+    assert!(result.is_err(), "Should fail - co-grantor received less than its share");
+    assert_eq!(extract_error_code(&result), Some(62)); // Error::InsufficientGrantorShare
+}
+
+/// Tests that the primary creator's remainder share is enforced as well.
+/// A co-grantor cannot terminate and keep the whole reclaimed amount.
+#[test]
+fn test_creator_short_share_rejected() {
+    let result = verify_termination(Termination { co_grantor_payout: 5000, creator_payout: 0, ..Termination::default() });
+    assert!(result.is_err(), "Should fail - creator received less than its remainder share");
+    assert_eq!(extract_error_code(&result), Some(62)); // Error::InsufficientGrantorShare
+}
+
+/// Tests that every listed co-grantor must be paid, not only the one that signed.
+/// The extra co-grantor holds 1000 bps and is owed 500, but the transaction has no output for it.
+#[test]
+fn test_unpaid_extra_co_grantor_rejected() {
+    let result = verify_termination(Termination {
+        extra_grantors: vec![(create_dummy_lock_hash(4), 1000)],
+        ..Termination::default()
+    });
+    assert!(result.is_err(), "Should fail - extra co-grantor received nothing");
+    assert_eq!(extract_error_code(&result), Some(62)); // Error::InsufficientGrantorShare
+}
+
+/// Tests that shares totalling more than 10000 bps are rejected at parse time.
+/// The creator's remainder would otherwise be negative.
+#[test]
+fn test_co_grantor_shares_over_total_rejected() {
+    let result = verify_termination(Termination {
+        extra_grantors: vec![(create_dummy_lock_hash(4), 6001)],
+        ..Termination::default()
+    });
+    assert!(result.is_err(), "Should fail - co-grantor shares exceed 10000 bps");
+    assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS));
+}
+
+/// Tests that zero shares, repeated grantors, the creator itself and a fifth co-grantor are rejected.
+/// Each would make the split ambiguous or let one lock claim a share twice.
+#[test]
+fn test_invalid_co_grantor_lists_rejected() {
+    let invalid_lists = [
+        Termination { co_grantor_share: 0, ..Termination::default() },
+        Termination {
+            extra_grantors: vec![(create_dummy_lock_hash(4), 1000), (create_dummy_lock_hash(4), 1000)],
+            ..Termination::default()
+        },
+        Termination { list_creator: true, ..Termination::default() },
+        Termination {
+            extra_grantors: (4..8).map(|value| (create_dummy_lock_hash(value), 100)).collect(),
+            ..Termination::default()
+        },
+    ];
+
+    for termination in invalid_lists {
+        let result = verify_termination(termination);
+        assert!(result.is_err(), "Should fail - invalid co-grantor list");
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS));
+    }
+}
\ No newline at end of file
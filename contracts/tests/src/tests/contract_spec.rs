@@ -0,0 +1,225 @@
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+// The script sources and the spec generated from them.
+const ERROR_SOURCE: &str = include_str!("../../../contracts/vesting_lock/src/error.rs");
+const MAIN_SOURCE: &str = include_str!("../../../contracts/vesting_lock/src/main.rs");
+const CONTRACT_SPEC: &str = include_str!("../../../contracts/vesting_lock/spec.json");
+
+// Syscall failures can surface from any validator through the ? operator.
+const SYSCALL_STAGE: &str = "CKB syscall errors";
+
+// Highest code released before the stage blocks; codes up to it keep their numbers wherever they fall.
+const LAST_RELEASED_CODE: u64 = 64;
+
+/// A validation stage parsed from a section comment in error.rs.
+/// Holds the block new codes of the stage come from and the variants declared in that section.
+struct Stage {
+    name: String,
+    first_new_code: u64,
+    last_new_code: u64,
+    errors: Vec<(String, u64)>,
+}
+
+/// Parses the validation stages from the Error enum in error.rs.
+/// Each section comment names a stage and ends with its block of new codes, such as "Header errors (new codes 81-88)".
+fn parse_stages(source: &str) -> Vec<Stage> {
+    let mut stages: Vec<Stage> = Vec::new();
+
+    let body = source
+        .split("pub enum Error {")
+        .nth(1)
+        .and_then(|rest| rest.split("\n}").next())
+        .expect("error.rs should define the Error enum");
+
+    for line in body.lines().map(str::trim) {
+        if line.starts_with("///") {
+            continue;
+        } else if let Some(comment) = line.strip_prefix("//") {
+            let (name, range) = comment
+                .trim()
+                .strip_suffix(')')
+                .and_then(|comment| comment.split_once(" (new codes "))
+                .expect("section comment should end with a block of new codes");
+            let (first_new_code, last_new_code) = range
+                .split_once('-')
+                .and_then(|(first, last)| Some((first.parse().ok()?, last.parse().ok()?)))
+                .expect("code block should be two numbers");
+            stages.push(Stage { name: name.to_string(), first_new_code, last_new_code, errors: Vec::new() });
+        } else if let Some((name, rest)) = line.split_once(" = ") {
+            let code: u64 = rest
+                .split(',')
+                .next()
+                .and_then(|code| code.trim().parse().ok())
+                .expect("variant should have a numeric code");
+            stages
+                .last_mut()
+                .expect("variant should follow a section comment")
+                .errors
+                .push((name.to_string(), code));
+        }
+    }
+
+    stages
+}
+
+/// Collects the Error variants each validate_* function in main.rs returns directly.
+/// Errors propagated from called functions belong to those functions and are not counted.
+fn parse_validators(source: &str) -> Vec<(String, Vec<String>)> {
+    let mut validators = Vec::new();
+
+    for chunk in source.split("\nfn ").skip(1) {
+        let name: String = chunk.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+        if !name.starts_with("validate_") {
+            continue;
+        }
+
+        // Function bodies end at the first closing brace in column zero.
+        let body = chunk.split("\n}").next().unwrap_or(chunk);
+        let mut errors: Vec<String> = Vec::new();
+        for (offset, _) in body.match_indices("Error::") {
+            // Skip qualified paths of other error types such as SysError.
+            let preceding = body[..offset].chars().last();
+            if preceding.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                continue;
+            }
+            let variant: String = body[offset + "Error::".len()..]
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .collect();
+            if !errors.contains(&variant) {
+                errors.push(variant);
+            }
+        }
+        validators.push((name, errors));
+    }
+
+    validators
+}
+
+/// Finds the stage that declares the given Error variant.
+/// Panics on unknown variants so a stale spec fails loudly.
+fn stage_of<'a>(stages: &'a [Stage], variant: &str) -> &'a Stage {
+    stages
+        .iter()
+        .find(|stage| stage.errors.iter().any(|(name, _)| name == variant))
+        .unwrap_or_else(|| panic!("Error::{} is not declared in error.rs", variant))
+}
+
+/// Generates the contract spec from error.rs and main.rs.
+/// Each validator is assigned the stage of the first non-syscall error it returns.
+fn generate_contract_spec(error_source: &str, main_source: &str) -> Value {
+    let stages = parse_stages(error_source);
+
+    let stage_entries: Vec<Value> = stages
+        .iter()
+        .map(|stage| {
+            let errors: Vec<Value> = stage
+                .errors
+                .iter()
+                .map(|(name, code)| json!({ "name": name, "code": code }))
+                .collect();
+            json!({
+                "name": stage.name,
+                "first_new_code": stage.first_new_code,
+                "last_new_code": stage.last_new_code,
+                "errors": errors,
+            })
+        })
+        .collect();
+
+    let validator_entries: Vec<Value> = parse_validators(main_source)
+        .into_iter()
+        .map(|(name, errors)| {
+            let stage = errors
+                .iter()
+                .map(|variant| stage_of(&stages, variant))
+                .find(|stage| stage.name != SYSCALL_STAGE)
+                .map(|stage| stage.name.clone())
+                .unwrap_or_else(|| panic!("Validator {} returns no stage errors", name));
+            json!({ "name": name, "stage": stage, "errors": errors })
+        })
+        .collect();
+
+    json!({ "stages": stage_entries, "validators": validator_entries })
+}
+
+/// Tests that the committed spec.json matches the spec generated from the script sources.
+/// Run with UPDATE_CONTRACT_SPEC=1 to regenerate the file after changing errors or validators.
+#[test]
+fn test_contract_spec_in_sync() {
+    let generated = generate_contract_spec(ERROR_SOURCE, MAIN_SOURCE);
+
+    if std::env::var("UPDATE_CONTRACT_SPEC").is_ok() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("../contracts/vesting_lock/spec.json");
+        let json = serde_json::to_string_pretty(&generated).expect("json");
+        std::fs::write(path, json + "\n").expect("write spec.json");
+        return;
+    }
+
+    let committed: Value = serde_json::from_str(CONTRACT_SPEC).expect("spec.json should be valid JSON");
+    assert_eq!(generated, committed, "spec.json is out of date; rerun with UPDATE_CONTRACT_SPEC=1");
+}
+
+/// Tests that codes added after the release come from their stage's block and that the blocks do not overlap.
+/// Tools categorize new failures by block, so a misplaced code would be reported under the wrong stage.
+#[test]
+fn test_new_codes_within_stage_blocks() {
+    let stages = parse_stages(ERROR_SOURCE);
+
+    for stage in &stages {
+        assert!(stage.first_new_code <= stage.last_new_code, "Stage {} has an empty block", stage.name);
+        for (name, code) in &stage.errors {
+            let block = stages.iter().find(|block| (block.first_new_code..=block.last_new_code).contains(code));
+            match block {
+                Some(block) => assert!(
+                    block.name == stage.name,
+                    "Error::{} = {} is in the {} block {}-{}",
+                    name, code, block.name, block.first_new_code, block.last_new_code
+                ),
+                None => assert!(
+                    *code <= LAST_RELEASED_CODE,
+                    "Error::{} = {} is outside the {} block {}-{}",
+                    name, code, stage.name, stage.first_new_code, stage.last_new_code
+                ),
+            }
+        }
+    }
+
+    let mut blocks: Vec<&Stage> = stages.iter().collect();
+    blocks.sort_by_key(|stage| stage.first_new_code);
+    for pair in blocks.windows(2) {
+        assert!(pair[0].last_new_code < pair[1].first_new_code, "Stages {} and {} overlap", pair[0].name, pair[1].name);
+    }
+}
+
+/// Tests that every validator returns only codes of its assigned stage.
+/// Assignments come from the committed spec.json, so moving a validator to another stage is a reviewed change.
+#[test]
+fn test_validators_return_codes_from_assigned_stage() {
+    let stages = parse_stages(ERROR_SOURCE);
+    let committed: Value = serde_json::from_str(CONTRACT_SPEC).expect("spec.json should be valid JSON");
+    let assigned = committed["validators"].as_array().expect("validators should be an array");
+
+    for (name, errors) in parse_validators(MAIN_SOURCE) {
+        let stage_name = assigned
+            .iter()
+            .find(|validator| validator["name"] == name.as_str())
+            .and_then(|validator| validator["stage"].as_str())
+            .unwrap_or_else(|| panic!("Validator {} has no assigned stage in spec.json", name));
+        let stage = stages
+            .iter()
+            .find(|stage| stage.name == stage_name)
+            .unwrap_or_else(|| panic!("Validator {} is assigned to unknown stage {}", name, stage_name));
+
+        for variant in errors {
+            let error_stage = stage_of(&stages, &variant);
+            assert!(
+                error_stage.name == SYSCALL_STAGE || error_stage.name == stage.name,
+                "Validator {} returns Error::{} outside its {} stage",
+                name, variant, stage.name
+            );
+        }
+    }
+}
\ No newline at end of file
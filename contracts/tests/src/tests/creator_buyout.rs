@@ -0,0 +1,177 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+// Capacity of each authorization input, returned to its owner as change.
+const AUTH_CAPACITY: u64 = 6100000000;
+
+/// Parties that sign a buyout and how it pays the beneficiary.
+struct Buyout {
+    creator_signs: bool,
+    beneficiary_signs: bool,
+    witness: Bytes,
+    beneficiary_net_payment: u64,
+    keep_continuation: bool,
+}
+
+impl Default for Buyout {
+    fn default() -> Self {
+        Buyout {
+            creator_signs: true,
+            beneficiary_signs: true,
+            witness: create_buyout_witness(7000),
+            beneficiary_net_payment: 7000,
+            keep_continuation: false,
+        }
+    }
+}
+
+/// Builds and verifies a buyout of a 10000 grant at epoch 200, when 5000 has vested.
+/// The beneficiary output returns their authorization input plus the net payment.
+fn verify_buyout(buyout: Buyout) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .header_dep(header_hash)
+        // The vesting input is at index 0, so its witness is the first one.
+        .witness(buyout.witness.pack());
+
+    let mut beneficiary_output_capacity = buyout.beneficiary_net_payment;
+    if buyout.beneficiary_signs {
+        let beneficiary_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(AUTH_CAPACITY.pack())
+                .lock(beneficiary_lock.clone())
+                .build(),
+            Bytes::new(),
+        );
+        builder = builder.input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build());
+        beneficiary_output_capacity += AUTH_CAPACITY;
+    }
+
+    if buyout.creator_signs {
+        let creator_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(AUTH_CAPACITY.pack())
+                .lock(creator_lock.clone())
+                .build(),
+            Bytes::new(),
+        );
+        builder = builder.input(CellInput::new_builder().previous_output(creator_input_out_point).build());
+    }
+
+    builder = builder
+        .output(CellOutput::new_builder()
+            .capacity(beneficiary_output_capacity.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .output(CellOutput::new_builder()
+            .capacity(AUTH_CAPACITY.pack())
+            .lock(creator_lock)
+            .build())
+        .output_data(Bytes::new().pack());
+
+    if buyout.keep_continuation {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(3161u64.pack())
+                .lock(lock_script)
+                .build())
+            .output_data(create_vesting_data(10000, 5000, 0, 201).pack());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that both parties can end the grant for an agreed payment above the vested amount.
+/// The beneficiary receives 7000 for a grant with 5000 vested; the creator keeps the rest.
+#[test]
+fn test_buyout_valid() {
+    let result = verify_buyout(Buyout::default());
+    assert!(result.is_ok(), "Should succeed - jointly signed buyout, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the creator alone cannot buy out the beneficiary.
+/// A buyout changes the agreement, so the beneficiary must co-sign.
+#[test]
+fn test_buyout_without_beneficiary_rejected() {
+    let result = verify_buyout(Buyout { beneficiary_signs: false, ..Buyout::default() });
+    assert!(result.is_err(), "Should fail - beneficiary did not co-sign");
+    assert_eq!(extract_error_code(&result), Some(25)); // Error::Unauthorized
+}
+
+/// Tests that the beneficiary alone cannot trigger a buyout.
+/// Ending the schedule early also needs the creator's consent.
+#[test]
+fn test_buyout_without_creator_rejected() {
+    let result = verify_buyout(Buyout { creator_signs: false, ..Buyout::default() });
+    assert!(result.is_err(), "Should fail - creator did not sign");
+    assert_eq!(extract_error_code(&result), Some(25)); // Error::Unauthorized
+}
+
+/// Tests that the agreed amount cannot be lower than the vested, unclaimed amount.
+/// At epoch 200 the beneficiary already owns 5000, so 4999 is rejected even with both signatures.
+#[test]
+fn test_buyout_below_vested_rejected() {
+    let result = verify_buyout(Buyout {
+        witness: create_buyout_witness(4999),
+        beneficiary_net_payment: 4999,
+        ..Buyout::default()
+    });
+    assert!(result.is_err(), "Should fail - buyout below the vested amount");
+    assert_eq!(extract_error_code(&result), Some(61)); // Error::InvalidBuyout
+}
+
+/// Tests that the beneficiary must actually net the agreed amount.
+/// Their own change is subtracted, so paying one shannon short fails.
+#[test]
+fn test_buyout_underpaid_rejected() {
+    let result = verify_buyout(Buyout { beneficiary_net_payment: 6999, ..Buyout::default() });
+    assert!(result.is_err(), "Should fail - beneficiary received less than agreed");
+    assert_eq!(extract_error_code(&result), Some(46)); // Error::InsufficientPayout
+}
+
+/// Tests that a buyout must consume the vesting cell.
+/// Keeping a continuation would leave a schedule the buyout was meant to cancel.
+#[test]
+fn test_buyout_with_continuation_rejected() {
+    let result = verify_buyout(Buyout { keep_continuation: true, ..Buyout::default() });
+    assert!(result.is_err(), "Should fail - buyout kept a continuation cell");
+    assert_eq!(extract_error_code(&result), Some(61)); // Error::InvalidBuyout
+}
+
+/// Tests that a buyout declaration with the wrong length is rejected.
+/// The operation byte selects the buyout, so a rotation-sized value is malformed.
+#[test]
+fn test_malformed_buyout_rejected() {
+    let result = verify_buyout(Buyout { witness: create_rotation_witness(8, 0), ..Buyout::default() });
+    assert!(result.is_err(), "Should fail - malformed buyout declaration");
+    assert_eq!(extract_error_code(&result), Some(61)); // Error::InvalidBuyout
+}
\ No newline at end of file
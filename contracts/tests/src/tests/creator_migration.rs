@@ -0,0 +1,294 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const ROTATION_INITIATE: u8 = 1;
+const ROTATION_VETO: u8 = 2;
+const ROTATION_FINALIZE: u8 = 3;
+
+const OPTION_MIGRATION_DELAY: u8 = 0x07;
+const OPTION_PENDING_MIGRATION: u8 = 0x08;
+const MIGRATION_DELAY: u64 = 100;
+
+/// Party that authorizes the rotation.
+#[derive(Clone, Copy)]
+enum Signer {
+    Creator,
+    Beneficiary,
+    Anonymous,
+}
+
+/// Builds the delay option record used by every migration-enabled cell.
+fn delay_option() -> (u8, Vec<u8>) {
+    (OPTION_MIGRATION_DELAY, MIGRATION_DELAY.to_le_bytes().to_vec())
+}
+
+/// Builds a pending migration option record for the given new lock hash and effective block.
+fn pending_option(new_lock_hash: [u8; 32], effective_block: u64) -> (u8, Vec<u8>) {
+    let mut value = new_lock_hash.to_vec();
+    value.extend_from_slice(&effective_block.to_le_bytes());
+    (OPTION_PENDING_MIGRATION, value)
+}
+
+/// Builds and verifies a rotation that relocks the cell from the input options to the output options.
+/// The input tracks block 200, and the output creator hash defaults to the current creator.
+fn verify_rotation(
+    signer: Signer,
+    operation: u8,
+    header_block: u64,
+    input_options: &[(u8, Vec<u8>)],
+    output_options: &[(u8, Vec<u8>)],
+    output_creator_hash: Option<[u8; 32]>,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let input_args = create_vesting_args_with_options(
+        create_vesting_args(creator_hash, beneficiary_hash, 100, 300, 120),
+        input_options,
+    );
+    let output_args = create_vesting_args_with_options(
+        create_vesting_args(output_creator_hash.unwrap_or(creator_hash), beneficiary_hash, 100, 300, 120),
+        output_options,
+    );
+
+    let input_lock_script = context.build_script(&out_point, input_args).expect("script");
+    let output_lock_script = context.build_script(&out_point, output_args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, header_block, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(input_lock_script)
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(output_lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, header_block).pack())
+        .header_dep(header_hash)
+        .witness(create_rotation_witness(operation, 0).pack());
+
+    let auth_lock = match signer {
+        Signer::Creator => Some(creator_lock),
+        Signer::Beneficiary => Some(beneficiary_lock),
+        Signer::Anonymous => None,
+    };
+    if let Some(auth_lock) = auth_lock {
+        let auth_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(auth_lock)
+                .build(),
+            Bytes::new(),
+        );
+        builder = builder.input(CellInput::new_builder().previous_output(auth_input_out_point).build());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that the creator can initiate a migration that becomes effective after the delay.
+/// Header block 201 plus the 100-block delay gives effective block 301.
+#[test]
+fn test_creator_initiates_migration() {
+    let result = verify_rotation(
+        Signer::Creator,
+        ROTATION_INITIATE,
+        201,
+        &[delay_option()],
+        &[delay_option(), pending_option(create_dummy_lock_hash(9), 301)],
+        None,
+    );
+    assert!(result.is_ok(), "Should succeed - migration initiated, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that initiating with a shortened delay is rejected.
+/// The effective block is fixed by the configured delay, not chosen by the creator.
+#[test]
+fn test_initiate_with_short_delay_rejected() {
+    let result = verify_rotation(
+        Signer::Creator,
+        ROTATION_INITIATE,
+        201,
+        &[delay_option()],
+        &[delay_option(), pending_option(create_dummy_lock_hash(9), 300)],
+        None,
+    );
+    assert!(result.is_err(), "Should fail - effective block is before the delay");
+    assert_eq!(extract_error_code(&result), Some(56)); // Error::InvalidRotation
+}
+
+/// Tests that only the creator can initiate a migration.
+/// The beneficiary must not be able to replace the creator.
+#[test]
+fn test_beneficiary_cannot_initiate_migration() {
+    let result = verify_rotation(
+        Signer::Beneficiary,
+        ROTATION_INITIATE,
+        201,
+        &[delay_option()],
+        &[delay_option(), pending_option(create_dummy_lock_hash(9), 301)],
+        None,
+    );
+    assert!(result.is_err(), "Should fail - beneficiary initiated a creator migration");
+    assert_eq!(extract_error_code(&result), Some(25)); // Error::Unauthorized
+}
+
+/// Tests that migration is unavailable unless the cell opted in with a delay.
+/// Legacy cells keep their creator for life.
+#[test]
+fn test_initiate_without_delay_option_rejected() {
+    let result = verify_rotation(
+        Signer::Creator,
+        ROTATION_INITIATE,
+        201,
+        &[],
+        &[pending_option(create_dummy_lock_hash(9), 301)],
+        None,
+    );
+    assert!(result.is_err(), "Should fail - migration not enabled");
+    assert_eq!(extract_error_code(&result), Some(56)); // Error::InvalidRotation
+}
+
+/// Tests that the beneficiary can veto a pending migration.
+/// The relocked cell drops the pending record and keeps the original creator.
+#[test]
+fn test_beneficiary_vetoes_migration() {
+    let result = verify_rotation(
+        Signer::Beneficiary,
+        ROTATION_VETO,
+        250,
+        &[delay_option(), pending_option(create_dummy_lock_hash(9), 301)],
+        &[delay_option()],
+        None,
+    );
+    assert!(result.is_ok(), "Should succeed - migration vetoed, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the creator cannot veto on the beneficiary's behalf.
+/// Otherwise a leaked creator key could block the legitimate migration.
+#[test]
+fn test_creator_cannot_veto_migration() {
+    let result = verify_rotation(
+        Signer::Creator,
+        ROTATION_VETO,
+        250,
+        &[delay_option(), pending_option(create_dummy_lock_hash(9), 301)],
+        &[delay_option()],
+        None,
+    );
+    assert!(result.is_err(), "Should fail - creator vetoed");
+    assert_eq!(extract_error_code(&result), Some(25)); // Error::Unauthorized
+}
+
+/// Tests that a migration cannot be finalized before its effective block.
+/// The veto window must stay open for the full delay.
+#[test]
+fn test_finalize_before_effective_block_rejected() {
+    let new_creator_hash = create_dummy_lock_hash(9);
+    let result = verify_rotation(
+        Signer::Anonymous,
+        ROTATION_FINALIZE,
+        300,
+        &[delay_option(), pending_option(new_creator_hash, 301)],
+        &[delay_option()],
+        Some(new_creator_hash),
+    );
+    assert!(result.is_err(), "Should fail - migration not yet effective");
+    assert_eq!(extract_error_code(&result), Some(57)); // Error::RotationNotReady
+}
+
+/// Tests that anyone can finalize a migration at its effective block.
+/// The relocked cell carries the new creator hash and no pending record.
+#[test]
+fn test_finalize_at_effective_block() {
+    let new_creator_hash = create_dummy_lock_hash(9);
+    let result = verify_rotation(
+        Signer::Anonymous,
+        ROTATION_FINALIZE,
+        301,
+        &[delay_option(), pending_option(new_creator_hash, 301)],
+        &[delay_option()],
+        Some(new_creator_hash),
+    );
+    assert!(result.is_ok(), "Should succeed - migration finalized, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that finalizing must actually install the pending creator hash.
+/// Dropping the pending record without applying it is rejected.
+#[test]
+fn test_finalize_keeping_old_creator_rejected() {
+    let result = verify_rotation(
+        Signer::Anonymous,
+        ROTATION_FINALIZE,
+        301,
+        &[delay_option(), pending_option(create_dummy_lock_hash(9), 301)],
+        &[delay_option()],
+        None,
+    );
+    assert!(result.is_err(), "Should fail - new creator hash not applied");
+    assert_eq!(extract_error_code(&result), Some(56)); // Error::InvalidRotation
+}
+
+
+/// Tests that a third party finalizing a migration cannot attach a type script to the relocked cell.
+/// Anyone may finalize, so the output must keep the input's type script exactly.
+#[test]
+fn test_finalize_with_foreign_type_script_rejected() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (_beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+    let (foreign_type, _foreign_type_hash) = create_always_success_lock_with_args(&mut context, vec![0x66u8]);
+    let new_creator_hash = create_dummy_lock_hash(9);
+
+    let input_args = create_vesting_args_with_options(
+        create_vesting_args(creator_hash, beneficiary_hash, 100, 300, 120),
+        &[delay_option(), pending_option(new_creator_hash, 301)],
+    );
+    let output_args = create_vesting_args_with_options(
+        create_vesting_args(new_creator_hash, beneficiary_hash, 100, 300, 120),
+        &[delay_option()],
+    );
+
+    let input_lock_script = context.build_script(&out_point, input_args).expect("script");
+    let output_lock_script = context.build_script(&out_point, output_args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 301, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(input_lock_script)
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(output_lock_script)
+            .type_(Some(foreign_type).pack())
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 301).pack())
+        .header_dep(header_hash)
+        .witness(create_rotation_witness(ROTATION_FINALIZE, 0).pack())
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - the relocked cell gained a type script");
+    assert_eq!(extract_error_code(&result), Some(56)); // Error::InvalidRotation
+}
\ No newline at end of file
@@ -0,0 +1,133 @@
+use super::helpers::*;
+use ckb_testtool::ckb_types::packed::Script;
+use ckb_testtool::context::Context;
+
+const OPTION_CREATOR_AUTH_POLICY: u8 = 0x01;
+const OPTION_CO_GRANTORS: u8 = 0x0f;
+const OPTION_CREATOR_SIGNERS: u8 = 0x1b;
+
+// Args of the always-success locks standing in for each party
+const BENEFICIARY: u8 = 1;
+const CREATOR: u8 = 2;
+const ALICE: u8 = 3;
+const BOB: u8 = 4;
+const CAROL: u8 = 5;
+
+/// Returns the lock hash of a party's always-success lock.
+/// Scripts reference the always-success code by its data hash, so the hash is the same in every context.
+fn party_lock_hash(party: u8) -> [u8; 32] {
+    create_always_success_lock_with_args(&mut Context::default(), vec![party]).1
+}
+
+/// Creates a creator signers option value from the threshold and the signers' parties.
+/// The value is threshold (1) followed by each signer's lock hash (32).
+fn create_signers_option(threshold: u8, signers: &[u8]) -> Vec<u8> {
+    let mut value = vec![threshold];
+    for signer in signers {
+        value.extend_from_slice(&party_lock_hash(*signer));
+    }
+    value
+}
+
+/// The signer set used by most tests: any two of Alice, Bob and Carol.
+fn two_of_three() -> (u8, Vec<u8>) {
+    (OPTION_CREATOR_SIGNERS, create_signers_option(2, &[ALICE, BOB, CAROL]))
+}
+
+/// Builds and verifies a termination of a 10000 grant at epoch 200, reclaiming the unvested 5000.
+/// Each party in inputs spends one cell with its always-success lock, so a repeated party spends several.
+fn verify_termination(
+    options: Vec<(u8, Vec<u8>)>,
+    inputs: &[u8],
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let signers: Vec<Script> = inputs
+        .iter()
+        .map(|party| create_always_success_lock_with_args(&mut context, vec![*party]).0)
+        .collect();
+    let tx = build_termination_tx(&mut context, &options, &signers, &[]);
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that enough distinct signers together authorize a termination.
+/// Any two of the three signers meet the threshold, as do all three.
+#[test]
+fn test_threshold_signers_terminate() {
+    for inputs in [vec![ALICE, BOB], vec![BOB, CAROL], vec![ALICE, BOB, CAROL]] {
+        let result = verify_termination(vec![two_of_three()], &inputs);
+        assert!(result.is_ok(), "Signers {:?} should terminate, got error code: {:?}", inputs, extract_error_code(&result));
+    }
+}
+
+/// Tests that a termination below the threshold is not authorized as the creator.
+/// Without the creator role the transaction is an anonymous update, which may not change the claimed amounts.
+#[test]
+fn test_signers_below_threshold_rejected() {
+    let result = verify_termination(vec![two_of_three()], &[ALICE]);
+    assert!(result.is_err(), "Should fail - one signer is below the threshold");
+    assert_eq!(extract_error_code(&result), Some(17)); // Error::InvalidStateChange
+}
+
+/// Tests that a signer spending several cells still counts once toward the threshold.
+/// Two cells of Alice's are not two signers.
+#[test]
+fn test_repeated_signer_counts_once() {
+    let result = verify_termination(vec![two_of_three()], &[ALICE, ALICE]);
+    assert!(result.is_err(), "Should fail - Alice counts once");
+    assert_eq!(extract_error_code(&result), Some(17)); // Error::InvalidStateChange
+}
+
+/// Tests that the creator lock no longer authorizes the creator role on its own.
+/// The creator's cell adds nothing toward the signer threshold unless the creator is a signer.
+#[test]
+fn test_creator_lock_alone_rejected() {
+    let result = verify_termination(vec![two_of_three()], &[CREATOR]);
+    assert!(result.is_err(), "Should fail - the creator lock is not a signer");
+    assert_eq!(extract_error_code(&result), Some(17)); // Error::InvalidStateChange
+
+    let result = verify_termination(
+        vec![(OPTION_CREATOR_SIGNERS, create_signers_option(2, &[CREATOR, ALICE]))],
+        &[CREATOR, ALICE],
+    );
+    assert!(result.is_ok(), "Should succeed - the creator is one of two signers, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a one-of-one signer set hands the creator role to another lock.
+/// Bob alone terminates while the creator lock is left only to receive reclaimed funds.
+#[test]
+fn test_single_signer_replaces_creator() {
+    let options = vec![(OPTION_CREATOR_SIGNERS, create_signers_option(1, &[BOB]))];
+
+    let result = verify_termination(options.clone(), &[BOB]);
+    assert!(result.is_ok(), "Should succeed - Bob is the only signer, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_termination(options, &[BENEFICIARY]);
+    assert!(result.is_err(), "Should fail - the beneficiary cannot terminate");
+}
+
+/// Tests that malformed signer sets and options that could act as the creator alone are rejected as invalid arguments.
+/// The threshold must lie between 1 and the number of signers, which must be distinct whole lock hashes.
+#[test]
+fn test_invalid_signer_options_rejected() {
+    let mut truncated = create_signers_option(1, &[ALICE, BOB]);
+    truncated.pop();
+    let mut co_grantors = party_lock_hash(CAROL).to_vec();
+    co_grantors.extend_from_slice(&4000u16.to_le_bytes());
+    let mut creator_pattern = vec![1u8];
+    creator_pattern.extend_from_slice(&[0u8; 33]);
+
+    let cases = [
+        ("no signers", vec![(OPTION_CREATOR_SIGNERS, vec![1u8])]),
+        ("zero threshold", vec![(OPTION_CREATOR_SIGNERS, create_signers_option(0, &[ALICE, BOB]))]),
+        ("threshold above signers", vec![(OPTION_CREATOR_SIGNERS, create_signers_option(3, &[ALICE, BOB]))]),
+        ("repeated signer", vec![(OPTION_CREATOR_SIGNERS, create_signers_option(2, &[ALICE, ALICE]))]),
+        ("truncated hash", vec![(OPTION_CREATOR_SIGNERS, truncated)]),
+        ("with creator auth policy", vec![two_of_three(), (OPTION_CREATOR_AUTH_POLICY, creator_pattern)]),
+        ("with co-grantors", vec![two_of_three(), (OPTION_CO_GRANTORS, co_grantors)]),
+    ];
+
+    for (label, options) in cases {
+        let result = verify_termination(options, &[ALICE, BOB]);
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS), "Case {} should fail with invalid args", label);
+    }
+}
\ No newline at end of file
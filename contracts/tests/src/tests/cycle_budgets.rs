@@ -0,0 +1,363 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::{Cycle, TransactionBuilder, TransactionView}, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+use serde_json::{json, Value};
+
+// Cycles measured per operation class on the built binary, including the proxy authorization locks.
+const CYCLE_MEASUREMENTS: &str = "cycle_measurements.json";
+
+// Headroom of each budget above its measured cycles, so small changes pass while real regressions fail.
+const CYCLE_BUDGET_MARGIN_PERCENT: u64 = 25;
+
+// Builds the transaction that measures an operation class.
+type OperationBuilder = fn(&mut Context) -> TransactionView;
+
+// The measured operation classes and the transactions that measure them.
+const OPERATIONS: &[(&str, OperationBuilder)] = &[
+    ("partial_claim", build_partial_claim),
+    ("full_claim", build_full_claim),
+    ("termination", build_termination),
+    ("anonymous_update", build_anonymous_update),
+];
+
+/// Builds a partial beneficiary claim, the most frequent operation.
+fn build_partial_claim(context: &mut Context) -> TransactionView {
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(5161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 5000, 0, 201).pack())
+        .output(CellOutput::new_builder()
+            .capacity(5000u64.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(header_hash)
+        .build();
+    context.complete_tx(tx)
+}
+
+/// Builds a full beneficiary claim consuming the cell, which synthesizes the final state.
+fn build_full_claim(context: &mut Context) -> TransactionView {
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(context, 351, 350);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .build(),
+        create_vesting_data(10000, 0, 0, 350),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(header_hash)
+        .build();
+    context.complete_tx(tx)
+}
+
+/// Builds a partial creator termination, where the beneficiary keeps the vested amount.
+fn build_termination(context: &mut Context) -> TransactionView {
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (_beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let creator_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(creator_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    // At epoch 200: vested = 5000, creator reclaims the unvested 5000.
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(creator_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(5161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 5000, 201).pack())
+        .output(CellOutput::new_builder()
+            .capacity(5000u64.pack())
+            .lock(creator_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(header_hash)
+        .build();
+    context.complete_tx(tx)
+}
+
+/// Builds an anonymous block update, whose cost keepers refreshing highest_block_seen pay.
+fn build_anonymous_update(context: &mut Context) -> TransactionView {
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let args = create_vesting_args(
+        create_dummy_lock_hash(1),
+        create_dummy_lock_hash(2),
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(context, 251, 250);
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 251).pack())
+        .header_dep(header_hash)
+        .build();
+    context.complete_tx(tx)
+}
+
+/// Builds and verifies the transaction of an operation class, returning its cycles.
+fn measure(operation: &str) -> Cycle {
+    let (_, build) = OPERATIONS.iter().find(|(name, _)| *name == operation).expect("known operation");
+    let mut context = Context::default();
+    let tx = build(&mut context);
+    context
+        .verify_tx(&tx, MAX_CYCLES)
+        .unwrap_or_else(|err| panic!("{} should pass: {:?}", operation, err))
+}
+
+/// Returns the cycle budget of an operation class: its recorded measurement plus the margin.
+fn cycle_budget(operation: &str) -> Cycle {
+    let path = fixture_path(CYCLE_MEASUREMENTS);
+    let file = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!("Cycle measurements {:?} are missing; record them with `cargo test -p tests record_cycle_measurements -- --ignored`", path)
+    });
+    let measurements: Value = serde_json::from_str(&file).expect("cycle measurements should be valid JSON");
+    let measured = measurements["cycles"][operation]
+        .as_u64()
+        .unwrap_or_else(|| panic!("No measured cycles recorded for {}", operation));
+    measured + measured * CYCLE_BUDGET_MARGIN_PERCENT / 100
+}
+
+/// Asserts that an operation class stays within its budget.
+fn assert_within_budget(operation: &str) {
+    let cycles = measure(operation);
+    let budget = cycle_budget(operation);
+    assert!(cycles <= budget, "{} used {} cycles, budget is {}", operation, cycles, budget);
+}
+
+/// Records the cycles of every operation class on the current binary.
+/// A measuring tool rather than a check: run with --ignored after `make build` whenever the contract's cost changes on purpose.
+#[test]
+#[ignore]
+fn record_cycle_measurements() {
+    let cycles: serde_json::Map<String, Value> = OPERATIONS
+        .iter()
+        .map(|(operation, _)| (operation.to_string(), json!(measure(operation))))
+        .collect();
+    let measurements = json!({
+        "margin_percent": CYCLE_BUDGET_MARGIN_PERCENT,
+        "cycles": cycles,
+    });
+
+    let json = serde_json::to_string_pretty(&measurements).expect("json");
+    std::fs::write(fixture_path(CYCLE_MEASUREMENTS), json + "\n").expect("write cycle measurements");
+}
+
+/// Tests that a partial beneficiary claim stays within the claim cycle budget.
+/// Guards against cycle regressions in the most frequent operation.
+#[test]
+fn test_partial_claim_cycle_budget() {
+    assert_within_budget("partial_claim");
+}
+
+/// Tests that a full beneficiary claim consuming the cell stays within its cycle budget.
+/// Covers the no-output path that synthesizes the final state.
+#[test]
+fn test_full_claim_cycle_budget() {
+    assert_within_budget("full_claim");
+}
+
+/// Tests that a partial creator termination stays within the termination cycle budget.
+/// Covers the continuation path where the beneficiary keeps the vested amount.
+#[test]
+fn test_termination_cycle_budget() {
+    assert_within_budget("termination");
+}
+
+/// Tests that an anonymous block update stays within the update cycle budget.
+/// Keepers refreshing highest_block_seen rely on this bound for fee estimation.
+#[test]
+fn test_anonymous_update_cycle_budget() {
+    assert_within_budget("anonymous_update");
+}
+
+/// Builds an anonymous block update that carries the given number of distinct header dependencies.
+/// Headers use consecutive block numbers above highest_block_seen so every one of them is loaded and compared.
+fn build_update_with_header_deps(context: &mut Context, header_count: u64) -> TransactionView {
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let args = create_vesting_args(
+        create_dummy_lock_hash(1),
+        create_dummy_lock_hash(2),
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let mut tx_builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 200 + header_count).pack());
+
+    for offset in 1..=header_count {
+        let header_hash = setup_header_with_block_and_epoch(context, 200 + offset, 250);
+        tx_builder = tx_builder.header_dep(header_hash);
+    }
+
+    context.complete_tx(tx_builder.build())
+}
+
+/// Measures anonymous update cycles for every header dependency count up to the contract cap.
+/// Reports the marginal cost per header and the count MAX_CYCLES could afford, and fails on regressions.
+#[test]
+fn test_header_dep_scaling() {
+    let mut cycles_by_count = Vec::new();
+    for header_count in 1..=MAX_HEADER_DEPS as u64 {
+        let mut context = Context::default();
+        let tx = build_update_with_header_deps(&mut context, header_count);
+        let cycles = context.verify_tx(&tx, MAX_CYCLES).expect("update within the header cap should pass");
+        cycles_by_count.push(cycles);
+    }
+
+    let first_cycles = cycles_by_count.first().copied().expect("at least one measurement");
+    let last_cycles = cycles_by_count.last().copied().expect("at least one measurement");
+    let marginal_cycles = last_cycles.saturating_sub(first_cycles) / (MAX_HEADER_DEPS as u64 - 1);
+    let practical_ceiling = MAX_CYCLES.saturating_sub(first_cycles) / marginal_cycles.max(1) + 1;
+
+    println!(
+        "header deps: 1 -> {} cycles, {} -> {} cycles, ~{} cycles per header, practical ceiling ~{} headers",
+        first_cycles, MAX_HEADER_DEPS, last_cycles, marginal_cycles, practical_ceiling
+    );
+
+    // Each additional header must never make verification cheaper.
+    for window in cycles_by_count.windows(2) {
+        assert!(window[1] >= window[0], "Cycles dropped when adding a header: {:?}", cycles_by_count);
+    }
+
+    assert!(
+        marginal_cycles <= HEADER_DEP_CYCLE_BUDGET,
+        "Each header dependency costs {} cycles, budget is {}", marginal_cycles, HEADER_DEP_CYCLE_BUDGET
+    );
+    assert!(
+        practical_ceiling >= MAX_HEADER_DEPS as u64,
+        "MAX_CYCLES only affords {} headers, below the contract cap of {}", practical_ceiling, MAX_HEADER_DEPS
+    );
+}
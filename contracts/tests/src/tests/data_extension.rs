@@ -0,0 +1,270 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+// Opaque fields a future version could define, such as a pause flag and a last-claim epoch.
+const EXTENSION_FIELDS: [u8; 9] = [1, 200, 0, 0, 0, 0, 0, 0, 0];
+
+/// Party that authorizes an extension transition.
+#[derive(Clone, Copy)]
+enum Signer {
+    Beneficiary,
+    Creator,
+    Anonymous,
+}
+
+/// Builds and verifies a transition of a vesting cell at epoch 200 and block 201.
+/// When output_data is None the vesting cell is consumed without a continuation output.
+fn verify_extension_transition(
+    signer: Signer,
+    input_data: Bytes,
+    output_data: Option<Bytes>,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        input_data,
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .header_dep(header_hash);
+
+    // Add the proxy authorization input for the signing party.
+    let auth_lock = match signer {
+        Signer::Beneficiary => Some(beneficiary_lock),
+        Signer::Creator => Some(creator_lock),
+        Signer::Anonymous => None,
+    };
+    if let Some(auth_lock) = auth_lock {
+        let auth_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(6100000000u64.pack())
+                .lock(auth_lock)
+                .build(),
+            Bytes::new(),
+        );
+        builder = builder.input(CellInput::new_builder().previous_output(auth_input_out_point).build());
+    }
+
+    if let Some(output_data) = output_data {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(10161u64.pack())
+                .lock(lock_script)
+                .build())
+            .output_data(output_data.pack());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that an anonymous update carries the extension area forward unchanged.
+/// Only highest_block_seen advances; the extension bytes are copied verbatim.
+#[test]
+fn test_anonymous_update_carries_extension() {
+    let input_data = with_data_extension(create_vesting_data(10000, 0, 0, 200), &EXTENSION_FIELDS);
+    let output_data = with_data_extension(create_vesting_data(10000, 0, 0, 201), &EXTENSION_FIELDS);
+
+    let result = verify_extension_transition(Signer::Anonymous, input_data, Some(output_data));
+    assert!(result.is_ok(), "Should succeed - extension carried forward, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a beneficiary claim carries the extension area forward unchanged.
+/// The claimed counter changes while the extension stays byte-for-byte identical.
+#[test]
+fn test_beneficiary_claim_carries_extension() {
+    let input_data = with_data_extension(create_vesting_data(10000, 0, 0, 200), &EXTENSION_FIELDS);
+    let output_data = with_data_extension(create_vesting_data(10000, 5000, 0, 201), &EXTENSION_FIELDS);
+
+    let result = verify_extension_transition(Signer::Beneficiary, input_data, Some(output_data));
+    assert!(result.is_ok(), "Should succeed - claim with extension carried forward, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a creator termination carries the extension area forward unchanged.
+/// The continuation cell keeps the vested half and the extension.
+#[test]
+fn test_creator_termination_carries_extension() {
+    let input_data = with_data_extension(create_vesting_data(10000, 0, 0, 200), &EXTENSION_FIELDS);
+    let output_data = with_data_extension(create_vesting_data(10000, 0, 5000, 201), &EXTENSION_FIELDS);
+
+    let result = verify_extension_transition(Signer::Creator, input_data, Some(output_data));
+    assert!(result.is_ok(), "Should succeed - termination with extension carried forward, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the extension area follows tranche records and is carried with them.
+/// The tranche count in the data marks where the records end and the area begins.
+#[test]
+fn test_extension_after_tranche_records() {
+    let tranches = [(4000, 0, 200, 400, 200)];
+    let input_data = with_data_extension(create_vesting_data_with_tranches(10000, 0, 0, 200, &tranches), &EXTENSION_FIELDS);
+    let output_data = with_data_extension(create_vesting_data_with_tranches(10000, 0, 0, 201, &tranches), &EXTENSION_FIELDS);
+
+    let result = verify_extension_transition(Signer::Anonymous, input_data, Some(output_data));
+    assert!(result.is_ok(), "Should succeed - extension after tranche records, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a full beneficiary claim consumes a cell carrying an extension area.
+/// Nothing is carried forward when there is no continuation cell.
+#[test]
+fn test_full_claim_consumes_extension() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 351, 350);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .build(),
+        with_data_extension(create_vesting_data(10000, 0, 0, 350), &EXTENSION_FIELDS),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_ok(), "Should succeed - full claim consumes the extension, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that dropping the extension area from the continuation cell is rejected.
+/// Old cells must not lose fields a newer version relies on.
+#[test]
+fn test_dropped_extension_rejected() {
+    let input_data = with_data_extension(create_vesting_data(10000, 0, 0, 200), &EXTENSION_FIELDS);
+    let output_data = create_vesting_data(10000, 0, 0, 201);
+
+    let result = verify_extension_transition(Signer::Anonymous, input_data, Some(output_data));
+    assert!(result.is_err(), "Should fail - extension dropped from the continuation cell");
+    assert_eq!(extract_error_code(&result), Some(59)); // Error::InvalidDataExtension
+}
+
+/// Tests that adding an extension area to a cell that had none is rejected.
+/// No operation defines the extension yet, so it cannot appear mid-life.
+#[test]
+fn test_added_extension_rejected() {
+    let input_data = create_vesting_data(10000, 0, 0, 200);
+    let output_data = with_data_extension(create_vesting_data(10000, 0, 0, 201), &EXTENSION_FIELDS);
+
+    let result = verify_extension_transition(Signer::Anonymous, input_data, Some(output_data));
+    assert!(result.is_err(), "Should fail - extension added to the continuation cell");
+    assert_eq!(extract_error_code(&result), Some(59)); // Error::InvalidDataExtension
+}
+
+/// Tests that modifying the extension area is rejected, even during an authorized claim.
+/// The beneficiary cannot rewrite fields no operation is allowed to update.
+#[test]
+fn test_modified_extension_rejected() {
+    let mut modified_fields = EXTENSION_FIELDS;
+    modified_fields[0] = 0;
+
+    let input_data = with_data_extension(create_vesting_data(10000, 0, 0, 200), &EXTENSION_FIELDS);
+    let output_data = with_data_extension(create_vesting_data(10000, 5000, 0, 201), &modified_fields);
+
+    let result = verify_extension_transition(Signer::Beneficiary, input_data, Some(output_data));
+    assert!(result.is_err(), "Should fail - extension modified by the claim");
+    assert_eq!(extract_error_code(&result), Some(59)); // Error::InvalidDataExtension
+}
+
+/// Tests that an extension area with an unknown version is rejected.
+/// Versions define how the fields are interpreted, so unknown ones cannot be carried blindly.
+#[test]
+fn test_unknown_extension_version_rejected() {
+    let mut input_data = with_tranche_count(create_vesting_data(10000, 0, 0, 200)).to_vec();
+    input_data.extend_from_slice(&[9, 0, 0, 0]);
+    let mut output_data = with_tranche_count(create_vesting_data(10000, 0, 0, 201)).to_vec();
+    output_data.extend_from_slice(&[9, 0, 0, 0]);
+
+    let result = verify_extension_transition(Signer::Anonymous, Bytes::from(input_data), Some(Bytes::from(output_data)));
+    assert!(result.is_err(), "Should fail - unknown extension version");
+    assert_eq!(extract_error_code(&result), Some(59)); // Error::InvalidDataExtension
+}
+/// Tests that a tranche count larger than the records in the data is rejected.
+/// The count is read from the data rather than inferred from its length, so missing records cannot pass as an extension.
+#[test]
+fn test_tranche_count_beyond_records_rejected() {
+    let tranches = [(4000, 0, 200, 400, 200)];
+    let mut input_data = create_vesting_data_with_tranches(10000, 0, 0, 200, &tranches).to_vec();
+    input_data[32] = 2;
+    let mut output_data = create_vesting_data_with_tranches(10000, 0, 0, 201, &tranches).to_vec();
+    output_data[32] = 2;
+
+    let result = verify_extension_transition(Signer::Anonymous, Bytes::from(input_data), Some(Bytes::from(output_data)));
+    assert!(result.is_err(), "Should fail - tranche count beyond the records");
+    assert_eq!(extract_error_code(&result), Some(47)); // Error::InvalidTrancheData
+}
+
+/// Tests that a zero tranche count with nothing after it is rejected.
+/// Bare cell data is the only encoding of a cell without tranches or an extension area.
+#[test]
+fn test_empty_tranche_count_rejected() {
+    let input_data = with_tranche_count(create_vesting_data(10000, 0, 0, 200));
+    let output_data = with_tranche_count(create_vesting_data(10000, 0, 0, 201));
+
+    let result = verify_extension_transition(Signer::Anonymous, input_data, Some(output_data));
+    assert!(result.is_err(), "Should fail - tranche count without records or extension");
+    assert_eq!(extract_error_code(&result), Some(47)); // Error::InvalidTrancheData
+}
+
+/// Tests that an extension area longer than 39 bytes is rejected.
+/// With a zero tranche count, a record-sized tail cannot be read as a tranche and exceeds the area's limit.
+#[test]
+fn test_oversized_extension_rejected() {
+    let input_data = with_data_extension(create_vesting_data(10000, 0, 0, 200), &[0u8; 39]);
+    let output_data = with_data_extension(create_vesting_data(10000, 0, 0, 201), &[0u8; 39]);
+
+    let result = verify_extension_transition(Signer::Anonymous, input_data, Some(output_data));
+    assert!(result.is_err(), "Should fail - extension area longer than 39 bytes");
+    assert_eq!(extract_error_code(&result), Some(59)); // Error::InvalidDataExtension
+}
\ No newline at end of file
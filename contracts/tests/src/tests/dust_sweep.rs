@@ -0,0 +1,259 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const OPTION_KEEPER_TIP: u8 = 0x03;
+const OPTION_MIN_UPDATE_INTERVAL: u8 = 0x04;
+const OPTION_SWEEP_LOCK: u8 = 0x12;
+const OPTION_UDT_TYPE: u8 = 0x13;
+
+/// A permissionless sweep of a vesting cell holding 10161 capacity.
+/// residual_tokens makes it a UDT cell still holding that many tokens, of which swept_tokens go to the sweep lock.
+struct Sweep {
+    input_data: Bytes,
+    designate_sweep_lock: bool,
+    pay_designated_lock: bool,
+    swept_capacity: u64,
+    keeper_tip: Option<u64>,
+    sweep_output_data: Bytes,
+    witness: Option<Bytes>,
+    residual_tokens: Option<u128>,
+    swept_tokens: u128,
+}
+
+impl Default for Sweep {
+    fn default() -> Self {
+        Sweep {
+            input_data: create_vesting_data(10000, 6000, 4000, 200),
+            designate_sweep_lock: true,
+            pay_designated_lock: true,
+            swept_capacity: 10161,
+            keeper_tip: None,
+            sweep_output_data: Bytes::new(),
+            witness: None,
+            residual_tokens: None,
+            swept_tokens: 0,
+        }
+    }
+}
+
+/// Builds and verifies a transaction that consumes the vesting cell without any signer.
+/// Swept capacity goes to the designated sweep lock, or to the creator when pay_designated_lock is unset; the sweeper keeps the rest.
+fn verify_sweep(sweep: Sweep) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (_beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+    let (treasury_lock, treasury_hash) = create_always_success_lock_with_args(&mut context, vec![6u8]);
+    let sweeper_lock = create_dummy_lock_script(&mut context);
+    let (udt_type, udt_type_hash) = create_always_success_lock_with_args(&mut context, vec![7u8]);
+
+    let mut options = Vec::new();
+    if sweep.designate_sweep_lock {
+        options.push((OPTION_SWEEP_LOCK, treasury_hash.to_vec()));
+    }
+    if let Some(tip) = sweep.keeper_tip {
+        options.push((OPTION_KEEPER_TIP, tip.to_le_bytes().to_vec()));
+        options.push((OPTION_MIN_UPDATE_INTERVAL, 100u64.to_le_bytes().to_vec()));
+    }
+    if sweep.residual_tokens.is_some() {
+        options.push((OPTION_UDT_TYPE, udt_type_hash.to_vec()));
+    }
+    let base_args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+    let args = if options.is_empty() { base_args } else { create_vesting_args_with_options(base_args, &options) };
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    // UDT cells carry the token amount ahead of the vesting state, and their sweep output carries the swept tokens.
+    let token_type = sweep.residual_tokens.map(|_| udt_type);
+    let (input_data, sweep_output_data) = match sweep.residual_tokens {
+        Some(tokens) => {
+            let mut input_data = tokens.to_le_bytes().to_vec();
+            input_data.extend_from_slice(&sweep.input_data);
+            (Bytes::from(input_data), Bytes::from(sweep.swept_tokens.to_le_bytes().to_vec()))
+        }
+        None => (sweep.input_data, sweep.sweep_output_data),
+    };
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .type_(token_type.clone().pack())
+            .build(),
+        input_data,
+    );
+
+    let sweep_lock = if sweep.pay_designated_lock { treasury_lock } else { creator_lock };
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(sweep.swept_capacity.pack())
+            .lock(sweep_lock)
+            .type_(token_type.pack())
+            .build())
+        .output_data(sweep_output_data.pack())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.saturating_sub(sweep.swept_capacity).pack())
+            .lock(sweeper_lock)
+            .build())
+        .output_data(Bytes::new().pack());
+
+    if let Some(witness) = sweep.witness {
+        builder = builder.witness(witness.pack());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that anyone can sweep a fully distributed cell to the sweep lock.
+/// The beneficiary claimed 6000 and the creator reclaimed 4000, so nothing is left to vest.
+#[test]
+fn test_sweep_settled_cell() {
+    let result = verify_sweep(Sweep::default());
+    assert!(result.is_ok(), "Should succeed - permissionless sweep of a settled cell, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that zero-amount cells are settled from the start and can be swept.
+/// Such cells would otherwise hold their capacity forever.
+#[test]
+fn test_sweep_zero_amount_cell() {
+    let result = verify_sweep(Sweep { input_data: create_vesting_data(0, 0, 0, 200), ..Sweep::default() });
+    assert!(result.is_ok(), "Should succeed - sweep of a zero-amount cell, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the designated sweep lock receives the capacity instead of the creator.
+/// Paying the creator is rejected once another sweep lock is configured.
+#[test]
+fn test_sweep_to_creator_rejected() {
+    let result = verify_sweep(Sweep { pay_designated_lock: false, ..Sweep::default() });
+    assert!(result.is_err(), "Should fail - capacity went to the creator instead of the sweep lock");
+    assert_eq!(extract_error_code(&result), Some(64)); // Error::InsufficientSweep
+}
+
+/// Tests that a cell without a sweep lock option cannot be swept at all.
+/// Capacity never falls back to the creator lock by default.
+#[test]
+fn test_sweep_without_sweep_lock_rejected() {
+    let result = verify_sweep(Sweep { designate_sweep_lock: false, pay_designated_lock: false, ..Sweep::default() });
+    assert!(result.is_err(), "Should fail - no sweep lock is configured");
+    assert_eq!(extract_error_code(&result), Some(122)); // Error::MissingSweepLock
+}
+
+/// Tests that the sweeper cannot keep any capacity without a keeper tip.
+/// Sending 10160 of 10161 to the sweep lock falls short.
+#[test]
+fn test_short_sweep_rejected() {
+    let result = verify_sweep(Sweep { swept_capacity: 10160, ..Sweep::default() });
+    assert!(result.is_err(), "Should fail - sweep lock received less than the cell's capacity");
+    assert_eq!(extract_error_code(&result), Some(64)); // Error::InsufficientSweep
+}
+
+/// Tests that the keeper tip bounds what the sweeper may keep for fees.
+/// With a tip of 100, 10061 is enough and 10060 is not.
+#[test]
+fn test_sweep_keeper_tip() {
+    let result = verify_sweep(Sweep { keeper_tip: Some(100), swept_capacity: 10061, ..Sweep::default() });
+    assert!(result.is_ok(), "Should succeed - sweeper keeps the keeper tip, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_sweep(Sweep { keeper_tip: Some(100), swept_capacity: 10060, ..Sweep::default() });
+    assert!(result.is_err(), "Should fail - sweeper kept more than the keeper tip");
+    assert_eq!(extract_error_code(&result), Some(64)); // Error::InsufficientSweep
+}
+
+/// Tests that the keeper tip of a UDT cell only covers capacity, never tokens.
+/// The sweeper keeps the 100 tip in capacity, while all 5 tokens left in the cell must reach the sweep lock.
+#[test]
+fn test_udt_sweep_keeper_tip() {
+    let result = verify_sweep(Sweep { keeper_tip: Some(100), swept_capacity: 10061, residual_tokens: Some(5), swept_tokens: 5, ..Sweep::default() });
+    assert!(result.is_ok(), "Should succeed - sweeper keeps the tip and sweeps every token, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_sweep(Sweep { keeper_tip: Some(100), swept_capacity: 10061, residual_tokens: Some(5), swept_tokens: 4, ..Sweep::default() });
+    assert!(result.is_err(), "Should fail - sweeper kept a token under the keeper tip");
+    assert_eq!(extract_error_code(&result), Some(64)); // Error::InsufficientSweep
+
+    let result = verify_sweep(Sweep { keeper_tip: Some(100), swept_capacity: 10060, residual_tokens: Some(5), swept_tokens: 5, ..Sweep::default() });
+    assert!(result.is_err(), "Should fail - sweeper kept more capacity than the keeper tip");
+    assert_eq!(extract_error_code(&result), Some(64)); // Error::InsufficientSweep
+}
+
+/// Tests that a cell with amounts left to vest or reclaim cannot be swept.
+/// Without a signer and a continuation cell the transaction is an invalid anonymous update.
+#[test]
+fn test_unsettled_cell_not_swept() {
+    let result = verify_sweep(Sweep { input_data: create_vesting_data(10000, 6000, 0, 200), ..Sweep::default() });
+    assert!(result.is_err(), "Should fail - cell still has unclaimed amounts");
+    assert_ne!(extract_error_code(&result), Some(64), "Unsettled cells must not reach the sweep check");
+}
+
+/// Tests that data on the sweep output is rejected.
+/// Only the data a UDT amount needs is allowed on payout outputs.
+#[test]
+fn test_sweep_output_with_data_rejected() {
+    let result = verify_sweep(Sweep { sweep_output_data: Bytes::from(vec![1u8]), ..Sweep::default() });
+    assert!(result.is_err(), "Should fail - sweep output carries data");
+    assert_eq!(extract_error_code(&result), Some(50)); // Error::PayoutOutputHasData
+}
+
+/// Tests that a sweep carries no witness fields.
+/// A payout redirection has no meaning when nobody claims.
+#[test]
+fn test_sweep_with_witness_rejected() {
+    let result = verify_sweep(Sweep { witness: Some(create_payout_witness(create_dummy_lock_hash(9))), ..Sweep::default() });
+    assert!(result.is_err(), "Should fail - sweep carries a payout witness");
+    assert_eq!(extract_error_code(&result), Some(11)); // Error::InvalidWitness
+}
+
+/// Tests that a malformed sweep lock option is rejected.
+/// The value must be exactly one 32-byte lock hash.
+#[test]
+fn test_invalid_sweep_lock_option_rejected() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            create_dummy_lock_hash(1),
+            create_dummy_lock_hash(2),
+            100, // start_epoch
+            300, // end_epoch
+            120, // cliff_epoch
+        ),
+        &[(OPTION_SWEEP_LOCK, vec![6u8; 31])],
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let sweeper_lock = create_dummy_lock_script(&mut context);
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .build(),
+        create_vesting_data(0, 0, 0, 200),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(sweeper_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - sweep lock option is not 32 bytes");
+    assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS));
+}
\ No newline at end of file
@@ -1,8 +1,19 @@
+use super::allocator_benchmark::build_heavy_claim;
 use super::helpers::*;
 use crate::Loader;
 use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
 use ckb_testtool::context::Context;
 
+// Largest epoch number a packed header epoch can carry (24 bits)
+const MAX_HEADER_EPOCH: u64 = 0xff_ffff;
+// Largest args epoch whose schedule point (epoch * 65536) still fits a u64
+const MAX_UNSATURATED_EPOCH: u64 = (1 << 48) - 1;
+
+/// Packs an epoch number and the fraction of it that has passed the way block headers carry them.
+fn pack_epoch(number: u64, index: u64, length: u64) -> u64 {
+    number | (index << 24) | (length << 40)
+}
+
 /// Tests anyone-can-update functionality on vesting cells with zero amounts.
 /// Validates that security updates work even when no tokens are being vested.
 #[test]
@@ -117,58 +128,66 @@ fn test_cliff_equals_end_epoch() {
     assert!(result.is_ok(), "Should succeed - cliff=end epoch allows full vesting, got error code: {:?}", extract_error_code(&result));
 }
 
-/// Tests that vesting calculations handle arithmetic overflow gracefully.
-/// Validates protection against overflow in epoch-based vesting computations.
+/// Tests that linear vesting of a u64::MAX total at an epoch half way through is computed without overflow.
+/// Elapsed points times the total is far beyond u64, so the claim of exactly half only succeeds with wide math.
 #[test]
 fn test_overflow_protection_vesting_calculation() {
-    let mut context = Context::default();
-    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
-    let out_point = context.deploy_cell(contract_bin);
-
-    let creator_hash = create_dummy_lock_hash(1);
-    let beneficiary_hash = create_dummy_lock_hash(2);
-
-    let args = create_vesting_args(
-        creator_hash,
-        beneficiary_hash,
-        0, // start_epoch
-        u64::MAX, // end_epoch (max value to test overflow)
-        1, // cliff_epoch
-    );
-
-    let lock_script = context.build_script(&out_point, args).expect("script");
-
-    // Setup header with large block number and epoch
-    let header_hash = setup_header_with_block_and_epoch(&mut context, u64::MAX - 1, u64::MAX / 2);
-
-    let input_out_point = context.create_cell(
-        CellOutput::new_builder()
-            .capacity(10161u64.pack())
-            .lock(lock_script.clone())
-            .build(),
-        create_vesting_data(10000, 0, 0, u64::MAX / 2), // very large epoch
-    );
-
-    let input = CellInput::new_builder()
-        .previous_output(input_out_point)
-        .build();
-
-    // Should handle overflow gracefully
-    let output = CellOutput::new_builder()
-        .capacity(10161u64.pack())
-        .lock(lock_script)
-        .build();
-
-    let tx = TransactionBuilder::default()
-        .input(input)
-        .output(output)
-        .output_data(create_vesting_data(10000, 0, 0, u64::MAX - 1).pack()) // update block
-        .header_dep(header_hash)
-        .build();
-    let tx = context.complete_tx(tx);
-
-    let result = context.verify_tx(&tx, MAX_CYCLES);
-    assert!(result.is_ok(), "Should succeed - overflow protection in vesting calculation, got error code: {:?}", extract_error_code(&result));
+    for (claimed, should_succeed) in [(u64::MAX / 2, true), (u64::MAX / 2 + 1, false)] {
+        let mut context = Context::default();
+        let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+        let out_point = context.deploy_cell(contract_bin);
+
+        let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+        let args = create_vesting_args(
+            creator_hash,
+            beneficiary_hash,
+            0,                    // start_epoch
+            MAX_HEADER_EPOCH + 1, // end_epoch just past the last epoch a header can carry
+            0,                    // cliff_epoch
+        );
+
+        let lock_script = context.build_script(&out_point, args).expect("script");
+
+        // Half way through: (2^23 * 65536) * u64::MAX / (2^24 * 65536) = u64::MAX / 2, rounded down.
+        let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 1 << 23);
+
+        let input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(10161u64.pack())
+                .lock(lock_script.clone())
+                .build(),
+            create_vesting_data(u64::MAX, 0, 0, 200),
+        );
+
+        let beneficiary_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(1000u64.pack())
+                .lock(beneficiary_lock)
+                .build(),
+            Bytes::new(),
+        );
+
+        let tx = TransactionBuilder::default()
+            .input(CellInput::new_builder().previous_output(input_out_point).build())
+            .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+            .output(CellOutput::new_builder()
+                .capacity(10161u64.pack())
+                .lock(lock_script)
+                .build())
+            .output_data(create_vesting_data(u64::MAX, claimed, 0, 201).pack())
+            .header_dep(header_hash)
+            .build();
+        let tx = context.complete_tx(tx);
+
+        let result = context.verify_tx(&tx, MAX_CYCLES);
+        if should_succeed {
+            assert!(result.is_ok(), "Should succeed - claim of exactly the vested half, got error code: {:?}", extract_error_code(&result));
+        } else {
+            assert!(result.is_err(), "Should fail - claim exceeds the vested half by one");
+            assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+        }
+    }
 }
 
 /// Tests the contract's behavior with minimum CKB capacity requirements.
@@ -333,35 +352,35 @@ fn test_transition_partial_to_full_vesting() {
     assert!(result.is_ok(), "Should succeed - transition from partial to full vesting, got error code: {:?}", extract_error_code(&result));
 }
 
-/// Tests enhanced vesting calculation overflow protection.
-/// Validates protection against overflow in complex vesting scenarios.
+/// Tests schedule epochs on both sides of the largest epoch whose schedule point still fits a u64.
+/// The start epoch scales to just below u64::MAX, while the end and cliff epochs beyond it saturate instead of wrapping.
 #[test]
 fn test_vesting_calculation_overflow_protection() {
     let mut context = Context::default();
     let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
     let out_point = context.deploy_cell(contract_bin);
 
-    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+    let (_beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
 
     let args = create_vesting_args(
         creator_hash,
         beneficiary_hash,
-        u64::MAX - 1000, // start_epoch near max
-        u64::MAX - 100,  // end_epoch near max
-        u64::MAX - 500,  // cliff_epoch near max
+        MAX_UNSATURATED_EPOCH,     // start_epoch scales to u64::MAX - 65535
+        u64::MAX,                  // end_epoch saturates
+        MAX_UNSATURATED_EPOCH + 1, // cliff_epoch saturates
     );
 
     let lock_script = context.build_script(&out_point, args).expect("script");
 
-    // Setup header with epoch that could cause overflow in calculation
-    let header_hash = setup_header_with_block_and_epoch(&mut context, u64::MAX - 200, u64::MAX - 300);
+    // The latest point a header can carry is still far before the start, so nothing has vested.
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, pack_epoch(MAX_HEADER_EPOCH, 0xfffe, 0xffff));
 
     let input_out_point = context.create_cell(
         CellOutput::new_builder()
             .capacity(10161u64.pack())
             .lock(lock_script.clone())
             .build(),
-        create_vesting_data(u64::MAX / 2, 0, 0, u64::MAX - 400), // Large total amount
+        create_vesting_data(u64::MAX / 2, 0, 0, 200), // Large total amount
     );
 
     // Anonymous update test: just update block number with overflow protection
@@ -373,17 +392,17 @@ fn test_vesting_calculation_overflow_protection() {
     let tx = TransactionBuilder::default()
         .input(CellInput::new_builder().previous_output(input_out_point).build())
         .output(output)
-        .output_data(create_vesting_data(u64::MAX / 2, 0, 0, u64::MAX - 200).pack()) // Only block update
+        .output_data(create_vesting_data(u64::MAX / 2, 0, 0, 201).pack()) // Only block update
         .header_dep(header_hash)
         .build();
     let tx = context.complete_tx(tx);
 
     let result = context.verify_tx(&tx, MAX_CYCLES);
-    assert!(result.is_ok(), "Should succeed - overflow protection in complex vesting calculation, got error code: {:?}", extract_error_code(&result));
+    assert!(result.is_ok(), "Should succeed - saturated schedule points in an anonymous update, got error code: {:?}", extract_error_code(&result));
 }
 
 /// Tests maximum values edge cases for all parameters.
-/// Validates that the contract handles maximum u64 values correctly.
+/// Uses the largest header epoch and fraction, a u64::MAX total and a u64::MAX block number, none of which are masked away.
 #[test]
 fn test_maximum_values_edge_cases() {
     let mut context = Context::default();
@@ -396,15 +415,15 @@ fn test_maximum_values_edge_cases() {
     let args = create_vesting_args(
         creator_hash,
         beneficiary_hash,
-        0,         // start_epoch
-        u64::MAX,  // end_epoch (maximum)
-        1,         // cliff_epoch
+        0,                    // start_epoch
+        MAX_HEADER_EPOCH + 1, // end_epoch just past the last epoch a header can carry
+        1,                    // cliff_epoch
     );
 
     let lock_script = context.build_script(&out_point, args).expect("script");
 
-    // Setup header with maximum values
-    let header_hash = setup_header_with_block_and_epoch(&mut context, u64::MAX, u64::MAX);
+    // The last block of the last epoch keeps the elapsed points just below the duration.
+    let header_hash = setup_header_with_block_and_epoch(&mut context, u64::MAX, pack_epoch(MAX_HEADER_EPOCH, 0xfffe, 0xffff));
 
     let input_out_point = context.create_cell(
         CellOutput::new_builder()
@@ -489,4 +508,15 @@ fn test_cliff_equals_start_immediate_vesting() {
 
     let result = context.verify_tx(&tx, MAX_CYCLES);
     assert!(result.is_ok(), "Should succeed - cliff=start allows immediate termination, got error code: {:?}", extract_error_code(&result));
-}
\ No newline at end of file
+}
+
+/// Tests that a transaction with many header dependencies and unrelated inputs fits in the reduced heap.
+/// Every header is loaded and released in turn, so heap usage stays bounded.
+#[test]
+fn test_heavy_transaction_within_heap_limits() {
+    let mut context = Context::default();
+    let tx = build_heavy_claim(&mut context, "vesting_lock");
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_ok(), "Should succeed - heavy transaction within heap limits, got error code: {:?}", extract_error_code(&result));
+}
@@ -0,0 +1,128 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const OPTION_END_BONUS: u8 = 0x0c;
+
+/// Party that authorizes the transaction.
+#[derive(Clone, Copy)]
+enum Signer {
+    Beneficiary,
+    Creator,
+}
+
+/// Builds and verifies a claim or termination on a 10000 grant carrying a 2000 end bonus.
+/// The signer's payout is the given amount; a continuation output is kept unless the cell is emptied.
+fn verify_with_bonus(
+    signer: Signer,
+    epoch: u64,
+    amount: u64,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            creator_hash,
+            beneficiary_hash,
+            100, // start_epoch
+            300, // end_epoch
+            120, // cliff_epoch
+        ),
+        &[(OPTION_END_BONUS, 2000u64.to_le_bytes().to_vec())],
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, epoch);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let (auth_lock, output_data) = match signer {
+        Signer::Beneficiary => (beneficiary_lock, create_vesting_data(10000, amount, 0, 201)),
+        Signer::Creator => (creator_lock, create_vesting_data(10000, 0, amount, 201)),
+    };
+
+    let auth_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(auth_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(auth_input_out_point).build())
+        .header_dep(header_hash);
+
+    if amount < 10000 {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity((10161u64 - amount).pack())
+                .lock(lock_script)
+                .build())
+            .output_data(output_data.pack());
+    }
+
+    let tx = builder
+        .output(CellOutput::new_builder()
+            .capacity(amount.pack())
+            .lock(auth_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .build();
+    let tx = context.complete_tx(tx);
+
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that the bonus is excluded from linear vesting before the end epoch.
+/// At epoch 200: vested = (200-100)/(300-100) * (10000-2000) = 4000.
+#[test]
+fn test_bonus_excluded_before_end() {
+    let result = verify_with_bonus(Signer::Beneficiary, 200, 4000);
+    assert!(result.is_ok(), "Should succeed - claim of the linear part, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_with_bonus(Signer::Beneficiary, 200, 4001);
+    assert!(result.is_err(), "Should fail - claim reaches into the bonus");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that the bonus is still locked one epoch before the end.
+/// At epoch 299: vested = 199/200 * 8000 = 7960.
+#[test]
+fn test_bonus_locked_until_end() {
+    let result = verify_with_bonus(Signer::Beneficiary, 299, 7961);
+    assert!(result.is_err(), "Should fail - bonus not yet unlocked");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that the bonus unlocks at the end epoch.
+/// The beneficiary can claim the full 10000 and consume the cell.
+#[test]
+fn test_bonus_unlocks_at_end() {
+    let result = verify_with_bonus(Signer::Beneficiary, 300, 10000);
+    assert!(result.is_ok(), "Should succeed - full claim including the bonus, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that termination before the end forfeits the bonus to the creator.
+/// At epoch 200 the creator reclaims 10000 - 4000 = 6000, which includes the 2000 bonus.
+#[test]
+fn test_termination_forfeits_bonus() {
+    let result = verify_with_bonus(Signer::Creator, 200, 6000);
+    assert!(result.is_ok(), "Should succeed - creator reclaims the bonus, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_with_bonus(Signer::Creator, 200, 4000);
+    assert!(result.is_err(), "Should fail - termination must include the forfeited bonus");
+    assert_eq!(extract_error_code(&result), Some(20)); // Error::InvalidAmount
+}
@@ -0,0 +1,133 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const OPTION_MIN_HEADER_DEPS: u8 = 0x05;
+
+/// Packs an epoch number and the fraction of it elapsed into the header epoch format.
+/// The number takes the low 24 bits, followed by the 16-bit index and the 16-bit length.
+fn pack_epoch(number: u64, index: u64, length: u64) -> u64 {
+    number | (index << 24) | (length << 40)
+}
+
+/// Builds and verifies a beneficiary claim on a schedule over epochs 100 to 300 with a cliff at 120.
+/// Headers are (block, packed epoch) pairs; the output records the highest header block.
+fn verify_fraction_claim(
+    options: &[(u8, Vec<u8>)],
+    headers: &[(u64, u64)],
+    claimed: u64,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            creator_hash,
+            beneficiary_hash,
+            100, // start_epoch
+            300, // end_epoch
+            120, // cliff_epoch
+        ),
+        options,
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let highest_block = headers.iter().map(|(block, _)| *block).max().unwrap_or(0);
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity((10161u64 - claimed).pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, claimed, 0, highest_block).pack())
+        .output(CellOutput::new_builder()
+            .capacity(claimed.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack());
+
+    for (block_number, epoch) in headers.iter() {
+        let header_hash = setup_header_with_block_and_epoch(&mut context, *block_number, *epoch);
+        builder = builder.header_dep(header_hash);
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that vesting accrues within an epoch instead of jumping at epoch boundaries.
+/// Halfway through epoch 200: vested = (200.5-100)/(300-100) * 10000 = 5025, while 5026 is too much.
+#[test]
+fn test_vesting_accrues_within_epoch() {
+    let result = verify_fraction_claim(&[], &[(201, pack_epoch(200, 900, 1800))], 5025);
+    assert!(result.is_ok(), "Should succeed - half of epoch 200 has passed, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_fraction_claim(&[], &[(201, pack_epoch(200, 900, 1800))], 5026);
+    assert!(result.is_err(), "Should fail - claim exceeds the amount vested halfway through epoch 200");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that the cliff is reached only at the start of the cliff epoch.
+/// The last block of epoch 119 still vests nothing, and epoch 120 releases the 1000 accrued since epoch 100.
+#[test]
+fn test_cliff_reached_at_whole_epoch() {
+    let result = verify_fraction_claim(&[], &[(201, pack_epoch(119, 1799, 1800))], 1);
+    assert!(result.is_err(), "Should fail - epoch 119 is not over yet");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+
+    let result = verify_fraction_claim(&[], &[(201, pack_epoch(120, 0, 1800))], 1000);
+    assert!(result.is_ok(), "Should succeed - cliff epoch reached, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a header quorum compares epochs including their fractions.
+/// A quarter into epoch 200 comes before two thirds into it, even though its packed value is larger.
+#[test]
+fn test_quorum_compares_epoch_fractions() {
+    let options = [(OPTION_MIN_HEADER_DEPS, 2u64.to_le_bytes().to_vec())];
+    let headers = [(201, pack_epoch(200, 1, 4)), (202, pack_epoch(200, 2, 3))];
+
+    // At epoch 200.25: vested = 100.25/200 * 10000 = 5012, rounded down.
+    let result = verify_fraction_claim(&options, &headers, 5012);
+    assert!(result.is_ok(), "Should succeed - claim matches the earlier fraction, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_fraction_claim(&options, &headers, 5013);
+    assert!(result.is_err(), "Should fail - claim uses the later fraction");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that malformed fractions count as the start of the epoch.
+/// An index that is not below the length cannot come from a real chain, so epoch 200 vests exactly 5000.
+#[test]
+fn test_malformed_fraction_ignored() {
+    for epoch in [pack_epoch(200, 5, 5), pack_epoch(200, 7, 0)] {
+        let result = verify_fraction_claim(&[], &[(201, epoch)], 5000);
+        assert!(result.is_ok(), "Should succeed - fraction ignored, got error code: {:?}", extract_error_code(&result));
+
+        let result = verify_fraction_claim(&[], &[(201, epoch)], 5001);
+        assert!(result.is_err(), "Should fail - fraction must not add to the vested amount");
+        assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+    }
+}
\ No newline at end of file
@@ -0,0 +1,159 @@
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+// The script's error enum and the table generated from it.
+const ERROR_SOURCE: &str = include_str!("../../../contracts/vesting_lock/src/error.rs");
+const ERROR_TABLE: &str = include_str!("../../../contracts/vesting_lock/errors.json");
+
+// Codes as released before the stage blocks. Integrations match on them, so they must never change.
+const RELEASED_CODES: &[(&str, u64)] = &[
+    ("IndexOutOfBound", 1),
+    ("ItemMissing", 2),
+    ("LengthNotEnough", 3),
+    ("InvalidData", 4),
+    ("InvalidArgs", 10),
+    ("InvalidWitness", 11),
+    ("InvalidTransaction", 12),
+    ("InvalidTransactionStructure", 13),
+    ("TotalAmountChanged", 14),
+    ("InvalidBeneficiaryClaimedDelta", 15),
+    ("InvalidCreatorClaimedDelta", 16),
+    ("InvalidStateChange", 17),
+    ("InvalidAmount", 20),
+    ("InsufficientVested", 21),
+    ("AlreadyTerminated", 22),
+    ("InvalidEpoch", 23),
+    ("StaleHeader", 24),
+    ("Unauthorized", 25),
+    ("BlockNumberDecrease", 26),
+    ("BlockNumberMismatch", 27),
+    ("InvalidCellData", 30),
+    ("LoadCellDataFailed", 31),
+    ("WrongDataLength", 32),
+    ("NoMatchingInputCell", 33),
+    ("NoMatchingOutputCell", 34),
+    ("NoHeaderDependencies", 35),
+    ("MultipleInputsNotAllowed", 36),
+    ("CreatorOperationMissingOutput", 37),
+    ("AnonymousUpdateMissingOutput", 38),
+    ("InputDataWrongLength", 39),
+    ("OutputDataWrongLength", 40),
+    ("CreatorFullTerminationHasOutput", 41),
+    ("BeneficiaryFullClaimHasOutput", 42),
+    ("BeneficiaryPartialClaimMissingOutput", 43),
+    ("NothingToTerminate", 44),
+    ("TooManyHeaderDependencies", 45),
+    ("InsufficientPayout", 46),
+    ("InvalidTrancheData", 47),
+    ("TrancheChanged", 48),
+    ("InvalidTrancheClaim", 49),
+    ("PayoutOutputHasData", 50),
+    ("InvalidEvent", 51),
+    ("KeeperTipExceeded", 52),
+    ("UpdateTooFrequent", 53),
+    ("InsufficientHeaderDependencies", 54),
+    ("BlockAdvanceTooLarge", 55),
+    ("InvalidRotation", 56),
+    ("RotationNotReady", 57),
+    ("SimultaneousClaimAndTermination", 58),
+    ("InvalidDataExtension", 59),
+    ("InvalidBasisPointBacking", 60),
+    ("InvalidBuyout", 61),
+    ("InsufficientGrantorShare", 62),
+    ("InvalidClaimUnit", 63),
+    ("InsufficientSweep", 64),
+];
+
+/// Generates the error table from the Error enum in error.rs.
+/// Categories come from the section comments and descriptions from the variant doc comments.
+fn generate_error_table(source: &str) -> Value {
+    let mut entries = Vec::new();
+    let mut category = String::new();
+    let mut description = String::new();
+
+    let body = source
+        .split("pub enum Error {")
+        .nth(1)
+        .and_then(|rest| rest.split("\n}").next())
+        .expect("error.rs should define the Error enum");
+
+    for line in body.lines().map(str::trim) {
+        if let Some(doc) = line.strip_prefix("///") {
+            description = doc.trim().trim_end_matches('.').to_string();
+        } else if let Some(comment) = line.strip_prefix("//") {
+            // Section comments end with the stage's code range, which the category leaves out.
+            let comment = comment.trim();
+            category = comment.split(" (").next().unwrap_or(comment).to_string();
+        } else if let Some((name, rest)) = line.split_once(" = ") {
+            let code: u64 = rest
+                .split(',')
+                .next()
+                .and_then(|code| code.trim().parse().ok())
+                .expect("variant should have a numeric code");
+            assert!(!description.is_empty(), "Variant {} is missing a doc comment", name);
+            entries.push(json!({
+                "name": name,
+                "code": code,
+                "category": category,
+                "description": description,
+            }));
+            description.clear();
+        }
+    }
+
+    Value::Array(entries)
+}
+
+/// Tests that the committed errors.json matches the table generated from error.rs.
+/// Run with UPDATE_ERROR_TABLE=1 to regenerate the file after changing the Error enum.
+#[test]
+fn test_error_table_in_sync() {
+    let generated = generate_error_table(ERROR_SOURCE);
+
+    if std::env::var("UPDATE_ERROR_TABLE").is_ok() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("../contracts/vesting_lock/errors.json");
+        let json = serde_json::to_string_pretty(&generated).expect("json");
+        std::fs::write(path, json + "\n").expect("write errors.json");
+        return;
+    }
+
+    let committed: Value = serde_json::from_str(ERROR_TABLE).expect("errors.json should be valid JSON");
+    assert_eq!(generated, committed, "errors.json is out of date; rerun with UPDATE_ERROR_TABLE=1");
+}
+
+/// Tests that every error code in the table is unique and fits the script's i8 exit code.
+/// Wallets key their messages by code, so duplicates would show the wrong message.
+#[test]
+fn test_error_codes_unique() {
+    let generated = generate_error_table(ERROR_SOURCE);
+    let mut codes: Vec<u64> = generated
+        .as_array()
+        .expect("table should be an array")
+        .iter()
+        .map(|entry| entry["code"].as_u64().expect("code"))
+        .collect();
+
+    assert!(codes.iter().all(|code| *code >= 1 && *code <= i8::MAX as u64), "Codes must be positive i8 values");
+
+    let count = codes.len();
+    codes.sort();
+    codes.dedup();
+    assert_eq!(codes.len(), count, "Error codes must be unique");
+}
+
+/// Tests that every released error keeps its released code.
+/// Renumbering would silently change what stored or matched codes mean to wallets and indexers.
+#[test]
+fn test_released_codes_unchanged() {
+    let generated = generate_error_table(ERROR_SOURCE);
+    let table = generated.as_array().expect("table should be an array");
+
+    for (name, code) in RELEASED_CODES {
+        let current = table
+            .iter()
+            .find(|error| error["name"] == *name)
+            .unwrap_or_else(|| panic!("Released error {} is missing from the error table", name));
+        assert_eq!(current["code"].as_u64(), Some(*code), "Released error {} must keep code {}", name, code);
+    }
+}
@@ -0,0 +1,143 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const OPERATION_BLOCK_UPDATE: u8 = 0;
+const OPERATION_CLAIM: u8 = 1;
+const OPERATION_TERMINATION: u8 = 2;
+
+/// Party that authorizes the evented transaction.
+#[derive(Clone, Copy)]
+enum Signer {
+    Beneficiary,
+    Creator,
+    Anonymous,
+}
+
+/// Builds and verifies a transition at epoch 200 and block 201 with the given vesting witness.
+/// Beneficiaries claim 5000, creators terminate 5000, and anonymous updates only move the block.
+fn verify_with_event(
+    signer: Signer,
+    witness: Bytes,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let (auth_lock, output_capacity, output_data) = match signer {
+        Signer::Beneficiary => (Some(beneficiary_lock), 5161u64, create_vesting_data(10000, 5000, 0, 201)),
+        Signer::Creator => (Some(creator_lock), 5161u64, create_vesting_data(10000, 0, 5000, 201)),
+        Signer::Anonymous => (None, 10161u64, create_vesting_data(10000, 0, 0, 201)),
+    };
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(output_capacity.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(output_data.pack())
+        .header_dep(header_hash)
+        .witness(witness.pack());
+
+    if let Some(auth_lock) = auth_lock {
+        let auth_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(6100000000u64.pack())
+                .lock(auth_lock.clone())
+                .build(),
+            Bytes::new(),
+        );
+        builder = builder
+            .input(CellInput::new_builder().previous_output(auth_input_out_point).build())
+            .output(CellOutput::new_builder()
+                .capacity(5000u64.pack())
+                .lock(auth_lock)
+                .build())
+            .output_data(Bytes::new().pack());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that a claim event matching the state delta is accepted.
+/// Validates the event encoding for beneficiary claims.
+#[test]
+fn test_claim_event_accepted() {
+    let result = verify_with_event(Signer::Beneficiary, create_event_witness(OPERATION_CLAIM, 5000, 200, 201));
+    assert!(result.is_ok(), "Should succeed - claim event matches, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a termination event matching the state delta is accepted.
+/// Validates the event encoding for creator terminations.
+#[test]
+fn test_termination_event_accepted() {
+    let result = verify_with_event(Signer::Creator, create_event_witness(OPERATION_TERMINATION, 5000, 200, 201));
+    assert!(result.is_ok(), "Should succeed - termination event matches, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a block update event with a zero amount is accepted.
+/// Validates the event encoding for anonymous updates.
+#[test]
+fn test_block_update_event_accepted() {
+    let result = verify_with_event(Signer::Anonymous, create_event_witness(OPERATION_BLOCK_UPDATE, 0, 200, 201));
+    assert!(result.is_ok(), "Should succeed - block update event matches, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that events disagreeing with the validated transition are rejected.
+/// Covers the operation, amount, epoch and block number fields.
+#[test]
+fn test_inconsistent_events_rejected() {
+    let cases = [
+        ("wrong operation", create_event_witness(OPERATION_TERMINATION, 5000, 200, 201)),
+        ("wrong amount", create_event_witness(OPERATION_CLAIM, 4999, 200, 201)),
+        ("wrong epoch", create_event_witness(OPERATION_CLAIM, 5000, 199, 201)),
+        ("wrong block number", create_event_witness(OPERATION_CLAIM, 5000, 200, 200)),
+    ];
+
+    for (label, witness) in cases.iter() {
+        let result = verify_with_event(Signer::Beneficiary, witness.clone());
+        assert_eq!(extract_error_code(&result), Some(51), "Case {} should fail with an invalid event", label); // Error::InvalidEvent
+    }
+}
+
+/// Tests that events with an unknown version or wrong length are rejected.
+/// Indexers can rely on every accepted event following a known encoding.
+#[test]
+fn test_malformed_events_rejected() {
+    let mut unknown_version = vec![2u8, OPERATION_CLAIM];
+    unknown_version.extend_from_slice(&[0u8; 24]);
+    let truncated = vec![1u8, OPERATION_CLAIM, 0];
+
+    for event in [unknown_version, truncated] {
+        let witness = WitnessArgs::new_builder()
+            .output_type(Some(Bytes::from(event)).pack())
+            .build()
+            .as_bytes();
+        let result = verify_with_event(Signer::Beneficiary, witness);
+        assert_eq!(extract_error_code(&result), Some(51)); // Error::InvalidEvent
+    }
+}
@@ -0,0 +1,177 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+/// Creates grant metadata cell data with the given label, memo and external reference.
+/// The data is packed as version (1) followed by three length-prefixed fields.
+fn create_metadata_data(label: &[u8], memo: &[u8], external_ref: &[u8]) -> Bytes {
+    let mut data = vec![1u8];
+    for field in [label, memo, external_ref] {
+        data.push(field.len() as u8);
+        data.extend_from_slice(field);
+    }
+    Bytes::from(data)
+}
+
+/// Builds and verifies a grant creation transaction that also creates a metadata cell.
+/// The funding input uses funder_lock, and the metadata type args name metadata_creator_hash.
+fn verify_metadata_creation(
+    funder_is_creator: bool,
+    metadata_creator_is_creator: bool,
+    with_vesting_output: bool,
+    metadata: Bytes,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let vesting_out_point = context.deploy_cell(Loader::default().load_binary("vesting_lock"));
+    let metadata_out_point = context.deploy_cell(Loader::default().load_binary("grant_metadata"));
+
+    let (beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let vesting_lock = context.build_script(&vesting_out_point, args).expect("script");
+    let vesting_lock_hash: [u8; 32] = vesting_lock.calc_script_hash().unpack();
+
+    let metadata_creator_hash = if metadata_creator_is_creator { creator_hash } else { beneficiary_hash };
+    let mut metadata_args = vesting_lock_hash.to_vec();
+    metadata_args.extend_from_slice(&metadata_creator_hash);
+    let metadata_type = context.build_script(&metadata_out_point, Bytes::from(metadata_args)).expect("script");
+
+    let funder_lock = if funder_is_creator { creator_lock.clone() } else { beneficiary_lock };
+    let funding_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(funder_lock)
+            .build(),
+        Bytes::new(),
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(funding_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(20000u64.pack())
+            .lock(creator_lock)
+            .type_(Some(metadata_type).pack())
+            .build())
+        .output_data(metadata.pack());
+
+    if with_vesting_output {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(10161u64.pack())
+                .lock(vesting_lock)
+                .build())
+            .output_data(create_vesting_data(10000, 0, 0, 0).pack());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that the creator can label a grant in the transaction that creates it.
+/// The metadata links to the vesting cell by its lock hash.
+#[test]
+fn test_metadata_created_with_grant() {
+    let metadata = create_metadata_data(b"2026 refresher", b"Approved by compensation committee", b"OFFER-1042");
+    let result = verify_metadata_creation(true, true, true, metadata);
+    assert!(result.is_ok(), "Should succeed - creator labels a new grant, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that metadata cannot be created without the linked vesting cell.
+/// Prevents metadata from pointing at grants that do not exist.
+#[test]
+fn test_metadata_without_vesting_cell_rejected() {
+    let metadata = create_metadata_data(b"2026 refresher", b"", b"OFFER-1042");
+    let result = verify_metadata_creation(true, true, false, metadata);
+    assert!(result.is_err(), "Should fail - linked vesting cell missing");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::VestingCellNotFound
+}
+
+/// Tests that someone other than the creator cannot create metadata.
+/// The beneficiary funds the transaction but the type args name the creator.
+#[test]
+fn test_metadata_by_non_creator_rejected() {
+    let metadata = create_metadata_data(b"2026 refresher", b"", b"OFFER-1042");
+    let result = verify_metadata_creation(false, true, true, metadata);
+    assert!(result.is_err(), "Should fail - creator authorization missing");
+    assert_eq!(extract_error_code(&result), Some(20)); // Error::Unauthorized
+}
+
+/// Tests that metadata naming a creator other than the grant's creator is rejected.
+/// The beneficiary cannot attach metadata by claiming the creator role in the type args.
+#[test]
+fn test_metadata_creator_mismatch_rejected() {
+    let metadata = create_metadata_data(b"2026 refresher", b"", b"OFFER-1042");
+    let result = verify_metadata_creation(false, false, true, metadata);
+    assert!(result.is_err(), "Should fail - type args creator differs from the grant creator");
+    assert_eq!(extract_error_code(&result), Some(22)); // Error::CreatorMismatch
+}
+
+/// Tests that malformed metadata layouts are rejected.
+/// Covers unknown versions, oversized fields, truncated fields and trailing bytes.
+#[test]
+fn test_malformed_metadata_rejected() {
+    let mut unknown_version = create_metadata_data(b"label", b"", b"").to_vec();
+    unknown_version[0] = 2;
+    let oversized_label = create_metadata_data(&[b'a'; 65], b"", b"");
+    let mut truncated = create_metadata_data(b"label", b"memo", b"ref").to_vec();
+    truncated.pop();
+    let mut trailing = create_metadata_data(b"label", b"memo", b"ref").to_vec();
+    trailing.push(0);
+
+    let cases = [
+        ("unknown version", Bytes::from(unknown_version)),
+        ("oversized label", oversized_label),
+        ("truncated field", Bytes::from(truncated)),
+        ("trailing bytes", Bytes::from(trailing)),
+    ];
+
+    for (label, metadata) in cases.iter() {
+        let result = verify_metadata_creation(true, true, true, metadata.clone());
+        assert_eq!(extract_error_code(&result), Some(11), "Case {} should fail with invalid metadata", label); // Error::InvalidMetadata
+    }
+}
+
+/// Tests that the creator can update metadata after the grant exists without touching the grant.
+/// Updates only need creator authorization because the link was checked at creation.
+#[test]
+fn test_metadata_update_by_creator() {
+    let mut context = Context::default();
+    let metadata_out_point = context.deploy_cell(Loader::default().load_binary("grant_metadata"));
+
+    let (_beneficiary_lock, _beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let mut metadata_args = create_dummy_lock_hash(9).to_vec();
+    metadata_args.extend_from_slice(&creator_hash);
+    let metadata_type = context.build_script(&metadata_out_point, Bytes::from(metadata_args)).expect("script");
+
+    let metadata_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(20000u64.pack())
+            .lock(creator_lock.clone())
+            .type_(Some(metadata_type.clone()).pack())
+            .build(),
+        create_metadata_data(b"2026 refresher", b"", b"OFFER-1042"),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(metadata_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(20000u64.pack())
+            .lock(creator_lock)
+            .type_(Some(metadata_type).pack())
+            .build())
+        .output_data(create_metadata_data(b"2026 refresher", b"Amended after promotion", b"OFFER-1042-A").pack())
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_ok(), "Should succeed - creator updates metadata, got error code: {:?}", extract_error_code(&result));
+}
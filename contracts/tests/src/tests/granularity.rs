@@ -0,0 +1,187 @@
+use super::helpers::*;
+use ckb_testtool::ckb_types::bytes::Bytes;
+use ckb_testtool::context::Context;
+
+const OPTION_CLIFF_ACCRUAL: u8 = 0x10;
+const OPTION_SCHEDULE: u8 = 0x15;
+const OPTION_GRANULARITY: u8 = 0x16;
+const CLIFF_ACCRUAL_FROM_CLIFF: u8 = 1;
+
+/// Builds and verifies a beneficiary claim on a schedule over epochs 100 to 300 with a cliff at 120.
+/// The option records are passed raw, so the granularity can be combined with other options.
+fn verify_claim(
+    options: &[(u8, Vec<u8>)],
+    epoch: u64,
+    input_data: Bytes,
+    output_data: Bytes,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let tx = build_claim_tx(&mut context, options, 20161, epoch, input_data, Some(output_data));
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Creates a granularity option record vesting in the given number of steps.
+fn granularity(steps: u64) -> (u8, Vec<u8>) {
+    (OPTION_GRANULARITY, steps.to_le_bytes().to_vec())
+}
+
+/// Tests that nothing vests before the first step completes.
+/// With four steps of 50 epochs, epoch 149 is still in the first step, where continuous vesting would give 2450.
+#[test]
+fn test_nothing_vested_before_first_step() {
+    let result = verify_claim(
+        &[granularity(4)],
+        149,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 1, 0, 201),
+    );
+    assert!(result.is_err(), "Should fail - first step not completed");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that each completed step releases an equal share.
+/// At epoch 150 one of four steps is complete: 2500 can be claimed and 2501 cannot.
+#[test]
+fn test_completed_step_releases_equal_share() {
+    let result = verify_claim(
+        &[granularity(4)],
+        150,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 2500, 0, 201),
+    );
+    assert!(result.is_ok(), "Should succeed - first step completed, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_claim(
+        &[granularity(4)],
+        150,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 2501, 0, 201),
+    );
+    assert!(result.is_err(), "Should fail - claim exceeds one step");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that the vested amount stays flat until the next step completes.
+/// At epoch 299 three steps are complete, so 7500 is vested where continuous vesting would give 9950.
+#[test]
+fn test_vested_amount_flat_within_step() {
+    let result = verify_claim(
+        &[granularity(4)],
+        299,
+        create_vesting_data(10000, 2500, 0, 200),
+        create_vesting_data(10000, 7500, 0, 201),
+    );
+    assert!(result.is_ok(), "Should succeed - three steps completed, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_claim(
+        &[granularity(4)],
+        299,
+        create_vesting_data(10000, 2500, 0, 200),
+        create_vesting_data(10000, 7501, 0, 201),
+    );
+    assert!(result.is_err(), "Should fail - fourth step not completed");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests steps that do not divide the schedule evenly.
+/// Three steps over 200 epochs complete the first at epoch 166.67, vesting 3333 from epoch 167 onwards.
+#[test]
+fn test_uneven_steps_round_down() {
+    let result = verify_claim(
+        &[granularity(3)],
+        166,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 1, 0, 201),
+    );
+    assert!(result.is_err(), "Should fail - first step completes after epoch 166");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+
+    let result = verify_claim(
+        &[granularity(3)],
+        167,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 3333, 0, 201),
+    );
+    assert!(result.is_ok(), "Should succeed - first step completed, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_claim(
+        &[granularity(3)],
+        167,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 3334, 0, 201),
+    );
+    assert!(result.is_err(), "Should fail - claim exceeds one third rounded down");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that steps are measured from the cliff when accruing from the cliff.
+/// Four steps over the 180 epochs from 120 to 300 are 45 epochs each, so the first completes at epoch 165.
+#[test]
+fn test_steps_follow_cliff_accrual() {
+    let options = [granularity(4), (OPTION_CLIFF_ACCRUAL, vec![CLIFF_ACCRUAL_FROM_CLIFF])];
+
+    let result = verify_claim(
+        &options,
+        164,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 1, 0, 201),
+    );
+    assert!(result.is_err(), "Should fail - first step completes at epoch 165");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+
+    let result = verify_claim(
+        &options,
+        165,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 2500, 0, 201),
+    );
+    assert!(result.is_ok(), "Should succeed - first step since the cliff completed, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that tranches vest in the same number of steps as the base schedule.
+/// At epoch 299 a 4000 tranche over epochs 200 to 400 has completed one of four steps, so 1000 is vested rather than 1980.
+#[test]
+fn test_steps_apply_to_tranches() {
+    let result = verify_claim(
+        &[granularity(4)],
+        299,
+        create_vesting_data_with_tranches(14000, 0, 0, 200, &[(4000, 0, 200, 400, 250)]),
+        create_vesting_data_with_tranches(14000, 8500, 0, 201, &[(4000, 1000, 200, 400, 250)]),
+    );
+    assert!(result.is_ok(), "Should succeed - stepped base and tranche claims, got error code: {:?}", extract_error_code(&result));
+
+    // The aggregate stays within the vested total, so only the tranche bound is exceeded.
+    let result = verify_claim(
+        &[granularity(4)],
+        299,
+        create_vesting_data_with_tranches(14000, 0, 0, 200, &[(4000, 0, 200, 400, 250)]),
+        create_vesting_data_with_tranches(14000, 8500, 0, 201, &[(4000, 1001, 200, 400, 250)]),
+    );
+    assert!(result.is_err(), "Should fail - tranche claim exceeds its completed step");
+    assert_eq!(extract_error_code(&result), Some(49)); // Error::InvalidTrancheClaim
+}
+
+/// Tests that malformed granularity values are rejected as invalid arguments.
+/// The value is a non-zero u64, and milestone schedules cannot be stepped again.
+#[test]
+fn test_invalid_granularity_values_rejected() {
+    let mut milestones = vec![1u8];
+    milestones.extend_from_slice(&300u64.to_le_bytes());
+    milestones.extend_from_slice(&10000u16.to_le_bytes());
+
+    let cases = [
+        ("zero steps", vec![granularity(0)]),
+        ("short value", vec![(OPTION_GRANULARITY, vec![4u8; 7])]),
+        ("milestone schedule", vec![granularity(4), (OPTION_SCHEDULE, milestones)]),
+    ];
+
+    for (label, options) in cases {
+        let result = verify_claim(
+            &options,
+            200,
+            create_vesting_data(10000, 0, 0, 200),
+            create_vesting_data(10000, 0, 0, 201),
+        );
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS), "Case {} should fail with invalid args", label);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,182 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+// The contract trusts the contents of every header dep: consensus only lets a transaction
+// reference headers of blocks on the canonical chain. These tests document which header
+// manipulations the script itself tolerates and which ones it rejects.
+
+/// Builds and verifies a beneficiary claim against the given header deps.
+/// The input tracks input_block; partial claims keep a continuation output recording output_block.
+fn verify_claim_with_headers(
+    options: &[(u8, Vec<u8>)],
+    input_block: u64,
+    header_deps: &[(u64, u64)],
+    output_block: u64,
+    claimed: u64,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let base_args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+    let args = if options.is_empty() { base_args } else { create_vesting_args_with_options(base_args, options) };
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, input_block),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build());
+
+    // A full claim consumes the cell.
+    if claimed < 10000 {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity((10161u64 - claimed).pack())
+                .lock(lock_script)
+                .build())
+            .output_data(create_vesting_data(10000, claimed, 0, output_block).pack());
+    }
+
+    builder = builder
+        .output(CellOutput::new_builder()
+            .capacity(claimed.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack());
+
+    for (block_number, epoch) in header_deps.iter() {
+        let header_hash = setup_header_with_block_and_epoch(&mut context, *block_number, *epoch);
+        builder = builder.header_dep(header_hash);
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that a header with a huge epoch but a low block number is taken at face value.
+/// The script does not cross-check epochs against block numbers, so such a header fully vests the grant.
+#[test]
+fn test_huge_epoch_low_block_tolerated() {
+    let result = verify_claim_with_headers(&[], 200, &[(201, u64::MAX)], 201, 10000);
+    assert!(result.is_ok(), "Tolerated - epoch is trusted as given, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a block number lower than its epoch would imply is taken at face value.
+/// Only freshness against highest_block_seen is checked, never the block-to-epoch ratio.
+#[test]
+fn test_block_lower_than_epoch_implies_tolerated() {
+    // At epoch 250: vested = (250-100)/(300-100) * 10000 = 7500.
+    let result = verify_claim_with_headers(&[], 200, &[(201, 250)], 201, 7500);
+    assert!(result.is_ok(), "Tolerated - block and epoch are not cross-checked, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the highest block and the highest epoch may come from different headers.
+/// The output records the highest block while vesting uses the highest epoch.
+#[test]
+fn test_block_and_epoch_from_different_headers() {
+    let result = verify_claim_with_headers(&[], 200, &[(300, 150), (250, 250)], 300, 7500);
+    assert!(result.is_ok(), "Should succeed - maxima taken independently, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_claim_with_headers(&[], 200, &[(300, 150), (250, 250)], 250, 7500);
+    assert_eq!(extract_error_code(&result), Some(27), "Output must record the highest block"); // Error::BlockNumberMismatch
+}
+
+/// Tests that duplicating one header cannot satisfy a header quorum.
+/// Whether rejected by the script or by transaction verification, a duplicate is never a second observation.
+#[test]
+fn test_duplicate_headers_do_not_satisfy_quorum() {
+    let quorum = [(0x05u8, 2u64.to_le_bytes().to_vec())];
+    let result = verify_claim_with_headers(&quorum, 200, &[(201, 200), (201, 200)], 201, 5000);
+    assert!(result.is_err(), "Should fail - duplicate headers count once");
+}
+
+/// Tests that a header dep unknown to the chain is rejected.
+/// Headers from an unrelated chain cannot be resolved, so the script never sees them.
+#[test]
+fn test_unknown_header_rejected() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let lock_script = context
+        .build_script(&out_point, create_vesting_args(create_dummy_lock_hash(1), create_dummy_lock_hash(2), 100, 300, 120))
+        .expect("script");
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    // A header hash that was never added to this chain.
+    let foreign_header_hash = Byte32::new([0x42; 32]);
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 201).pack())
+        .header_dep(foreign_header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - header is not on this chain");
+}
+
+/// Tests that a cell which recorded block u64::MAX can never be spent again without the bound.
+/// No header can be fresher, which is why the maximum block advance option exists.
+#[test]
+fn test_maximum_block_freezes_cell() {
+    let result = verify_claim_with_headers(&[], u64::MAX, &[(u64::MAX, 300)], u64::MAX, 5000);
+    assert!(result.is_err(), "Should fail - no header can be fresher than u64::MAX");
+    assert_eq!(extract_error_code(&result), Some(24)); // Error::StaleHeader
+}
+
+/// Tests that the maximum block advance option rejects the header that would freeze the cell.
+/// With the bound configured, a u64::MAX header can never be recorded in the first place.
+#[test]
+fn test_block_advance_bound_prevents_freeze() {
+    let bound = [(0x06u8, 1000u64.to_le_bytes().to_vec())];
+    let result = verify_claim_with_headers(&bound, 200, &[(u64::MAX, 200)], u64::MAX, 5000);
+    assert!(result.is_err(), "Should fail - header jumps to u64::MAX");
+    assert_eq!(extract_error_code(&result), Some(55)); // Error::BlockAdvanceTooLarge
+}
+
+/// Tests that a header older than highest_block_seen still contributes its epoch next to a fresh header.
+/// Freshness only applies to the highest block; on a real chain older headers never carry higher epochs.
+#[test]
+fn test_stale_header_alongside_fresh_header() {
+    let result = verify_claim_with_headers(&[], 200, &[(150, 250), (201, 200)], 201, 7500);
+    assert!(result.is_ok(), "Tolerated - a stale header may raise the epoch, got error code: {:?}", extract_error_code(&result));
+}
@@ -0,0 +1,124 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+/// Builds and verifies a beneficiary claim for a cell requiring the given number of distinct header deps.
+/// The output records the highest header block, and the input tracks block 200.
+fn verify_quorum_claim(
+    min_header_deps: u64,
+    headers: &[(u64, u64)],
+    claimed: u64,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            creator_hash,
+            beneficiary_hash,
+            100, // start_epoch
+            300, // end_epoch
+            120, // cliff_epoch
+        ),
+        &[(0x05, min_header_deps.to_le_bytes().to_vec())],
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let highest_block = headers.iter().map(|(block, _)| *block).max().unwrap_or(0);
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity((10161u64 - claimed).pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, claimed, 0, highest_block).pack())
+        .output(CellOutput::new_builder()
+            .capacity(claimed.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack());
+
+    for (block_number, epoch) in headers.iter() {
+        let header_hash = setup_header_with_block_and_epoch(&mut context, *block_number, *epoch);
+        builder = builder.header_dep(header_hash);
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that vesting uses the lowest epoch among the required headers.
+/// At epoch 200: vested = (200-100)/(300-100) * 10000 = 5000, even though another header shows epoch 250.
+#[test]
+fn test_quorum_uses_lowest_epoch() {
+    let result = verify_quorum_claim(2, &[(201, 200), (202, 250)], 5000);
+    assert!(result.is_ok(), "Should succeed - claim matches the lowest epoch, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a single header with an inflated epoch cannot raise the vested amount.
+/// Claiming the amount vested at the highest epoch (7500) exceeds the quorum's vested amount.
+#[test]
+fn test_quorum_rejects_claim_at_highest_epoch() {
+    let result = verify_quorum_claim(2, &[(201, 200), (202, 250)], 7500);
+    assert!(result.is_err(), "Should fail - claim uses the highest epoch");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that fewer header deps than required are rejected.
+/// A single header cannot satisfy a quorum of two.
+#[test]
+fn test_quorum_rejects_too_few_headers() {
+    let result = verify_quorum_claim(2, &[(201, 200)], 5000);
+    assert!(result.is_err(), "Should fail - only one header dependency");
+    assert_eq!(extract_error_code(&result), Some(54)); // Error::InsufficientHeaderDependencies
+}
+
+/// Tests that headers from the same block count only once toward the quorum.
+/// Two headers sharing a block number are not independent observations.
+#[test]
+fn test_quorum_rejects_headers_from_same_block() {
+    let result = verify_quorum_claim(2, &[(201, 200), (201, 210)], 5000);
+    assert!(result.is_err(), "Should fail - headers share a block number");
+    assert_eq!(extract_error_code(&result), Some(54)); // Error::InsufficientHeaderDependencies
+}
+
+/// Tests that a quorum larger than the header dep cap is rejected as invalid args.
+/// No transaction could ever satisfy such a cell, so it must not be spendable by accident.
+#[test]
+fn test_quorum_above_header_cap_rejected() {
+    let result = verify_quorum_claim(33, &[(201, 200), (202, 250)], 5000);
+    assert!(result.is_err(), "Should fail - quorum exceeds the header dep cap");
+    assert_eq!(extract_error_code(&result), Some(10)); // Error::InvalidArgs
+}
+
+/// Tests that a zero quorum is rejected as invalid args.
+/// Cells without a quorum simply omit the option.
+#[test]
+fn test_quorum_of_zero_rejected() {
+    let result = verify_quorum_claim(0, &[(201, 200)], 5000);
+    assert!(result.is_err(), "Should fail - zero quorum");
+    assert_eq!(extract_error_code(&result), Some(10)); // Error::InvalidArgs
+}
@@ -0,0 +1,396 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+/// Tests that the highest block number is selected from a large set of header dependencies.
+/// Validates that block selection is independent of header position and mixed heights.
+#[test]
+fn test_many_headers_highest_block_selected() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let creator_hash = create_dummy_lock_hash(1);
+    let beneficiary_hash = create_dummy_lock_hash(2);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        400, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    // Create twelve headers with mixed heights, placing the highest block in the middle.
+    let blocks_and_epochs: [(u64, u64); 12] = [
+        (210, 205),
+        (180, 175),
+        (330, 320),
+        (205, 200),
+        (260, 250),
+        (415, 360), // Highest block
+        (300, 290),
+        (150, 140),
+        (390, 370), // Highest epoch
+        (220, 210),
+        (100, 95),
+        (240, 230),
+    ];
+    let mut builder = TransactionBuilder::default();
+    for (block_number, epoch) in blocks_and_epochs.iter() {
+        let header_hash = setup_header_with_block_and_epoch(&mut context, *block_number, *epoch);
+        builder = builder.header_dep(header_hash);
+    }
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200), // highest_block_seen = 200
+    );
+
+    // Anonymous update must record the highest block among all headers.
+    let tx = builder
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 415).pack())
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_ok(), "Should succeed - highest block selected from many headers, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that recording a block lower than the maximum across many headers is rejected.
+/// Validates that the second-highest header cannot be used to satisfy the exact match rule.
+#[test]
+fn test_many_headers_second_highest_block_rejected() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let creator_hash = create_dummy_lock_hash(1);
+    let beneficiary_hash = create_dummy_lock_hash(2);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        400, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    let mut builder = TransactionBuilder::default();
+    for block_number in [260u64, 390, 210, 415, 300, 330, 240, 280] {
+        let header_hash = setup_header_with_block_and_epoch(&mut context, block_number, block_number - 10);
+        builder = builder.header_dep(header_hash);
+    }
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200), // highest_block_seen = 200
+    );
+
+    // Output records 390 (second highest) instead of 415.
+    let tx = builder
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 390).pack())
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - output must match the highest header block");
+    assert_eq!(extract_error_code(&result), Some(27)); // Error::BlockNumberMismatch
+}
+
+/// Tests that stale headers mixed with one fresh header are tolerated.
+/// Validates that freshness is judged on the maximum block rather than on every header.
+#[test]
+fn test_irrelevant_stale_headers_tolerated() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    // Several headers at or below the input's highest_block_seen (200) and one fresh header.
+    let stale_hash_1 = setup_header_with_block_and_epoch(&mut context, 50, 40);
+    let stale_hash_2 = setup_header_with_block_and_epoch(&mut context, 200, 190);
+    let stale_hash_3 = setup_header_with_block_and_epoch(&mut context, 120, 110);
+    let fresh_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    // At epoch 200: vested = (200-100)/(300-100) * 10000 = 5000.
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(5161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 5000, 0, 201).pack())
+        .output(CellOutput::new_builder()
+            .capacity(5000u64.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(stale_hash_1)
+        .header_dep(stale_hash_2)
+        .header_dep(fresh_hash)
+        .header_dep(stale_hash_3)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_ok(), "Should succeed - stale headers are ignored when a fresh header exists, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the highest block and highest epoch are selected independently.
+/// When one header has the highest block and another the highest epoch, both maxima are used.
+#[test]
+fn test_disagreeing_headers_use_independent_maxima() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        400, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    // Header A has the highest block but a low epoch; header B has the highest epoch but a lower block.
+    let header_a = setup_header_with_block_and_epoch(&mut context, 500, 150);
+    let header_b = setup_header_with_block_and_epoch(&mut context, 300, 250);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    // Vesting uses epoch 250 from header B: (250-100)/(400-100) * 10000 = 5000.
+    // Block tracking uses block 500 from header A.
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(5161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 5000, 0, 500).pack())
+        .output(CellOutput::new_builder()
+            .capacity(5000u64.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(header_a)
+        .header_dep(header_b)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_ok(), "Should succeed - block and epoch maxima are selected independently, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that header dependency order does not affect the validation outcome.
+/// The same disagreeing headers in reversed order must produce identical results.
+#[test]
+fn test_header_order_is_deterministic() {
+    // Each header order is verified in a fresh context with the same claim.
+    let header_orders: [[(u64, u64); 3]; 2] = [
+        [(500, 150), (300, 250), (260, 180)],
+        [(260, 180), (300, 250), (500, 150)],
+    ];
+
+    for headers in header_orders.iter() {
+        let mut context = Context::default();
+        let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+        let out_point = context.deploy_cell(contract_bin);
+
+        let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+        let args = create_vesting_args(
+            creator_hash,
+            beneficiary_hash,
+            100, // start_epoch
+            400, // end_epoch
+            120, // cliff_epoch
+        );
+
+        let lock_script = context.build_script(&out_point, args).expect("script");
+
+        let vesting_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(10161u64.pack())
+                .lock(lock_script.clone())
+                .build(),
+            create_vesting_data(10000, 0, 0, 200),
+        );
+
+        let beneficiary_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(6100000000u64.pack())
+                .lock(beneficiary_lock.clone())
+                .build(),
+            Bytes::new(),
+        );
+
+        let mut builder = TransactionBuilder::default();
+        for (block_number, epoch) in headers.iter() {
+            let header_hash = setup_header_with_block_and_epoch(&mut context, *block_number, *epoch);
+            builder = builder.header_dep(header_hash);
+        }
+
+        // Claiming one more than vested at epoch 250 must fail regardless of order.
+        let over_claim_tx = builder
+            .clone()
+            .input(CellInput::new_builder().previous_output(vesting_input_out_point.clone()).build())
+            .input(CellInput::new_builder().previous_output(beneficiary_input_out_point.clone()).build())
+            .output(CellOutput::new_builder()
+                .capacity(5160u64.pack())
+                .lock(lock_script.clone())
+                .build())
+            .output_data(create_vesting_data(10000, 5001, 0, 500).pack())
+            .build();
+        let over_claim_tx = context.complete_tx(over_claim_tx);
+
+        let result = context.verify_tx(&over_claim_tx, MAX_CYCLES);
+        assert_eq!(extract_error_code(&result), Some(21), "Over-claim must fail identically for header order {:?}", headers);
+
+        // Claiming exactly the vested amount must succeed regardless of order.
+        let claim_tx = builder
+            .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+            .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+            .output(CellOutput::new_builder()
+                .capacity(5161u64.pack())
+                .lock(lock_script)
+                .build())
+            .output_data(create_vesting_data(10000, 5000, 0, 500).pack())
+            .build();
+        let claim_tx = context.complete_tx(claim_tx);
+
+        let result = context.verify_tx(&claim_tx, MAX_CYCLES);
+        assert!(result.is_ok(), "Should succeed for header order {:?}, got error code: {:?}", headers, extract_error_code(&result));
+    }
+}
+
+/// Builds an anonymous update that carries the given number of header dependencies.
+/// The freshest header is always last so the expected output block is known.
+fn verify_update_with_header_count(
+    header_count: u64,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let args = create_vesting_args(
+        create_dummy_lock_hash(1),
+        create_dummy_lock_hash(2),
+        100, // start_epoch
+        400, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let mut builder = TransactionBuilder::default();
+    for offset in 1..=header_count {
+        let header_hash = setup_header_with_block_and_epoch(&mut context, 200 + offset, 150 + offset);
+        builder = builder.header_dep(header_hash);
+    }
+
+    let tx = builder
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 200 + header_count).pack())
+        .build();
+    let tx = context.complete_tx(tx);
+
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that exactly the maximum number of header dependencies is accepted.
+/// Validates the inclusive boundary of the header dependency cap.
+#[test]
+fn test_header_deps_at_cap_accepted() {
+    let result = verify_update_with_header_count(32);
+    assert!(result.is_ok(), "Should succeed - 32 header deps is within the cap, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that one header dependency beyond the cap is rejected.
+/// Prevents adversarial transactions from inflating header iteration cost.
+#[test]
+fn test_header_deps_over_cap_rejected() {
+    let result = verify_update_with_header_count(33);
+    assert!(result.is_err(), "Should fail - 33 header deps exceeds the cap");
+    assert_eq!(extract_error_code(&result), Some(45)); // Error::TooManyHeaderDependencies
+}
@@ -1,10 +1,27 @@
 use crate::Loader;
 use ckb_testtool::builtin::ALWAYS_SUCCESS;
-use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::ckb_types::{
+    bytes::Bytes,
+    core::{Cycle, TransactionBuilder, TransactionView},
+    packed::*,
+    prelude::*,
+};
 use ckb_testtool::context::Context;
+use std::path::PathBuf;
 
 pub const MAX_CYCLES: u64 = 10_000_000;
 
+/// Header dependency limit enforced by the contract and the marginal cycle budget per extra header.
+/// The scaling harness checks both so the cap stays far below what MAX_CYCLES could afford.
+pub const MAX_HEADER_DEPS: usize = 32;
+pub const HEADER_DEP_CYCLE_BUDGET: u64 = 50_000;
+
+/// Capacity of the proxy authorization input the shared builders add for each signing party.
+pub const AUTH_INPUT_CAPACITY: u64 = 6_100_000_000;
+
+/// Fee a shared builder declares when a signing party pays for the transaction.
+pub const TX_FEE: u64 = 1000;
+
 /// Error codes from the vesting lock contract.
 pub const ERROR_INVALID_ARGS: i8 = 10;
 pub const ERROR_INVALID_EPOCH: i8 = 23;
@@ -67,6 +84,37 @@ pub fn create_vesting_args(
     Bytes::from(args)
 }
 
+/// Appends an args extension to base vesting args.
+/// The extension is a version byte (1) followed by (tag, length, value) option records.
+pub fn create_vesting_args_with_options(base_args: Bytes, options: &[(u8, Vec<u8>)]) -> Bytes {
+    let mut args = base_args.to_vec();
+    args.push(1);
+    for (tag, value) in options.iter() {
+        args.push(*tag);
+        args.push(value.len() as u8);
+        args.extend_from_slice(value);
+    }
+    Bytes::from(args)
+}
+
+/// Creates an args prefix auth policy option value matching the given lock script's code.
+/// The value is policy (2) + code_hash (32) + hash_type (1) + args_prefix.
+pub fn create_auth_lock_pattern(lock: &Script, args_prefix: &[u8]) -> Vec<u8> {
+    let mut value = create_code_hash_auth_policy(lock);
+    value[0] = 2;
+    value.extend_from_slice(args_prefix);
+    value
+}
+
+/// Creates a code hash auth policy option value matching the given lock script's code.
+/// The value is policy (1) + code_hash (32) + hash_type (1).
+pub fn create_code_hash_auth_policy(lock: &Script) -> Vec<u8> {
+    let mut value = vec![1u8];
+    value.extend_from_slice(lock.code_hash().as_slice());
+    value.push(lock.hash_type().into());
+    value
+}
+
 /// Creates vesting cell data from the given parameters.
 /// The data is packed as 32 bytes: total_amount (8) + beneficiary_claimed (8) +
 /// creator_claimed (8) + highest_block_seen (8).
@@ -84,6 +132,93 @@ pub fn create_vesting_data(
     Bytes::from(data)
 }
 
+/// Creates vesting cell data followed by the tranche count and tranche records.
+/// Each tranche is given as (total_amount, claimed, start_epoch, end_epoch, cliff_epoch) and
+/// packed as 40 bytes after the 32-byte aggregate state and the 1-byte count.
+pub fn create_vesting_data_with_tranches(
+    total_amount: u64,
+    beneficiary_claimed: u64,
+    creator_claimed: u64,
+    highest_block_seen: u64,
+    tranches: &[(u64, u64, u64, u64, u64)],
+) -> Bytes {
+    let mut data = create_vesting_data(total_amount, beneficiary_claimed, creator_claimed, highest_block_seen).to_vec();
+    if !tranches.is_empty() {
+        data.push(tranches.len() as u8);
+    }
+    for (tranche_total, claimed, start_epoch, end_epoch, cliff_epoch) in tranches.iter() {
+        data.extend_from_slice(&tranche_total.to_le_bytes());
+        data.extend_from_slice(&claimed.to_le_bytes());
+        data.extend_from_slice(&start_epoch.to_le_bytes());
+        data.extend_from_slice(&end_epoch.to_le_bytes());
+        data.extend_from_slice(&cliff_epoch.to_le_bytes());
+    }
+    Bytes::from(data)
+}
+
+/// Appends a version 1 extension area with the given fields to vesting cell data.
+/// The area follows the tranche records and is 1 to 39 bytes long including the version byte.
+pub fn with_data_extension(data: Bytes, fields: &[u8]) -> Bytes {
+    let mut data = with_tranche_count(data).to_vec();
+    data.push(1);
+    data.extend_from_slice(fields);
+    Bytes::from(data)
+}
+
+/// Appends a zero tranche count to bare 32-byte vesting cell data.
+/// Anything stored after the cell data must follow the count, so extension areas need it even without tranches.
+pub fn with_tranche_count(data: Bytes) -> Bytes {
+    let mut data = data.to_vec();
+    if data.len() == 32 {
+        data.push(0);
+    }
+    Bytes::from(data)
+}
+
+/// Creates a vesting input witness that redirects a beneficiary claim to the given payout lock.
+/// The payout lock hash is placed in the lock field of a WitnessArgs structure.
+pub fn create_payout_witness(payout_lock_hash: [u8; 32]) -> Bytes {
+    WitnessArgs::new_builder()
+        .lock(Some(Bytes::from(payout_lock_hash.to_vec())).pack())
+        .build()
+        .as_bytes()
+}
+
+/// Creates a vesting input witness carrying an event in its output_type field.
+/// The event is version (1) + operation (1) + amount (8) + epoch (8) + block_number (8).
+pub fn create_event_witness(operation: u8, amount: u64, epoch: u64, block_number: u64) -> Bytes {
+    let mut event = vec![1u8, operation];
+    event.extend_from_slice(&amount.to_le_bytes());
+    event.extend_from_slice(&epoch.to_le_bytes());
+    event.extend_from_slice(&block_number.to_le_bytes());
+    WitnessArgs::new_builder()
+        .output_type(Some(Bytes::from(event)).pack())
+        .build()
+        .as_bytes()
+}
+
+/// Creates a vesting input witness requesting a role rotation in its input_type field.
+/// The rotation is operation (1) + index of the relocked output (4).
+pub fn create_rotation_witness(operation: u8, output_index: u32) -> Bytes {
+    let mut rotation = vec![operation];
+    rotation.extend_from_slice(&output_index.to_le_bytes());
+    WitnessArgs::new_builder()
+        .input_type(Some(Bytes::from(rotation)).pack())
+        .build()
+        .as_bytes()
+}
+
+/// Creates a vesting input witness requesting a creator buyout in its input_type field.
+/// The buyout is operation 8 followed by the amount the beneficiary receives (8).
+pub fn create_buyout_witness(buyout_amount: u64) -> Bytes {
+    let mut buyout = vec![8u8];
+    buyout.extend_from_slice(&buyout_amount.to_le_bytes());
+    WitnessArgs::new_builder()
+        .input_type(Some(Bytes::from(buyout)).pack())
+        .build()
+        .as_bytes()
+}
+
 /// Creates ALWAYS_SUCCESS lock scripts with distinct arguments for testing proxy lock patterns.
 /// This technique allows creating different lock scripts that all validate successfully,
 /// enabling proper authorization testing in the vesting contract.
@@ -94,6 +229,14 @@ pub fn create_always_success_lock_with_args(context: &mut Context, args: Vec<u8>
     (lock_script, lock_hash)
 }
 
+/// Returns the path of a file in the fixtures directory.
+pub fn fixture_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("fixtures");
+    path.push(name);
+    path
+}
+
 /// Sets up authorization locks for testing beneficiary and creator operations.
 /// Returns (beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) tuple.
 pub fn setup_authorization_locks(context: &mut Context) -> (Script, [u8; 32], Script, [u8; 32]) {
@@ -135,4 +278,245 @@ pub fn setup_header_with_block_and_epoch(context: &mut Context, block_number: u6
 pub fn setup_header_with_epoch(context: &mut Context, epoch: u64) -> Byte32 {
     // Use epoch as block number for backward compatibility.
     setup_header_with_block_and_epoch(context, epoch, epoch)
-}
\ No newline at end of file
+}
+
+/// Creates a test context whose generated out points are identical across runs and machines.
+/// Scenarios built from this context can be replayed exactly from their printed description.
+pub fn deterministic_context() -> Context {
+    Context::new_with_deterministic_rng()
+}
+
+/// Formats bytes as a lowercase hexadecimal string.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Produces a compact description of a transaction and the cells and headers it references.
+/// Lists inputs with their data, outputs with capacity, lock hash and data, and headers with block and epoch.
+pub fn describe_scenario(context: &Context, tx: &TransactionView) -> String {
+    let mut lines = vec![format!("tx 0x{:x}", tx.hash())];
+
+    for input in tx.inputs().into_iter() {
+        let out_point = input.previous_output();
+        let (output, data) = context.get_cell(&out_point).expect("input cell");
+        let capacity: u64 = output.capacity().unpack();
+        lines.push(format!(
+            "  input 0x{:x}:{} capacity={} lock=0x{} data=0x{}",
+            out_point.tx_hash(),
+            Unpack::<u32>::unpack(&out_point.index()),
+            capacity,
+            to_hex(&output.lock().calc_script_hash().as_slice()[..8]),
+            to_hex(&data),
+        ));
+    }
+
+    for (output, data) in tx.outputs_with_data_iter() {
+        let capacity: u64 = output.capacity().unpack();
+        lines.push(format!(
+            "  output capacity={} lock=0x{} data=0x{}",
+            capacity,
+            to_hex(&output.lock().calc_script_hash().as_slice()[..8]),
+            to_hex(&data),
+        ));
+    }
+
+    for header_hash in tx.header_deps_iter() {
+        let header = context.headers.get(&header_hash).expect("header dep");
+        lines.push(format!(
+            "  header 0x{:x} block={} epoch={}",
+            header_hash,
+            header.number(),
+            header.epoch().full_value(),
+        ));
+    }
+
+    lines.join("\n")
+}
+
+/// Appends an output returning the given capacity to a lock, with empty data.
+/// Shared builders add it last so the indices of the outputs under test do not move.
+pub fn with_change_output(builder: TransactionBuilder, lock: Script, capacity: u64) -> TransactionBuilder {
+    builder
+        .output(CellOutput::new_builder()
+            .capacity(capacity.pack())
+            .lock(lock)
+            .build())
+        .output_data(Bytes::new().pack())
+}
+
+/// Deploys the vesting lock with a schedule over epochs 100 to 300 and a cliff at 120.
+/// Empty options leave the args without an extension.
+fn deploy_feature_lock(context: &mut Context, options: &[(u8, Vec<u8>)]) -> Script {
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (_beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(context);
+
+    let base_args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+    let args = if options.is_empty() { base_args } else { create_vesting_args_with_options(base_args, options) };
+    context.build_script(&out_point, args).expect("script")
+}
+
+/// Builds a beneficiary claim for the per-feature tests, with the header at block 201 and the given epoch.
+/// The continuation keeps the input capacity; output_data None consumes the cell instead.
+pub fn build_claim_tx(
+    context: &mut Context,
+    options: &[(u8, Vec<u8>)],
+    capacity: u64,
+    epoch: u64,
+    input_data: Bytes,
+    output_data: Option<Bytes>,
+) -> TransactionView {
+    let lock_script = deploy_feature_lock(context, options);
+    let (beneficiary_lock, _) = create_always_success_lock_with_args(context, vec![1u8]);
+    let header_hash = setup_header_with_block_and_epoch(context, 201, epoch);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(capacity.pack())
+            .lock(lock_script.clone())
+            .build(),
+        input_data,
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(AUTH_INPUT_CAPACITY.pack())
+            .lock(beneficiary_lock)
+            .build(),
+        Bytes::new(),
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .header_dep(header_hash);
+
+    if let Some(output_data) = output_data {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(capacity.pack())
+                .lock(lock_script)
+                .build())
+            .output_data(output_data.pack());
+    }
+
+    context.complete_tx(builder.build())
+}
+
+/// Builds a termination of a 10000 grant at epoch 200 for the per-feature tests, reclaiming the unvested 5000.
+/// Each signer spends one authorization input, and the payouts follow the continuation as plain outputs.
+pub fn build_termination_tx(
+    context: &mut Context,
+    options: &[(u8, Vec<u8>)],
+    signers: &[Script],
+    payouts: &[(Script, u64)],
+) -> TransactionView {
+    let lock_script = deploy_feature_lock(context, options);
+    let header_hash = setup_header_with_block_and_epoch(context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(5161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 5000, 201).pack())
+        .header_dep(header_hash);
+
+    for signer_lock in signers {
+        let signer_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(AUTH_INPUT_CAPACITY.pack())
+                .lock(signer_lock.clone())
+                .build(),
+            Bytes::new(),
+        );
+        builder = builder.input(CellInput::new_builder().previous_output(signer_input_out_point).build());
+    }
+
+    for (payout_lock, payout_capacity) in payouts {
+        builder = with_change_output(builder, payout_lock.clone(), *payout_capacity);
+    }
+
+    context.complete_tx(builder.build())
+}
+
+/// Sums the input and output capacities of a built transaction.
+/// Input capacities are looked up from the cells created in the context.
+pub fn sum_capacities(context: &Context, tx: &TransactionView) -> (u64, u64) {
+    let input_capacity = tx
+        .inputs()
+        .into_iter()
+        .map(|input| {
+            let (output, _) = context.get_cell(&input.previous_output()).expect("input cell");
+            Unpack::<u64>::unpack(&output.capacity())
+        })
+        .sum();
+    let output_capacity = tx
+        .outputs()
+        .into_iter()
+        .map(|output| Unpack::<u64>::unpack(&output.capacity()))
+        .sum();
+    (input_capacity, output_capacity)
+}
+
+/// Asserts that the outputs hold exactly the input capacity minus the declared fee.
+/// Replaces hand-computed capacity arithmetic in tests that check conservation.
+pub fn assert_capacity_conserved(context: &Context, tx: &TransactionView, fee: u64) {
+    let (input_capacity, output_capacity) = sum_capacities(context, tx);
+    assert_eq!(
+        input_capacity,
+        output_capacity + fee,
+        "Capacity not conserved: inputs {} != outputs {} + fee {}",
+        input_capacity, output_capacity, fee
+    );
+}
+
+/// Verifies a transaction and prints its scenario description when verification fails.
+/// Use together with deterministic_context so the printed scenario can be replayed on any machine.
+pub fn verify_scenario(
+    context: &Context,
+    tx: &TransactionView,
+    max_cycles: u64,
+) -> Result<Cycle, ckb_testtool::ckb_error::Error> {
+    // Scenarios never create capacity, whatever fee they leave.
+    let (input_capacity, output_capacity) = sum_capacities(context, tx);
+    assert!(
+        output_capacity <= input_capacity,
+        "Scenario creates capacity: outputs {} > inputs {}\n{}",
+        output_capacity, input_capacity, describe_scenario(context, tx)
+    );
+
+    let result = context.verify_tx(tx, max_cycles);
+    if result.is_err() {
+        println!("Failed scenario (error code {:?}):\n{}", extract_error_code(&result), describe_scenario(context, tx));
+    }
+    result
+}
+
+/// Verifies a transaction that pays exactly the declared fee, printing its scenario description when verification fails.
+/// Builders that return change through with_change_output use this so an unbalanced transaction fails loudly.
+pub fn verify_with_fee(
+    context: &Context,
+    tx: &TransactionView,
+    fee: u64,
+    max_cycles: u64,
+) -> Result<Cycle, ckb_testtool::ckb_error::Error> {
+    assert_capacity_conserved(context, tx, fee);
+    verify_scenario(context, tx, max_cycles)
+}
@@ -0,0 +1,259 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::{Capacity, TransactionBuilder}, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const OPTION_KEEPER_TIP: u8 = 0x03;
+const OPTION_MIN_UPDATE_INTERVAL: u8 = 0x04;
+
+/// Capacity of a funded vesting cell, well above its 10000 unclaimed plus its occupied capacity.
+const FUNDED_CAPACITY: u64 = 100_000_000_000;
+
+/// Builds and verifies an anonymous update from block 200 to 300 that moves the cell to the given capacity.
+/// A keeper tip option and a 100-block update interval are added to the args when keeper_tip is set.
+fn verify_update_with_capacity(
+    keeper_tip: Option<u64>,
+    input_capacity: u64,
+    output_capacity: u64,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let base_args = create_vesting_args(
+        create_dummy_lock_hash(1),
+        create_dummy_lock_hash(2),
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+    let args = match keeper_tip {
+        Some(tip) => create_vesting_args_with_options(
+            base_args,
+            &[
+                (OPTION_KEEPER_TIP, tip.to_le_bytes().to_vec()),
+                (OPTION_MIN_UPDATE_INTERVAL, 100u64.to_le_bytes().to_vec()),
+            ],
+        ),
+        None => base_args,
+    };
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let keeper_lock = create_dummy_lock_script(&mut context);
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 300, 200);
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(input_capacity.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    // The keeper collects whatever capacity the update releases.
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(output_capacity.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 300).pack())
+        .output(CellOutput::new_builder()
+            .capacity(input_capacity.saturating_sub(output_capacity).pack())
+            .lock(keeper_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that a keeper may take exactly the configured tip.
+/// The tip is the incentive for third parties to keep highest_block_seen fresh.
+#[test]
+fn test_keeper_takes_configured_tip() {
+    let result = verify_update_with_capacity(Some(100), FUNDED_CAPACITY, FUNDED_CAPACITY - 100);
+    assert!(result.is_ok(), "Should succeed - keeper takes the configured tip, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a keeper cannot take more than the configured tip.
+/// Validates that the capacity decrease is bounded.
+#[test]
+fn test_keeper_tip_above_limit_rejected() {
+    let result = verify_update_with_capacity(Some(100), FUNDED_CAPACITY, FUNDED_CAPACITY - 101);
+    assert!(result.is_err(), "Should fail - keeper takes more than the tip");
+    assert_eq!(extract_error_code(&result), Some(52)); // Error::KeeperTipExceeded
+}
+
+/// Tests that updates without a keeper tip option cannot remove any capacity.
+/// Protects vesting funds from being drained through anonymous updates.
+#[test]
+fn test_update_without_tip_cannot_remove_capacity() {
+    let result = verify_update_with_capacity(None, FUNDED_CAPACITY, FUNDED_CAPACITY - 1);
+    assert!(result.is_err(), "Should fail - no tip is configured");
+    assert_eq!(extract_error_code(&result), Some(52)); // Error::KeeperTipExceeded
+}
+
+/// Tests that an update keeping the full capacity succeeds with or without a tip option.
+/// Keepers may always choose to take less than the tip.
+#[test]
+fn test_update_keeping_full_capacity_accepted() {
+    for keeper_tip in [None, Some(100)] {
+        let result = verify_update_with_capacity(keeper_tip, FUNDED_CAPACITY, FUNDED_CAPACITY);
+        assert!(result.is_ok(), "Should succeed - no capacity removed, got error code: {:?}", extract_error_code(&result));
+    }
+}
+
+/// Tests that a tip cannot be paid out of the capacity backing the unclaimed amount.
+/// A cell holding just its 10000 unclaimed plus a little has nothing left over for keepers.
+#[test]
+fn test_keeper_tip_below_backing_rejected() {
+    let result = verify_update_with_capacity(Some(100), 10161, 10061);
+    assert!(result.is_err(), "Should fail - the tip digs into the unclaimed backing and occupied capacity");
+    assert_eq!(extract_error_code(&result), Some(52)); // Error::KeeperTipExceeded
+}
+
+/// Tests that a keeper tip without a minimum update interval is rejected.
+/// Unspaced tipped updates would let keepers drain the cell one block at a time.
+#[test]
+fn test_keeper_tip_requires_update_interval() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(create_dummy_lock_hash(1), create_dummy_lock_hash(2), 100, 300, 120),
+        &[(OPTION_KEEPER_TIP, 100u64.to_le_bytes().to_vec())],
+    );
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 300, 200);
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(FUNDED_CAPACITY.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(FUNDED_CAPACITY.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 300).pack())
+        .header_dep(header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS));
+}
+
+/// Tests a chain of tipped updates, each spending the continuation of the one before.
+/// Keepers are paid until the next tip would dig into the unclaimed backing, which is then rejected.
+#[test]
+fn test_chained_tipped_updates() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(create_dummy_lock_hash(1), create_dummy_lock_hash(2), 100, 300, 120),
+        &[
+            (OPTION_KEEPER_TIP, 100u64.to_le_bytes().to_vec()),
+            (OPTION_MIN_UPDATE_INTERVAL, 100u64.to_le_bytes().to_vec()),
+        ],
+    );
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let keeper_lock = create_dummy_lock_script(&mut context);
+
+    // Fund the cell with its occupied capacity, the 10000 unclaimed and room for two and a half tips.
+    let occupied_capacity = CellOutput::new_builder()
+        .lock(lock_script.clone())
+        .build()
+        .occupied_capacity(Capacity::bytes(create_vesting_data(10000, 0, 0, 200).len()).expect("data capacity"))
+        .expect("occupied capacity")
+        .as_u64();
+    let mut capacity = occupied_capacity + 10000 + 250;
+    let mut block = 200;
+
+    for update in 0..3 {
+        let input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(capacity.pack())
+                .lock(lock_script.clone())
+                .build(),
+            create_vesting_data(10000, 0, 0, block),
+        );
+        let header_hash = setup_header_with_block_and_epoch(&mut context, block + 100, 200);
+
+        let tx = TransactionBuilder::default()
+            .input(CellInput::new_builder().previous_output(input_out_point).build())
+            .output(CellOutput::new_builder()
+                .capacity((capacity - 100).pack())
+                .lock(lock_script.clone())
+                .build())
+            .output_data(create_vesting_data(10000, 0, 0, block + 100).pack())
+            .output(CellOutput::new_builder()
+                .capacity(100u64.pack())
+                .lock(keeper_lock.clone())
+                .build())
+            .output_data(Bytes::new().pack())
+            .header_dep(header_hash)
+            .build();
+        let tx = context.complete_tx(tx);
+
+        let result = context.verify_tx(&tx, MAX_CYCLES);
+        if update < 2 {
+            assert!(result.is_ok(), "Should succeed - update {} leaves the backing intact, got error code: {:?}", update, extract_error_code(&result));
+        } else {
+            assert!(result.is_err(), "Should fail - the third tip would dig into the unclaimed backing");
+            assert_eq!(extract_error_code(&result), Some(52)); // Error::KeeperTipExceeded
+        }
+
+        capacity -= 100;
+        block += 100;
+    }
+}
+
+/// Tests that a keeper tip option with the wrong value length is rejected.
+/// Validates strict option parsing.
+#[test]
+fn test_malformed_keeper_tip_option_rejected() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(create_dummy_lock_hash(1), create_dummy_lock_hash(2), 100, 300, 120),
+        &[(OPTION_KEEPER_TIP, vec![100u8, 0, 0, 0])],
+    );
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 201).pack())
+        .header_dep(header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS));
+}
@@ -0,0 +1,276 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_chain_spec::consensus::TYPE_ID_CODE_HASH;
+use ckb_testtool::ckb_jsonrpc_types as rpc;
+use ckb_testtool::ckb_types::{
+    bytes::Bytes,
+    core::{ScriptHashType, TransactionBuilder, TransactionView},
+    packed::*,
+    prelude::*,
+};
+use ckb_testtool::context::Context;
+use serde_json::{json, Value};
+
+// Cells in the layout issued before the args extension and tranche records existed.
+const LEGACY_V0_FIXTURES: &str = include_str!("../../fixtures/legacy_v0.json");
+
+// The same cases as serialized mock transactions, recorded once and never edited.
+const LEGACY_V0_TXS: &str = "legacy_v0_txs.json";
+
+// Fixed out point and type ID args of the vesting lock code cell in the serialized transactions.
+// Scripts reference the code by type, so the current binary can be placed in the cell without changing a transaction.
+const VESTING_CODE_TX_HASH: [u8; 32] = [0x76; 32];
+const VESTING_CODE_TYPE_ID_ARGS: [u8; 32] = [0x56; 32];
+
+/// Decodes a lowercase hex string from the fixtures.
+/// Panics on malformed fixtures so a broken file fails loudly.
+fn decode_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).expect("fixture hex"))
+        .collect()
+}
+
+/// Builds the vesting lock code cell of the serialized transactions, holding the given binary.
+/// The cell's type ID script is fixed, so its type hash does not depend on the binary.
+fn vesting_code_cell(binary: Bytes) -> (OutPoint, CellOutput, Bytes) {
+    let type_script = Script::new_builder()
+        .code_hash(TYPE_ID_CODE_HASH.pack())
+        .hash_type(ScriptHashType::Type.into())
+        .args(VESTING_CODE_TYPE_ID_ARGS.to_vec().pack())
+        .build();
+    let out_point = OutPoint::new_builder().tx_hash(VESTING_CODE_TX_HASH.pack()).build();
+    let output = CellOutput::new_builder()
+        .capacity((binary.len() as u64 * 100_000_000).pack())
+        .type_(Some(type_script).pack())
+        .build();
+    (out_point, output, binary)
+}
+
+/// Builds one fixture case with the vesting lock code deployed at the given out point.
+/// The signer's payout goes to its own lock, and the continuation output keeps the remaining capacity.
+fn build_fixture_case(
+    context: &mut Context,
+    out_point: &OutPoint,
+    schedule: &Value,
+    input_capacity: u64,
+    case: &Value,
+) -> TransactionView {
+    let (beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        schedule["start_epoch"].as_u64().expect("start_epoch"),
+        schedule["end_epoch"].as_u64().expect("end_epoch"),
+        schedule["cliff_epoch"].as_u64().expect("cliff_epoch"),
+    );
+    assert_eq!(args.len(), 88, "Legacy args must use the 88-byte layout");
+
+    let lock_script = context.build_script(out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(
+        context,
+        case["header_block"].as_u64().expect("header_block"),
+        case["header_epoch"].as_u64().expect("header_epoch"),
+    );
+
+    let input_data = decode_hex(case["input_data"].as_str().expect("input_data"));
+    assert_eq!(input_data.len(), 32, "Legacy cell data must use the 32-byte layout");
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(input_capacity.pack())
+            .lock(lock_script.clone())
+            .build(),
+        Bytes::from(input_data),
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .header_dep(header_hash);
+
+    let payout = case["payout"].as_u64().expect("payout");
+    if let Some(output_data) = case["output_data"].as_str() {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity((input_capacity - payout).pack())
+                .lock(lock_script)
+                .build())
+            .output_data(Bytes::from(decode_hex(output_data)).pack());
+    }
+
+    let signer_lock = match case["signer"].as_str().expect("signer") {
+        "beneficiary" => Some(beneficiary_lock),
+        "creator" => Some(creator_lock),
+        "anonymous" => None,
+        other => panic!("Unknown fixture signer {}", other),
+    };
+    if let Some(signer_lock) = signer_lock {
+        let signer_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(6100000000u64.pack())
+                .lock(signer_lock.clone())
+                .build(),
+            Bytes::new(),
+        );
+        builder = builder
+            .input(CellInput::new_builder().previous_output(signer_input_out_point).build())
+            .output(CellOutput::new_builder()
+                .capacity(payout.pack())
+                .lock(signer_lock)
+                .build())
+            .output_data(Bytes::new().pack());
+    }
+
+    context.complete_tx(builder.build())
+}
+
+/// Builds and verifies one fixture case against the current binary.
+fn run_fixture_case(
+    schedule: &Value,
+    input_capacity: u64,
+    case: &Value,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let tx = build_fixture_case(&mut context, &out_point, schedule, input_capacity, case);
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Rebuilds a context from a serialized mock transaction and verifies the transaction with the given binary.
+/// The vesting lock code cell is recorded without data, and the binary is placed in it before verification.
+fn replay_serialized_case(
+    binary: Bytes,
+    mock_tx: &Value,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let (code_out_point, code_output, code_data) = vesting_code_cell(binary);
+    context.create_cell_with_out_point(code_out_point.clone(), code_output, code_data);
+
+    let mock_info = &mock_tx["mock_info"];
+    let inputs = mock_info["inputs"].as_array().expect("mock inputs");
+    let cell_deps = mock_info["cell_deps"].as_array().expect("mock cell deps");
+    for cell in inputs.iter().chain(cell_deps.iter()) {
+        let out_point: OutPoint = match cell.get("input") {
+            Some(input) => CellInput::from(serde_json::from_value::<rpc::CellInput>(input.clone()).expect("mock input")).previous_output(),
+            None => CellDep::from(serde_json::from_value::<rpc::CellDep>(cell["cell_dep"].clone()).expect("mock cell dep")).out_point(),
+        };
+        if out_point == code_out_point {
+            continue;
+        }
+        let output: CellOutput = serde_json::from_value::<rpc::CellOutput>(cell["output"].clone()).expect("mock output").into();
+        let data: rpc::JsonBytes = serde_json::from_value(cell["data"].clone()).expect("mock data");
+        context.create_cell_with_out_point(out_point, output, data.into_bytes());
+    }
+    for header in mock_info["header_deps"].as_array().expect("mock header deps") {
+        let header: rpc::HeaderView = serde_json::from_value(header.clone()).expect("mock header");
+        context.insert_header(header.into());
+    }
+
+    let tx: Transaction = serde_json::from_value::<rpc::Transaction>(mock_tx["tx"].clone()).expect("mock tx").into();
+    context.verify_tx(&tx.into_view(), MAX_CYCLES)
+}
+
+/// Loads the serialized fixture cases and checks they cover the fixture scenarios one to one.
+fn load_serialized_cases() -> Vec<Value> {
+    let path = fixture_path(LEGACY_V0_TXS);
+    let file = std::fs::read_to_string(&path).unwrap_or_else(|_| panic!("Serialized fixtures {:?} are missing", path));
+    let serialized: Value = serde_json::from_str(&file).expect("serialized fixtures should be valid JSON");
+    let fixtures: Value = serde_json::from_str(LEGACY_V0_FIXTURES).expect("fixtures should be valid JSON");
+
+    let cases = serialized["cases"].as_array().expect("cases").clone();
+    let names: Vec<&Value> = cases.iter().map(|case| &case["name"]).collect();
+    let expected: Vec<&Value> = fixtures["cases"].as_array().expect("cases").iter().map(|case| &case["name"]).collect();
+    assert_eq!(names, expected, "Serialized fixtures must record every fixture case in order");
+    cases
+}
+
+/// Tests that cells issued in the legacy layout keep validating exactly as before.
+/// Every fixture case is verified in a fresh context and reports its name on mismatch.
+#[test]
+fn test_legacy_v0_fixtures() {
+    let fixtures: Value = serde_json::from_str(LEGACY_V0_FIXTURES).expect("fixtures should be valid JSON");
+    let schedule = &fixtures["schedule"];
+    let input_capacity = fixtures["input_capacity"].as_u64().expect("input_capacity");
+    let cases = fixtures["cases"].as_array().expect("cases");
+    assert!(!cases.is_empty(), "Fixture file should contain cases");
+
+    for case in cases {
+        let name = case["name"].as_str().expect("name");
+        let result = run_fixture_case(schedule, input_capacity, case);
+        match case["expected_error"].as_i64() {
+            None => assert!(
+                result.is_ok(),
+                "Fixture '{}' should succeed, got error code: {:?}",
+                name, extract_error_code(&result)
+            ),
+            Some(code) => assert_eq!(
+                extract_error_code(&result),
+                Some(code as i8),
+                "Fixture '{}' should fail with error code {}",
+                name, code
+            ),
+        }
+    }
+}
+
+/// Records the fixture cases as serialized mock transactions, once.
+/// A recording tool rather than a check: run with --ignored when the file does not exist yet; recorded transactions are never rewritten.
+#[test]
+#[ignore]
+fn record_legacy_v0_txs() {
+    let path = fixture_path(LEGACY_V0_TXS);
+    assert!(!path.exists(), "Serialized fixtures {:?} are already recorded and must not be rewritten", path);
+
+    let fixtures: Value = serde_json::from_str(LEGACY_V0_FIXTURES).expect("fixtures should be valid JSON");
+    let schedule = &fixtures["schedule"];
+    let input_capacity = fixtures["input_capacity"].as_u64().expect("input_capacity");
+
+    let cases: Vec<Value> = fixtures["cases"]
+        .as_array()
+        .expect("cases")
+        .iter()
+        .map(|case| {
+            let mut context = deterministic_context();
+            let (code_out_point, code_output, code_data) = vesting_code_cell(Bytes::new());
+            context.create_cell_with_out_point(code_out_point.clone(), code_output, code_data);
+            let tx = build_fixture_case(&mut context, &code_out_point, schedule, input_capacity, case);
+            let mock_tx = context.dump_tx(&tx).expect("dump tx");
+            json!({
+                "name": case["name"],
+                "expected_error": case["expected_error"],
+                "mock_tx": mock_tx,
+            })
+        })
+        .collect();
+
+    let serialized = json!({
+        "baseline_commit": "11df4d5",
+        "cases": cases,
+    });
+    let json = serde_json::to_string_pretty(&serialized).expect("json");
+    std::fs::write(path, json + "\n").expect("write serialized fixtures");
+}
+
+/// Tests that the serialized legacy transactions validate on the current binary as they did on the baseline.
+/// Released error codes never change, so the recorded codes are compared as they are.
+#[test]
+fn test_legacy_v0_txs_on_current_binary() {
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+
+    for case in load_serialized_cases() {
+        let name = case["name"].as_str().expect("name");
+        let result = replay_serialized_case(contract_bin.clone(), &case["mock_tx"]);
+        match case["expected_error"].as_i64() {
+            None => assert!(result.is_ok(), "Serialized fixture '{}' should succeed, got error code: {:?}", name, extract_error_code(&result)),
+            Some(code) => assert_eq!(
+                extract_error_code(&result),
+                Some(code as i8),
+                "Serialized fixture '{}' should fail with error code {}",
+                name, code
+            ),
+        }
+    }
+}
@@ -0,0 +1,446 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_hash::blake2b_256;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const OPTION_CREATOR_AUTH_POLICY: u8 = 0x01;
+const OPTION_TERMINATION_ACCELERATION: u8 = 0x0e;
+const OPTION_CO_BENEFICIARIES: u8 = 0x19;
+const OPTION_MERKLE_BENEFICIARIES: u8 = 0x1a;
+const OPTION_CREATOR_SIGNERS: u8 = 0x1b;
+const DATA_EXTENSION_MERKLE_VERSION: u8 = 3;
+const OPERATION_MERKLE_CLAIM: u8 = 9;
+const TREE_DEPTH: u8 = 2;
+
+// Args of the always-success locks standing in for each party
+const BENEFICIARY: u8 = 1;
+const CREATOR: u8 = 2;
+const ALICE: u8 = 3;
+const BOB: u8 = 4;
+
+// Leaves of the test tree as (party, amount, start_epoch, end_epoch, cliff_epoch); slots 2 and 3 are unused
+const ALICE_LEAF: (u8, u64, u64, u64, u64) = (ALICE, 6000, 100, 300, 120);
+const BOB_LEAF: (u8, u64, u64, u64, u64) = (BOB, 4000, 200, 400, 200);
+const ALICE_INDEX: u32 = 0;
+const BOB_INDEX: u32 = 1;
+
+/// Returns the lock hash of a party's always-success lock.
+/// Scripts reference the always-success code by its data hash, so the hash is the same in every context.
+fn party_lock_hash(party: u8) -> [u8; 32] {
+    create_always_success_lock_with_args(&mut Context::default(), vec![party]).1
+}
+
+/// Serializes a leaf as lock hash (32) + amount (8) + start, end and cliff epoch (8 each) + claimed (8).
+/// The claimed amount is the only field that changes as the leaf is claimed.
+fn create_leaf(leaf: (u8, u64, u64, u64, u64), claimed: u64) -> Vec<u8> {
+    let (party, amount, start_epoch, end_epoch, cliff_epoch) = leaf;
+    let mut bytes = party_lock_hash(party).to_vec();
+    for field in [amount, start_epoch, end_epoch, cliff_epoch, claimed] {
+        bytes.extend_from_slice(&field.to_le_bytes());
+    }
+    bytes
+}
+
+/// Hashes two child nodes into their parent.
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    blake2b_256([left.as_slice(), right.as_slice()].concat())
+}
+
+/// Computes the root of the test tree and the sibling hashes proving one slot.
+/// Alice and Bob hold slots 0 and 1 with the given claimed amounts, and the unused slots hash to zeros.
+fn build_tree(alice_claimed: u64, bob_claimed: u64, index: u32) -> ([u8; 32], Vec<u8>) {
+    let leaves = [
+        blake2b_256(create_leaf(ALICE_LEAF, alice_claimed)),
+        blake2b_256(create_leaf(BOB_LEAF, bob_claimed)),
+        [0u8; 32],
+        [0u8; 32],
+    ];
+    let nodes = [hash_pair(&leaves[0], &leaves[1]), hash_pair(&leaves[2], &leaves[3])];
+
+    let index = index as usize;
+    let mut siblings = leaves[index ^ 1].to_vec();
+    siblings.extend_from_slice(&nodes[(index >> 1) ^ 1]);
+    (hash_pair(&nodes[0], &nodes[1]), siblings)
+}
+
+/// Returns the root of the test tree with the given claimed amounts.
+fn tree_root(alice_claimed: u64, bob_claimed: u64) -> [u8; 32] {
+    build_tree(alice_claimed, bob_claimed, ALICE_INDEX).0
+}
+
+/// Creates the merkle beneficiaries option for the test tree before any claim.
+fn merkle_option() -> (u8, Vec<u8>) {
+    let mut value = vec![TREE_DEPTH];
+    value.extend_from_slice(&tree_root(0, 0));
+    (OPTION_MERKLE_BENEFICIARIES, value)
+}
+
+/// Creates a merkle claim for Alice's or Bob's leaf as recorded in a tree with the given claimed amounts.
+/// The claim is operation 9 + index (4) + leaf (72) + one sibling hash per level.
+fn create_claim(index: u32, alice_claimed: u64, bob_claimed: u64) -> Vec<u8> {
+    let leaf = if index == ALICE_INDEX {
+        create_leaf(ALICE_LEAF, alice_claimed)
+    } else {
+        create_leaf(BOB_LEAF, bob_claimed)
+    };
+    let (_, siblings) = build_tree(alice_claimed, bob_claimed, index);
+
+    let mut claim = vec![OPERATION_MERKLE_CLAIM];
+    claim.extend_from_slice(&index.to_le_bytes());
+    claim.extend_from_slice(&leaf);
+    claim.extend_from_slice(&siblings);
+    claim
+}
+
+/// Creates merkle cell data from the aggregate state and the current root.
+/// The root follows the cell data in a version 3 extension area.
+fn create_merkle_data(total: u64, beneficiary_claimed: u64, block: u64, root: [u8; 32]) -> Bytes {
+    let mut data = with_tranche_count(create_vesting_data(total, beneficiary_claimed, 0, block)).to_vec();
+    data.push(DATA_EXTENSION_MERKLE_VERSION);
+    data.extend_from_slice(&root);
+    Bytes::from(data)
+}
+
+/// Builds and verifies a transition of a 10000 merkle cell whose base schedule runs over epochs 100 to 300.
+/// Signers are given by the args of their always-success locks; output_data None consumes the cell and claim None sends no witness.
+fn verify_merkle(
+    options: Vec<(u8, Vec<u8>)>,
+    signers: &[u8],
+    epoch: u64,
+    input_data: Bytes,
+    output_data: Option<Bytes>,
+    claim: Option<Vec<u8>>,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (_beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            creator_hash,
+            beneficiary_hash,
+            100, // start_epoch
+            300, // end_epoch
+            120, // cliff_epoch
+        ),
+        &options,
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, epoch);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        input_data,
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .header_dep(header_hash);
+
+    for signer in signers {
+        let (signer_lock, _) = create_always_success_lock_with_args(&mut context, vec![*signer]);
+        let signer_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(6100000000u64.pack())
+                .lock(signer_lock)
+                .build(),
+            Bytes::new(),
+        );
+        builder = builder.input(CellInput::new_builder().previous_output(signer_input_out_point).build());
+    }
+
+    if let Some(output_data) = output_data {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(10161u64.pack())
+                .lock(lock_script)
+                .build())
+            .output_data(output_data.pack());
+    }
+
+    if let Some(claim) = claim {
+        builder = builder.witness(
+            WitnessArgs::new_builder()
+                .input_type(Some(Bytes::from(claim)).pack())
+                .build()
+                .as_bytes()
+                .pack(),
+        );
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that a leaf claims what it has vested on its own schedule and records the claim in the root.
+/// At epoch 200 Alice's 6000 over epochs 100 to 300 has vested 3000, so 3001 is too much.
+#[test]
+fn test_leaf_claims_vested_amount() {
+    let result = verify_merkle(
+        vec![merkle_option()],
+        &[ALICE],
+        200,
+        create_merkle_data(10000, 0, 200, tree_root(0, 0)),
+        Some(create_merkle_data(10000, 3000, 201, tree_root(3000, 0))),
+        Some(create_claim(ALICE_INDEX, 0, 0)),
+    );
+    assert!(result.is_ok(), "Should succeed - Alice claims her vested amount, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_merkle(
+        vec![merkle_option()],
+        &[ALICE],
+        200,
+        create_merkle_data(10000, 0, 200, tree_root(0, 0)),
+        Some(create_merkle_data(10000, 3001, 201, tree_root(3001, 0))),
+        Some(create_claim(ALICE_INDEX, 0, 0)),
+    );
+    assert!(result.is_err(), "Should fail - claim exceeds Alice's vested amount");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that each leaf vests on its own schedule rather than the base schedule.
+/// Bob's cliff is at epoch 200, and at epoch 300 half of his 4000 over epochs 200 to 400 has vested.
+#[test]
+fn test_leaf_vests_on_own_schedule() {
+    let result = verify_merkle(
+        vec![merkle_option()],
+        &[BOB],
+        199,
+        create_merkle_data(10000, 3000, 200, tree_root(3000, 0)),
+        Some(create_merkle_data(10000, 3001, 201, tree_root(3000, 1))),
+        Some(create_claim(BOB_INDEX, 3000, 0)),
+    );
+    assert!(result.is_err(), "Should fail - Bob's cliff not reached yet");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+
+    let result = verify_merkle(
+        vec![merkle_option()],
+        &[BOB],
+        300,
+        create_merkle_data(10000, 3000, 200, tree_root(3000, 0)),
+        Some(create_merkle_data(10000, 5000, 201, tree_root(3000, 2000))),
+        Some(create_claim(BOB_INDEX, 3000, 0)),
+    );
+    assert!(result.is_ok(), "Should succeed - Bob claims half his leaf, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a claim whose proof does not lead to the cell's current root is rejected.
+/// A stale leaf, a wrong index and a tampered amount each prove against a different root.
+#[test]
+fn test_invalid_proof_rejected() {
+    let mut wrong_index = create_claim(BOB_INDEX, 3000, 0);
+    wrong_index[1] = ALICE_INDEX as u8;
+    let mut tampered_amount = create_claim(BOB_INDEX, 3000, 0);
+    tampered_amount[5 + 32] = 0xff;
+
+    let cases = [
+        ("stale leaf", create_claim(BOB_INDEX, 0, 0)),
+        ("wrong index", wrong_index),
+        ("tampered amount", tampered_amount),
+    ];
+
+    for (label, claim) in cases {
+        let result = verify_merkle(
+            vec![merkle_option()],
+            &[BOB],
+            300,
+            create_merkle_data(10000, 3000, 200, tree_root(3000, 0)),
+            Some(create_merkle_data(10000, 5000, 201, tree_root(3000, 2000))),
+            Some(claim),
+        );
+        assert_eq!(extract_error_code(&result), Some(113), "Case {} should fail with InvalidMerkleProof", label);
+    }
+}
+
+/// Tests that only the leaf's own lock can authorize its claim.
+/// Bob, the creator and the beneficiary cannot claim Alice's leaf.
+#[test]
+fn test_claim_requires_leaf_lock() {
+    for signer in [BOB, CREATOR, BENEFICIARY] {
+        let result = verify_merkle(
+            vec![merkle_option()],
+            &[signer],
+            200,
+            create_merkle_data(10000, 0, 200, tree_root(0, 0)),
+            Some(create_merkle_data(10000, 3000, 201, tree_root(3000, 0))),
+            Some(create_claim(ALICE_INDEX, 0, 0)),
+        );
+        assert_eq!(extract_error_code(&result), Some(25), "Signer {} should fail with Unauthorized", signer);
+    }
+}
+
+/// Tests that the continuation must record the claim in its root.
+/// Keeping the old root or recording a different amount than the aggregate grew by both fail.
+#[test]
+fn test_output_root_must_record_claim() {
+    let cases = [
+        ("unchanged root", tree_root(0, 0)),
+        ("amount differs from aggregate", tree_root(2000, 0)),
+        ("other leaf updated", tree_root(0, 3000)),
+    ];
+
+    for (label, output_root) in cases {
+        let result = verify_merkle(
+            vec![merkle_option()],
+            &[ALICE],
+            200,
+            create_merkle_data(10000, 0, 200, tree_root(0, 0)),
+            Some(create_merkle_data(10000, 3000, 201, output_root)),
+            Some(create_claim(ALICE_INDEX, 0, 0)),
+        );
+        assert_eq!(extract_error_code(&result), Some(106), "Case {} should fail with MerkleRootMismatch", label);
+    }
+}
+
+/// Tests that the claim settling the cell consumes it and that earlier claims must continue it.
+/// With Alice fully claimed, Bob's 4000 at epoch 400 is the remainder of the cell.
+#[test]
+fn test_final_claim_consumes_cell() {
+    let result = verify_merkle(
+        vec![merkle_option()],
+        &[BOB],
+        400,
+        create_merkle_data(10000, 6000, 200, tree_root(6000, 0)),
+        None,
+        Some(create_claim(BOB_INDEX, 6000, 0)),
+    );
+    assert!(result.is_ok(), "Should succeed - final claim consumes the cell, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_merkle(
+        vec![merkle_option()],
+        &[BOB],
+        400,
+        create_merkle_data(10000, 6000, 200, tree_root(6000, 0)),
+        Some(create_merkle_data(10000, 10000, 201, tree_root(6000, 4000))),
+        Some(create_claim(BOB_INDEX, 6000, 0)),
+    );
+    assert!(result.is_err(), "Should fail - settling claim keeps a continuation");
+    assert_eq!(extract_error_code(&result), Some(42)); // Error::BeneficiaryFullClaimHasOutput
+
+    let result = verify_merkle(
+        vec![merkle_option()],
+        &[BOB],
+        400,
+        create_merkle_data(10000, 3000, 200, tree_root(3000, 0)),
+        None,
+        Some(create_claim(BOB_INDEX, 3000, 0)),
+    );
+    assert!(result.is_err(), "Should fail - Alice's leaf is not claimed yet");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that merkle cells only accept claims and anonymous updates.
+/// Without a claim the creator cannot terminate and the beneficiary cannot claim, while an update keeps the root.
+#[test]
+fn test_only_claims_and_updates_accepted() {
+    let result = verify_merkle(
+        vec![merkle_option()],
+        &[CREATOR],
+        200,
+        create_merkle_data(10000, 0, 200, tree_root(0, 0)),
+        None,
+        None,
+    );
+    assert!(result.is_err(), "Should fail - merkle cells cannot be terminated");
+    assert_eq!(extract_error_code(&result), Some(25)); // Error::Unauthorized
+
+    let result = verify_merkle(
+        vec![merkle_option()],
+        &[BENEFICIARY],
+        200,
+        create_merkle_data(10000, 0, 200, tree_root(0, 0)),
+        Some(create_merkle_data(10000, 5000, 201, tree_root(0, 0))),
+        None,
+    );
+    assert!(result.is_err(), "Should fail - the beneficiary has no leaf");
+    assert_eq!(extract_error_code(&result), Some(25)); // Error::Unauthorized
+
+    let result = verify_merkle(
+        vec![merkle_option()],
+        &[],
+        200,
+        create_merkle_data(10000, 0, 200, tree_root(0, 0)),
+        Some(create_merkle_data(10000, 0, 201, tree_root(0, 0))),
+        None,
+    );
+    assert!(result.is_ok(), "Should succeed - anonymous update keeps the root, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that malformed claims and merkle cell data are rejected.
+/// The proof must have one sibling per level and an index within the tree, and the root must sit in a version 3 extension.
+#[test]
+fn test_malformed_claims_and_data_rejected() {
+    let mut truncated = create_claim(ALICE_INDEX, 0, 0);
+    truncated.pop();
+    let mut index_beyond_tree = create_claim(ALICE_INDEX, 0, 0);
+    index_beyond_tree[1] = 4;
+
+    for (label, claim) in [("truncated proof", truncated), ("index beyond tree", index_beyond_tree)] {
+        let result = verify_merkle(
+            vec![merkle_option()],
+            &[ALICE],
+            200,
+            create_merkle_data(10000, 0, 200, tree_root(0, 0)),
+            Some(create_merkle_data(10000, 3000, 201, tree_root(3000, 0))),
+            Some(claim),
+        );
+        assert_eq!(extract_error_code(&result), Some(11), "Case {} should fail with InvalidWitness", label);
+    }
+
+    let result = verify_merkle(
+        vec![merkle_option()],
+        &[],
+        200,
+        create_vesting_data(10000, 0, 0, 200),
+        Some(create_vesting_data(10000, 0, 0, 201)),
+        None,
+    );
+    assert!(result.is_err(), "Should fail - merkle cell without a root");
+    assert_eq!(extract_error_code(&result), Some(59)); // Error::InvalidDataExtension
+}
+
+/// Tests that malformed merkle options and incompatible options are rejected as invalid arguments.
+/// The depth must be between 1 and 20, leaves cannot share the cell with co-beneficiaries, and nothing may set up a termination.
+#[test]
+fn test_invalid_merkle_options_rejected() {
+    let (_, valid) = merkle_option();
+    let mut zero_depth = valid.clone();
+    zero_depth[0] = 0;
+    let mut too_deep = valid.clone();
+    too_deep[0] = 21;
+    let mut co_beneficiaries = party_lock_hash(ALICE).to_vec();
+    co_beneficiaries.extend_from_slice(&3000u16.to_le_bytes());
+    let (alice_lock, _) = create_always_success_lock_with_args(&mut Context::default(), vec![ALICE]);
+    let creator_auth_policy = create_code_hash_auth_policy(&alice_lock);
+    let mut creator_signers = vec![1u8];
+    creator_signers.extend_from_slice(&party_lock_hash(ALICE));
+
+    let cases = [
+        ("zero depth", vec![(OPTION_MERKLE_BENEFICIARIES, zero_depth)]),
+        ("too deep", vec![(OPTION_MERKLE_BENEFICIARIES, too_deep)]),
+        ("missing root", vec![(OPTION_MERKLE_BENEFICIARIES, vec![TREE_DEPTH])]),
+        ("with co-beneficiaries", vec![merkle_option(), (OPTION_CO_BENEFICIARIES, co_beneficiaries)]),
+        ("with creator auth policy", vec![merkle_option(), (OPTION_CREATOR_AUTH_POLICY, creator_auth_policy)]),
+        ("with creator signers", vec![merkle_option(), (OPTION_CREATOR_SIGNERS, creator_signers)]),
+        ("with termination acceleration", vec![merkle_option(), (OPTION_TERMINATION_ACCELERATION, 2500u16.to_le_bytes().to_vec())]),
+    ];
+
+    for (label, options) in cases {
+        let result = verify_merkle(
+            options,
+            &[],
+            200,
+            create_merkle_data(10000, 0, 200, tree_root(0, 0)),
+            Some(create_merkle_data(10000, 0, 201, tree_root(0, 0))),
+            None,
+        );
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS), "Case {} should fail with invalid args", label);
+    }
+}
\ No newline at end of file
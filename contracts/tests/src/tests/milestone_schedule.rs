@@ -0,0 +1,206 @@
+use super::helpers::*;
+use ckb_testtool::ckb_types::bytes::Bytes;
+use ckb_testtool::context::Context;
+
+const OPTION_SCHEDULE: u8 = 0x15;
+const SCHEDULE_LINEAR: u8 = 0;
+const SCHEDULE_MILESTONES: u8 = 1;
+
+/// Creates a milestone schedule option value from (epoch, share_bps) records.
+/// The value is the schedule type followed by 10-byte records of epoch (8) + share (2).
+fn create_milestone_schedule(milestones: &[(u64, u16)]) -> Vec<u8> {
+    let mut value = vec![SCHEDULE_MILESTONES];
+    for (epoch, share_bps) in milestones {
+        value.extend_from_slice(&epoch.to_le_bytes());
+        value.extend_from_slice(&share_bps.to_le_bytes());
+    }
+    value
+}
+
+/// The milestones used by most tests: 25% at epoch 150, 25% at epoch 200 and the remaining 50% at epoch 300.
+fn quarterly_milestones() -> Vec<u8> {
+    create_milestone_schedule(&[(150, 2500), (200, 2500), (300, 5000)])
+}
+
+/// Builds and verifies a beneficiary claim on a schedule over epochs 100 to 300 with a cliff at 120.
+/// The schedule option value is passed raw; None leaves the option out entirely.
+fn verify_claim(
+    schedule: Option<Vec<u8>>,
+    epoch: u64,
+    input_data: Bytes,
+    output_data: Bytes,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let options: Vec<(u8, Vec<u8>)> = schedule.map(|value| (OPTION_SCHEDULE, value)).into_iter().collect();
+    let mut context = Context::default();
+    let tx = build_claim_tx(&mut context, &options, 20161, epoch, input_data, Some(output_data));
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that nothing vests between the cliff and the first milestone.
+/// A linear schedule would have vested 2450 at epoch 149, but the first milestone is at epoch 150.
+#[test]
+fn test_nothing_vested_before_first_milestone() {
+    let result = verify_claim(
+        Some(quarterly_milestones()),
+        149,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 1, 0, 201),
+    );
+    assert!(result.is_err(), "Should fail - no milestone reached yet");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that reaching a milestone releases exactly its share.
+/// At epoch 150 the first 25% is vested: 2500 can be claimed and 2501 cannot.
+#[test]
+fn test_milestone_releases_its_share() {
+    let result = verify_claim(
+        Some(quarterly_milestones()),
+        150,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 2500, 0, 201),
+    );
+    assert!(result.is_ok(), "Should succeed - first milestone reached, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_claim(
+        Some(quarterly_milestones()),
+        150,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 2501, 0, 201),
+    );
+    assert!(result.is_err(), "Should fail - claim exceeds the first milestone's share");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that the vested amount stays flat between milestones.
+/// At epoch 250 two milestones have been reached, so 5000 is vested where a linear schedule would vest 7500.
+#[test]
+fn test_vested_amount_flat_between_milestones() {
+    let result = verify_claim(
+        Some(quarterly_milestones()),
+        250,
+        create_vesting_data(10000, 2500, 0, 200),
+        create_vesting_data(10000, 5000, 0, 201),
+    );
+    assert!(result.is_ok(), "Should succeed - two milestones reached, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_claim(
+        Some(quarterly_milestones()),
+        250,
+        create_vesting_data(10000, 2500, 0, 200),
+        create_vesting_data(10000, 5001, 0, 201),
+    );
+    assert!(result.is_err(), "Should fail - third milestone not reached yet");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that the cliff still gates milestones that fall before it.
+/// Half the total unlocks at epoch 110, but nothing is vested until the cliff at epoch 120.
+#[test]
+fn test_milestones_before_cliff_release_at_cliff() {
+    let schedule = create_milestone_schedule(&[(110, 5000), (300, 5000)]);
+
+    let result = verify_claim(
+        Some(schedule.clone()),
+        119,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 1, 0, 201),
+    );
+    assert!(result.is_err(), "Should fail - cliff not reached yet");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+
+    let result = verify_claim(
+        Some(schedule),
+        120,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 5000, 0, 201),
+    );
+    assert!(result.is_ok(), "Should succeed - cliff releases the earlier milestone, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that milestone shares are rounded down on the cumulative share.
+/// Of 999 tokens a third vests 332, rounded down from 332.97.
+#[test]
+fn test_milestone_share_rounded_down() {
+    let schedule = create_milestone_schedule(&[(150, 3333), (200, 3333), (250, 3334)]);
+
+    let result = verify_claim(
+        Some(schedule.clone()),
+        150,
+        create_vesting_data(999, 0, 0, 200),
+        create_vesting_data(999, 332, 0, 201),
+    );
+    assert!(result.is_ok(), "Should succeed - rounded down share, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_claim(
+        Some(schedule),
+        150,
+        create_vesting_data(999, 0, 0, 200),
+        create_vesting_data(999, 333, 0, 201),
+    );
+    assert!(result.is_err(), "Should fail - claim exceeds the rounded down share");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that the linear schedule type can be selected explicitly and matches the default.
+/// At epoch 200 half of the 100 to 300 schedule has vested.
+#[test]
+fn test_explicit_linear_schedule() {
+    let result = verify_claim(
+        Some(vec![SCHEDULE_LINEAR]),
+        200,
+        create_vesting_data(10000, 0, 0, 200),
+        create_vesting_data(10000, 5000, 0, 201),
+    );
+    assert!(result.is_ok(), "Should succeed - explicit linear schedule, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that tranches keep vesting linearly under a milestone base schedule.
+/// At epoch 250 the base has vested 5000 and a 4000 tranche over epochs 200 to 400 has vested 1000.
+#[test]
+fn test_tranches_stay_linear_with_milestones() {
+    let result = verify_claim(
+        Some(quarterly_milestones()),
+        250,
+        create_vesting_data_with_tranches(14000, 0, 0, 200, &[(4000, 0, 200, 400, 250)]),
+        create_vesting_data_with_tranches(14000, 6000, 0, 201, &[(4000, 1000, 200, 400, 250)]),
+    );
+    assert!(result.is_ok(), "Should succeed - milestone base plus linear tranche, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that malformed schedule values are rejected as invalid arguments.
+/// Milestones must lie within the schedule in strictly increasing order, with non-zero shares totalling 10000.
+#[test]
+fn test_invalid_schedule_values_rejected() {
+    let nine_milestones: Vec<(u64, u16)> = (0..9).map(|index| (110 + index * 10, if index == 0 { 2000 } else { 1000 })).collect();
+    let mut truncated = quarterly_milestones();
+    truncated.pop();
+    let mut linear_with_fields = vec![SCHEDULE_LINEAR];
+    linear_with_fields.extend_from_slice(&quarterly_milestones()[1..]);
+
+    let cases = [
+        ("empty value", vec![]),
+        ("unknown schedule type", vec![2u8]),
+        ("linear with fields", linear_with_fields),
+        ("no milestones", vec![SCHEDULE_MILESTONES]),
+        ("truncated record", truncated),
+        ("shares below 10000", create_milestone_schedule(&[(150, 2500), (300, 7499)])),
+        ("shares above 10000", create_milestone_schedule(&[(150, 2500), (300, 7501)])),
+        ("zero share", create_milestone_schedule(&[(150, 0), (300, 10000)])),
+        ("decreasing epochs", create_milestone_schedule(&[(200, 5000), (150, 5000)])),
+        ("repeated epoch", create_milestone_schedule(&[(200, 5000), (200, 5000)])),
+        ("before start epoch", create_milestone_schedule(&[(99, 5000), (300, 5000)])),
+        ("after end epoch", create_milestone_schedule(&[(150, 5000), (301, 5000)])),
+        ("too many milestones", create_milestone_schedule(&nine_milestones)),
+    ];
+
+    for (label, schedule) in cases {
+        let result = verify_claim(
+            Some(schedule),
+            200,
+            create_vesting_data(10000, 0, 0, 200),
+            create_vesting_data(10000, 0, 0, 201),
+        );
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS), "Case {} should fail with invalid args", label);
+    }
+}
\ No newline at end of file
@@ -1,11 +1,55 @@
+pub mod accelerated_termination;
+pub mod allocator_benchmark;
 pub mod args_validation;
+pub mod auth_lock_patterns;
 pub mod authorization;
 pub mod batching;
 pub mod beneficiary_claims;
+pub mod beneficiary_recovery;
+pub mod beneficiary_transfer;
+pub mod block_advance;
+pub mod block_schedule;
+pub mod bps_denomination;
+pub mod claim_boundaries;
+pub mod claim_receipt;
+pub mod claim_unit;
+pub mod cliff_accrual;
+pub mod co_beneficiaries;
+pub mod co_grantors;
+pub mod contract_spec;
+pub mod creator_buyout;
+pub mod creator_migration;
+pub mod creator_signers;
 pub mod creator_termination;
+pub mod cycle_budgets;
+pub mod data_extension;
+pub mod dust_sweep;
 pub mod edge_cases;
+pub mod end_bonus;
+pub mod epoch_fraction;
 pub mod error_paths;
+pub mod error_table;
+pub mod events;
+pub mod grant_metadata;
+pub mod granularity;
+pub mod header_manipulation;
+pub mod header_quorum;
+pub mod header_selection;
 pub mod helpers;
 pub mod invalid_cell_creation;
+pub mod keeper_tips;
+pub mod legacy_compatibility;
+pub mod merkle_beneficiaries;
+pub mod milestone_schedule;
+pub mod payout_redirection;
+pub mod performance_multiplier;
+pub mod reorg_simulation;
+pub mod scenario_replay;
 pub mod security;
-pub mod state_invariants;
\ No newline at end of file
+pub mod since_timing;
+pub mod simultaneous_claims;
+pub mod state_invariants;
+pub mod tranches;
+pub mod udt_vesting;
+pub mod update_interval;
+pub mod xudt_vesting;
\ No newline at end of file
@@ -0,0 +1,455 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+/// Builds and verifies a partial beneficiary claim of 5000 paid to an exchange lock.
+/// The vesting witness is built from the exchange lock hash by the given closure.
+fn verify_redirected_claim(
+    build_witness: impl Fn([u8; 32]) -> Bytes,
+    exchange_payout: u64,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+    let (exchange_lock, exchange_hash) = create_always_success_lock_with_args(&mut context, vec![3u8]);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock)
+            .build(),
+        Bytes::new(),
+    );
+
+    // At epoch 200: vested = (200-100)/(300-100) * 10000 = 5000.
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(5161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 5000, 0, 201).pack())
+        .output(CellOutput::new_builder()
+            .capacity(exchange_payout.pack())
+            .lock(exchange_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(header_hash)
+        // The vesting input is at index 0, so its witness is the first one.
+        .witness(build_witness(exchange_hash).pack())
+        .build();
+
+    let tx = context.complete_tx(tx);
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that a beneficiary claim can be redirected to an alternate payout lock.
+/// The exchange lock receives exactly the claimed amount.
+#[test]
+fn test_redirected_claim_paid_to_payout_lock() {
+    let result = verify_redirected_claim(create_payout_witness, 5000);
+    assert!(result.is_ok(), "Should succeed - claim redirected to the exchange lock, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a redirected claim paying less than the claimed amount is rejected.
+/// The contract still verifies the amount regardless of where it is sent.
+#[test]
+fn test_redirected_claim_underpaid_rejected() {
+    let result = verify_redirected_claim(create_payout_witness, 4999);
+    assert!(result.is_err(), "Should fail - payout lock receives less than the claimed amount");
+    assert_eq!(extract_error_code(&result), Some(46)); // Error::InsufficientPayout
+}
+
+/// Tests that a redirected claim to a lock with no outputs is rejected.
+/// Naming a payout lock that receives nothing cannot bypass the amount check.
+#[test]
+fn test_redirected_claim_to_absent_lock_rejected() {
+    let result = verify_redirected_claim(|_| create_payout_witness(create_dummy_lock_hash(9)), 5000);
+    assert!(result.is_err(), "Should fail - no outputs use the payout lock");
+    assert_eq!(extract_error_code(&result), Some(46)); // Error::InsufficientPayout
+}
+
+/// Tests that an empty vesting witness is treated as no redirection.
+/// Wallets commonly place empty witnesses at non-signing input positions.
+#[test]
+fn test_empty_witness_means_no_redirection() {
+    let result = verify_redirected_claim(|_| Bytes::new(), 5000);
+    assert!(result.is_ok(), "Should succeed - empty witness carries no redirection, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a payout lock field that is not a 32-byte lock hash is rejected.
+/// Validates strict witness format parsing.
+#[test]
+fn test_malformed_payout_witness_rejected() {
+    let witness = WitnessArgs::new_builder()
+        .lock(Some(Bytes::from(vec![3u8; 20])).pack())
+        .build()
+        .as_bytes();
+
+    let result = verify_redirected_claim(|_| witness.clone(), 5000);
+    assert!(result.is_err(), "Should fail - payout lock field must be 32 bytes");
+    assert_eq!(extract_error_code(&result), Some(11)); // Error::InvalidWitness
+}
+
+/// Tests that a witness which is not a WitnessArgs structure is rejected.
+/// Validates that garbage witness bytes cannot be mistaken for no redirection.
+#[test]
+fn test_non_witness_args_bytes_rejected() {
+    let result = verify_redirected_claim(|_| Bytes::from(vec![0xffu8; 7]), 5000);
+    assert!(result.is_err(), "Should fail - witness is not a WitnessArgs structure");
+    assert_eq!(extract_error_code(&result), Some(11)); // Error::InvalidWitness
+}
+
+/// Tests that payout redirection is rejected for anonymous block updates.
+/// Only beneficiary claims may carry a payout lock.
+#[test]
+fn test_redirection_rejected_for_anonymous_update() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let args = create_vesting_args(
+        create_dummy_lock_hash(1),
+        create_dummy_lock_hash(2),
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 201).pack())
+        .witness(create_payout_witness(create_dummy_lock_hash(9)).pack())
+        .header_dep(header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - anonymous updates cannot redirect payouts");
+    assert_eq!(extract_error_code(&result), Some(11)); // Error::InvalidWitness
+}
+
+/// Tests that a redirected claim can be paid straight into a NervosDAO deposit cell.
+/// The deposit is owned by the payout lock and carries a DAO-style type script with 8 zero bytes of data.
+#[test]
+fn test_redirected_claim_into_dao_deposit() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+    // Stand-in for the NervosDAO type script, which is not deployed in the test context.
+    let (dao_type, _dao_type_hash) = create_always_success_lock_with_args(&mut context, vec![0xdau8]);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    // The beneficiary redirects the claim to its own lock and deposits it into the DAO along with its own funds.
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(5161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 5000, 0, 201).pack())
+        .output(CellOutput::new_builder()
+            .capacity(6100005000u64.pack())
+            .lock(beneficiary_lock)
+            .type_(Some(dao_type).pack())
+            .build())
+        .output_data(Bytes::from(vec![0u8; 8]).pack())
+        .header_dep(header_hash)
+        .witness(create_payout_witness(beneficiary_hash).pack())
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_ok(), "Should succeed - claim paid into a DAO deposit, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that change returned to a payout lock that also funds the transaction does not count as payment.
+/// The exchange lock puts in a cell of its own and only gets it back, so it nets nothing from the claim.
+#[test]
+fn test_redirected_claim_payout_lock_change_not_counted() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+    let (exchange_lock, exchange_hash) = create_always_success_lock_with_args(&mut context, vec![3u8]);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let exchange_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(exchange_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    // The released 5000 goes back to the beneficiary; the exchange only receives its own cell back.
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(exchange_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(5161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 5000, 0, 201).pack())
+        .output(CellOutput::new_builder()
+            .capacity(6100005000u64.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .output(CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(exchange_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(header_hash)
+        .witness(create_payout_witness(exchange_hash).pack())
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - the exchange lock only receives its own change");
+    assert_eq!(extract_error_code(&result), Some(46)); // Error::InsufficientPayout
+}
+
+/// Builds and verifies a partial beneficiary claim whose payout output carries the given data.
+/// When typed is set the payout output gets an always-success type script.
+fn verify_claim_with_payout_data(
+    payout_data: Bytes,
+    typed: bool,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+    let (payout_type, _payout_type_hash) = create_always_success_lock_with_args(&mut context, vec![0x75u8]);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let payout_output = CellOutput::new_builder()
+        .capacity(5000u64.pack())
+        .lock(beneficiary_lock)
+        .type_(if typed { Some(payout_type) } else { None }.pack())
+        .build();
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(5161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 5000, 0, 201).pack())
+        .output(payout_output)
+        .output_data(payout_data.pack())
+        .header_dep(header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that an untyped payout output carrying data is rejected.
+/// Prevents state-carrying cells from being smuggled out as claims.
+#[test]
+fn test_untyped_payout_output_with_data_rejected() {
+    let result = verify_claim_with_payout_data(create_vesting_data(10000, 0, 0, 0), false);
+    assert!(result.is_err(), "Should fail - untyped payout output carries data");
+    assert_eq!(extract_error_code(&result), Some(50)); // Error::PayoutOutputHasData
+}
+
+/// Tests that a typed payout output may carry a UDT-sized amount.
+/// The type script is responsible for validating that data.
+#[test]
+fn test_typed_payout_output_with_udt_amount_accepted() {
+    let result = verify_claim_with_payout_data(Bytes::from(5000u128.to_le_bytes().to_vec()), true);
+    assert!(result.is_ok(), "Should succeed - typed payout output carries a UDT amount, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a typed payout output with more than a UDT amount of data is rejected.
+/// Validates the upper bound on typed payout data.
+#[test]
+fn test_typed_payout_output_with_excess_data_rejected() {
+    let result = verify_claim_with_payout_data(Bytes::from(vec![0u8; 17]), true);
+    assert!(result.is_err(), "Should fail - typed payout output carries more than a UDT amount");
+    assert_eq!(extract_error_code(&result), Some(50)); // Error::PayoutOutputHasData
+}
+
+/// Tests that creator termination outputs must also carry no data.
+/// The creator's payout is checked like a redirected claim's.
+#[test]
+fn test_creator_payout_output_with_data_rejected() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (_beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let creator_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(creator_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(creator_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(5161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 5000, 201).pack())
+        .output(CellOutput::new_builder()
+            .capacity(5000u64.pack())
+            .lock(creator_lock)
+            .build())
+        .output_data(Bytes::from(vec![1u8]).pack())
+        .header_dep(header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - creator payout output carries data");
+    assert_eq!(extract_error_code(&result), Some(50)); // Error::PayoutOutputHasData
+}
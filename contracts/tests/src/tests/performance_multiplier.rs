@@ -0,0 +1,182 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const OPTION_PERFORMANCE_ORACLE: u8 = 0x0b;
+const ROTATION_SET_PERFORMANCE_MULTIPLIER: u8 = 7;
+
+/// Builds a performance oracle option record.
+fn oracle_option(oracle_hash: [u8; 32], min_bps: u16, max_bps: u16, current_bps: u16) -> (u8, Vec<u8>) {
+    let mut value = oracle_hash.to_vec();
+    value.extend_from_slice(&min_bps.to_le_bytes());
+    value.extend_from_slice(&max_bps.to_le_bytes());
+    value.extend_from_slice(&current_bps.to_le_bytes());
+    (OPTION_PERFORMANCE_ORACLE, value)
+}
+
+/// Builds and verifies a beneficiary claim at epoch 200 under the given multiplier.
+/// Without a multiplier, (200-100)/(300-100) * 10000 = 5000 would be vested.
+fn verify_claim_with_multiplier(
+    current_bps: u16,
+    claimed: u64,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(creator_hash, beneficiary_hash, 100, 300, 120),
+        &[oracle_option(create_dummy_lock_hash(7), 0, 20000, current_bps)],
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity((10161u64 - claimed).pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, claimed, 0, 201).pack())
+        .output(CellOutput::new_builder()
+            .capacity(claimed.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Builds and verifies a multiplier update from 10000 bps under bounds 5000-15000.
+/// The output oracle record carries the given bounds and multiplier.
+fn verify_set_multiplier(
+    oracle_signs: bool,
+    output_max_bps: u16,
+    output_current_bps: u16,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+    let (oracle_lock, oracle_hash) = create_always_success_lock_with_args(&mut context, vec![7]);
+
+    let input_args = create_vesting_args_with_options(
+        create_vesting_args(creator_hash, beneficiary_hash, 100, 300, 120),
+        &[oracle_option(oracle_hash, 5000, 15000, 10000)],
+    );
+    let output_args = create_vesting_args_with_options(
+        create_vesting_args(creator_hash, beneficiary_hash, 100, 300, 120),
+        &[oracle_option(oracle_hash, 5000, output_max_bps, output_current_bps)],
+    );
+
+    let input_lock_script = context.build_script(&out_point, input_args).expect("script");
+    let output_lock_script = context.build_script(&out_point, output_args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(input_lock_script)
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let signer_lock = if oracle_signs { oracle_lock } else { beneficiary_lock };
+    let signer_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(1000u64.pack())
+            .lock(signer_lock)
+            .build(),
+        Bytes::new(),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(signer_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(output_lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 201).pack())
+        .header_dep(header_hash)
+        .witness(create_rotation_witness(ROTATION_SET_PERFORMANCE_MULTIPLIER, 0).pack())
+        .build();
+    let tx = context.complete_tx(tx);
+
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that a multiplier below 100% scales the vested amount down.
+/// At 5000 bps: vested = 5000 * 0.5 = 2500.
+#[test]
+fn test_reduced_multiplier_claim() {
+    let result = verify_claim_with_multiplier(5000, 2500);
+    assert!(result.is_ok(), "Should succeed - claim matches the scaled amount, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_claim_with_multiplier(5000, 2501);
+    assert!(result.is_err(), "Should fail - claim exceeds the scaled amount");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that a multiplier above 100% scales the vested amount up.
+/// At 15000 bps: vested = 5000 * 1.5 = 7500.
+#[test]
+fn test_bonus_multiplier_claim() {
+    let result = verify_claim_with_multiplier(15000, 7500);
+    assert!(result.is_ok(), "Should succeed - claim matches the bonus amount, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_claim_with_multiplier(15000, 7501);
+    assert!(result.is_err(), "Should fail - claim exceeds the bonus amount");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that the oracle can move the multiplier within the creator's bounds.
+/// Only the current multiplier changes in the relocked args.
+#[test]
+fn test_oracle_sets_multiplier() {
+    let result = verify_set_multiplier(true, 15000, 12000);
+    assert!(result.is_ok(), "Should succeed - multiplier within bounds, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the oracle cannot widen the bounds to exceed the creator's maximum.
+/// The bounds are fixed when the cell is created.
+#[test]
+fn test_oracle_cannot_exceed_bounds() {
+    let result = verify_set_multiplier(true, 30000, 25000);
+    assert!(result.is_err(), "Should fail - multiplier above the creator's maximum");
+    assert_eq!(extract_error_code(&result), Some(56)); // Error::InvalidRotation
+}
+
+/// Tests that only the oracle can change the multiplier.
+/// The beneficiary must not be able to raise their own payout.
+#[test]
+fn test_beneficiary_cannot_set_multiplier() {
+    let result = verify_set_multiplier(false, 15000, 15000);
+    assert!(result.is_err(), "Should fail - beneficiary set the multiplier");
+    assert_eq!(extract_error_code(&result), Some(25)); // Error::Unauthorized
+}
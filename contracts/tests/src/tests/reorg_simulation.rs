@@ -0,0 +1,232 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+/// Tests that a claim prepared against a cell consumed by a concurrent update is rejected when replayed.
+/// The continuation cell tracks a newer block, so the claim's original header becomes stale.
+#[test]
+fn test_claim_against_updated_cell_with_old_header_rejected() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    // Header the beneficiary selected when discovering the cell.
+    let claim_header_hash = setup_header_with_block_and_epoch(&mut context, 250, 250);
+    // Header used by the concurrent anonymous update.
+    let update_header_hash = setup_header_with_block_and_epoch(&mut context, 300, 260);
+
+    let original_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    // The concurrent update consumes the original cell first.
+    let update_tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(original_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 300).pack())
+        .header_dep(update_header_hash)
+        .build();
+    let update_tx = context.complete_tx(update_tx);
+
+    let result = context.verify_tx(&update_tx, MAX_CYCLES);
+    assert!(result.is_ok(), "Should succeed - concurrent update, got error code: {:?}", extract_error_code(&result));
+
+    // Materialize the continuation cell produced by the update.
+    let continuation_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 300),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    // The beneficiary re-targets the continuation cell but keeps the old header.
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(continuation_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(2661u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 7500, 0, 250).pack())
+        .output(CellOutput::new_builder()
+            .capacity(7500u64.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(claim_header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - header is stale relative to the updated cell");
+    assert_eq!(extract_error_code(&result), Some(24)); // Error::StaleHeader
+}
+
+/// Tests that re-discovering the continuation cell and selecting a fresh header succeeds.
+/// This is the recovery path a client takes after losing the race to a concurrent update.
+#[test]
+fn test_claim_after_rediscovery_with_fresh_header() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    // Fresh header selected after re-discovery (block 301 > continuation's 300).
+    let fresh_header_hash = setup_header_with_block_and_epoch(&mut context, 301, 250);
+
+    // Continuation cell left behind by the concurrent update.
+    let continuation_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 300),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    // At epoch 250: vested = (250-100)/(300-100) * 10000 = 7500.
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(continuation_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(2661u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 7500, 0, 301).pack())
+        .output(CellOutput::new_builder()
+            .capacity(7500u64.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(fresh_header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_ok(), "Should succeed - claim rebuilt against the continuation cell, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the monotonic block rule holds after a reorg replaces an update with a lower one.
+/// The surviving cell's highest_block_seen becomes the new floor for all later transactions.
+#[test]
+fn test_monotonic_rule_after_reorged_update() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let creator_hash = create_dummy_lock_hash(1);
+    let beneficiary_hash = create_dummy_lock_hash(2);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    // After the reorg, the competing update recorded block 260 instead of 300.
+    let surviving_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 260),
+    );
+
+    // Replaying with a header below the surviving floor is stale.
+    let below_floor_header_hash = setup_header_with_block_and_epoch(&mut context, 255, 255);
+    let stale_tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(surviving_out_point.clone()).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 255).pack())
+        .header_dep(below_floor_header_hash)
+        .build();
+    let stale_tx = context.complete_tx(stale_tx);
+
+    let result = context.verify_tx(&stale_tx, MAX_CYCLES);
+    assert_eq!(extract_error_code(&result), Some(24), "Header below the surviving floor must be stale");
+
+    // A fresh header cannot be used to write a block below the surviving floor.
+    let orphaned_header_hash = setup_header_with_block_and_epoch(&mut context, 300, 260);
+    let decrease_tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(surviving_out_point.clone()).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 255).pack())
+        .header_dep(orphaned_header_hash.clone())
+        .build();
+    let decrease_tx = context.complete_tx(decrease_tx);
+
+    let result = context.verify_tx(&decrease_tx, MAX_CYCLES);
+    assert_eq!(extract_error_code(&result), Some(26), "Output below the surviving floor must be rejected"); // Error::BlockNumberDecrease
+
+    // Re-applying the orphaned update on top of the surviving cell moves forward normally.
+    let replay_tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(surviving_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 0, 0, 300).pack())
+        .header_dep(orphaned_header_hash)
+        .build();
+    let replay_tx = context.complete_tx(replay_tx);
+
+    let result = context.verify_tx(&replay_tx, MAX_CYCLES);
+    assert!(result.is_ok(), "Should succeed - replayed update advances the floor, got error code: {:?}", extract_error_code(&result));
+}
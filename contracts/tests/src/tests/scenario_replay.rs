@@ -0,0 +1,101 @@
+use super::helpers::*;
+use crate::{debugger_command, dump_mock_tx, Loader};
+use ckb_testtool::ckb_types::{bytes::Bytes, core::{TransactionBuilder, TransactionView}, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+/// Builds a partial beneficiary claim scenario in the given context.
+/// Every step is deterministic when the context was created with deterministic_context.
+fn build_partial_claim_scenario(context: &mut Context) -> TransactionView {
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(5161u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, 5000, 0, 201).pack())
+        .output(CellOutput::new_builder()
+            .capacity(5000u64.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(header_hash)
+        .build();
+    context.complete_tx(tx)
+}
+
+/// Tests that scenarios built in separate deterministic contexts are byte-for-byte identical.
+/// Identical transaction hashes and descriptions make failures replayable across machines.
+#[test]
+fn test_deterministic_context_reproducible() {
+    let mut first_context = deterministic_context();
+    let first_tx = build_partial_claim_scenario(&mut first_context);
+
+    let mut second_context = deterministic_context();
+    let second_tx = build_partial_claim_scenario(&mut second_context);
+
+    assert_eq!(first_tx.hash(), second_tx.hash(), "Deterministic contexts should produce identical transactions");
+    assert_eq!(
+        describe_scenario(&first_context, &first_tx),
+        describe_scenario(&second_context, &second_tx),
+        "Deterministic contexts should produce identical scenario descriptions"
+    );
+}
+
+/// Tests that a scenario verified through verify_scenario behaves like a direct verification.
+/// The helper only adds the scenario description to failure output.
+#[test]
+fn test_verify_scenario_matches_verify_tx() {
+    let mut context = deterministic_context();
+    let tx = build_partial_claim_scenario(&mut context);
+
+    let result = verify_scenario(&context, &tx, MAX_CYCLES);
+    assert!(result.is_ok(), "Should succeed - deterministic partial claim, got error code: {:?}", extract_error_code(&result));
+    assert_eq!(result.ok(), context.verify_tx(&tx, MAX_CYCLES).ok(), "Cycle counts should match direct verification");
+}
+
+/// Tests that a dumped scenario is written as a mock transaction the debugger command points at.
+/// Failing tests name this command in their assertion message instead of printing it.
+#[test]
+fn test_dump_mock_tx_for_debugger() {
+    let mut context = deterministic_context();
+    let tx = build_partial_claim_scenario(&mut context);
+
+    let tx_file = dump_mock_tx(&context, &tx);
+    assert!(tx_file.exists(), "Mock transaction should be written to {:?}", tx_file);
+
+    let command = debugger_command(&tx_file);
+    assert!(command.contains(&tx_file.display().to_string()), "Debugger command should name the dump: {}", command);
+}
@@ -0,0 +1,148 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::{TransactionBuilder, TransactionView}, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+/// Builds a transaction that moves the vesting cell to the given claimed counters at epoch 200.
+/// The signer flags add creator and beneficiary authorization inputs, and each claimed delta is paid out.
+fn build_claim_transition(
+    context: &mut Context,
+    creator_signs: bool,
+    beneficiary_signs: bool,
+    beneficiary_claimed: u64,
+    creator_claimed: u64,
+) -> TransactionView {
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    // At epoch 200 half of the grant is vested: 5000 vested, 5000 unvested.
+    let header_hash = setup_header_with_block_and_epoch(context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, 200),
+    );
+
+    let mut tx_builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build());
+
+    if creator_signs {
+        let creator_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(6100000000u64.pack())
+                .lock(creator_lock.clone())
+                .build(),
+            Bytes::new(),
+        );
+        tx_builder = tx_builder.input(CellInput::new_builder().previous_output(creator_input_out_point).build());
+    }
+
+    if beneficiary_signs {
+        let beneficiary_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(6100000000u64.pack())
+                .lock(beneficiary_lock.clone())
+                .build(),
+            Bytes::new(),
+        );
+        tx_builder = tx_builder.input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build());
+    }
+
+    let tx = tx_builder
+        .output(CellOutput::new_builder()
+            .capacity((10161u64 - beneficiary_claimed - creator_claimed).pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_vesting_data(10000, beneficiary_claimed, creator_claimed, 201).pack())
+        .output(CellOutput::new_builder()
+            .capacity(beneficiary_claimed.pack())
+            .lock(beneficiary_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .output(CellOutput::new_builder()
+            .capacity(creator_claimed.pack())
+            .lock(creator_lock)
+            .build())
+        .output_data(Bytes::new().pack())
+        .header_dep(header_hash)
+        .build();
+    context.complete_tx(tx)
+}
+
+/// Tests that a creator-signed transition cannot also advance beneficiary_claimed.
+/// Claiming the vested half and reclaiming the unvested half at once fails with the dedicated error.
+#[test]
+fn test_simultaneous_claim_and_termination_by_creator() {
+    let mut context = Context::default();
+    let tx = build_claim_transition(&mut context, true, false, 5000, 5000);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - claim and termination in one transition");
+    assert_eq!(extract_error_code(&result), Some(58), "Should fail with SimultaneousClaimAndTermination error");
+}
+
+/// Tests that a beneficiary-signed transition cannot also advance creator_claimed.
+/// The rejection does not depend on which authorization branch the signer selects.
+#[test]
+fn test_simultaneous_claim_and_termination_by_beneficiary() {
+    let mut context = Context::default();
+    let tx = build_claim_transition(&mut context, false, true, 5000, 5000);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - claim and termination in one transition");
+    assert_eq!(extract_error_code(&result), Some(58), "Should fail with SimultaneousClaimAndTermination error");
+}
+
+/// Tests that both parties signing together still cannot claim and terminate in one transition.
+/// Joint authorization does not merge the two operations; they must be separate transactions.
+#[test]
+fn test_simultaneous_claim_and_termination_by_both_parties() {
+    let mut context = Context::default();
+    let tx = build_claim_transition(&mut context, true, true, 5000, 5000);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - claim and termination in one transition");
+    assert_eq!(extract_error_code(&result), Some(58), "Should fail with SimultaneousClaimAndTermination error");
+}
+
+/// Tests that a partial claim alongside a partial reclaim is rejected as well.
+/// Any increase of both counters is enough; the amounts themselves are not considered.
+#[test]
+fn test_simultaneous_partial_claim_and_termination() {
+    let mut context = Context::default();
+    let tx = build_claim_transition(&mut context, true, false, 1000, 1000);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - claim and termination in one transition");
+    assert_eq!(extract_error_code(&result), Some(58), "Should fail with SimultaneousClaimAndTermination error");
+}
+
+/// Tests that the same transaction shape succeeds when only one counter increases.
+/// Confirms the builder is otherwise valid, so the failures above come from the combined change.
+#[test]
+fn test_single_counter_transitions_still_succeed() {
+    let mut context = Context::default();
+    let claim_tx = build_claim_transition(&mut context, false, true, 5000, 0);
+    let result = context.verify_tx(&claim_tx, MAX_CYCLES);
+    assert!(result.is_ok(), "Should succeed - beneficiary claim only, got error code: {:?}", extract_error_code(&result));
+
+    let mut context = Context::default();
+    let termination_tx = build_claim_transition(&mut context, true, false, 0, 5000);
+    let result = context.verify_tx(&termination_tx, MAX_CYCLES);
+    assert!(result.is_ok(), "Should succeed - creator termination only, got error code: {:?}", extract_error_code(&result));
+}
\ No newline at end of file
@@ -0,0 +1,265 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const OPTION_KEEPER_TIP: u8 = 0x03;
+const OPTION_MIN_HEADER_DEPS: u8 = 0x05;
+const OPTION_SCHEDULE_UNIT: u8 = 0x17;
+const OPTION_TIME_SOURCE: u8 = 0x18;
+const TIME_SOURCE_HEADER_DEPS: u8 = 0;
+const TIME_SOURCE_INPUT_SINCE: u8 = 1;
+
+// Since flags of an absolute epoch, and the fractions per epoch in a schedule point
+const SINCE_ABSOLUTE_EPOCH: u64 = 0x20 << 56;
+const SCHEDULE_POINT_SCALE: u64 = 1 << 16;
+
+/// A transition of a since-timed cell vesting 10000 over epochs 100 to 300 with a cliff at 120.
+/// recorded_point is the schedule point the previous transition stored in highest_block_seen.
+struct SinceTransition {
+    options: Vec<(u8, Vec<u8>)>,
+    since: u64,
+    recorded_point: u64,
+    beneficiary_signs: bool,
+    creator_signs: bool,
+    output_data: Option<Bytes>,
+}
+
+impl Default for SinceTransition {
+    fn default() -> Self {
+        SinceTransition {
+            options: vec![(OPTION_TIME_SOURCE, vec![TIME_SOURCE_INPUT_SINCE])],
+            since: SINCE_ABSOLUTE_EPOCH | 200,
+            recorded_point: 0,
+            beneficiary_signs: true,
+            creator_signs: false,
+            output_data: Some(create_vesting_data(10000, 5000, 0, 200 * SCHEDULE_POINT_SCALE)),
+        }
+    }
+}
+
+/// Builds and verifies a transition of a since-timed cell without any header deps.
+/// The since is set on the vesting input, and output_data None consumes the cell.
+fn verify_since(transition: SinceTransition) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            creator_hash,
+            beneficiary_hash,
+            100, // start_epoch
+            300, // end_epoch
+            120, // cliff_epoch
+        ),
+        &transition.options,
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_vesting_data(10000, 0, 0, transition.recorded_point),
+    );
+
+    let mut builder = TransactionBuilder::default().input(
+        CellInput::new_builder()
+            .previous_output(vesting_input_out_point)
+            .since(transition.since.pack())
+            .build(),
+    );
+
+    for (signs, signer_lock) in [(transition.beneficiary_signs, beneficiary_lock), (transition.creator_signs, creator_lock)] {
+        if signs {
+            let signer_input_out_point = context.create_cell(
+                CellOutput::new_builder()
+                    .capacity(6100000000u64.pack())
+                    .lock(signer_lock)
+                    .build(),
+                Bytes::new(),
+            );
+            builder = builder.input(CellInput::new_builder().previous_output(signer_input_out_point).build());
+        }
+    }
+
+    if let Some(output_data) = transition.output_data {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(10161u64.pack())
+                .lock(lock_script)
+                .build())
+            .output_data(output_data.pack());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that a claim takes the current epoch from the input's since, without header deps.
+/// At epoch 200 half of the 100 to 300 schedule has vested, so 5000 can be claimed and 5001 cannot.
+#[test]
+fn test_since_claim_without_headers() {
+    let result = verify_since(SinceTransition::default());
+    assert!(result.is_ok(), "Should succeed - since supplies epoch 200, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_since(SinceTransition {
+        output_data: Some(create_vesting_data(10000, 5001, 0, 200 * SCHEDULE_POINT_SCALE)),
+        ..SinceTransition::default()
+    });
+    assert!(result.is_err(), "Should fail - claim exceeds the amount vested at the since epoch");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that the since epoch fraction counts towards vesting like a header epoch.
+/// Halfway through epoch 200, 5025 has vested.
+#[test]
+fn test_since_epoch_fraction() {
+    let half_point = 200 * SCHEDULE_POINT_SCALE + SCHEDULE_POINT_SCALE / 2;
+    let result = verify_since(SinceTransition {
+        since: SINCE_ABSOLUTE_EPOCH | 200 | (900 << 24) | (1800 << 40),
+        output_data: Some(create_vesting_data(10000, 5025, 0, half_point)),
+        ..SinceTransition::default()
+    });
+    assert!(result.is_ok(), "Should succeed - since halfway through epoch 200, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a since other than an absolute epoch is rejected.
+/// A missing since, an absolute block number and a relative epoch all leave the current epoch unknown.
+#[test]
+fn test_since_must_be_absolute_epoch() {
+    for since in [0u64, 200, (0xa0 << 56) | 200] {
+        let result = verify_since(SinceTransition { since, ..SinceTransition::default() });
+        assert!(result.is_err(), "Should fail - since {:#x} is not an absolute epoch", since);
+        assert_eq!(extract_error_code(&result), Some(81)); // Error::InvalidSince
+    }
+}
+
+/// Tests that a since before the recorded point is rejected.
+/// After a transition at epoch 250, a termination cannot pretend it is still epoch 200.
+#[test]
+fn test_stale_since_rejected() {
+    let result = verify_since(SinceTransition { recorded_point: 250 * SCHEDULE_POINT_SCALE, ..SinceTransition::default() });
+    assert!(result.is_err(), "Should fail - since is earlier than the recorded point");
+    assert_eq!(extract_error_code(&result), Some(82)); // Error::StaleSince
+
+    let result = verify_since(SinceTransition { recorded_point: 200 * SCHEDULE_POINT_SCALE, ..SinceTransition::default() });
+    assert!(result.is_ok(), "Should succeed - repeating the recorded point, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the continuation cell records the since point in highest_block_seen.
+/// Recording anything else would let the next transition use a stale since.
+#[test]
+fn test_since_point_recorded() {
+    let result = verify_since(SinceTransition {
+        output_data: Some(create_vesting_data(10000, 5000, 0, 201)),
+        ..SinceTransition::default()
+    });
+    assert!(result.is_err(), "Should fail - output does not record the since point");
+    assert_eq!(extract_error_code(&result), Some(27)); // Error::BlockNumberMismatch
+}
+
+/// Tests that a creator termination reclaims the amount unvested at the since epoch.
+/// At epoch 200 the creator reclaims the unvested 5000.
+#[test]
+fn test_since_termination() {
+    let result = verify_since(SinceTransition {
+        beneficiary_signs: false,
+        creator_signs: true,
+        output_data: Some(create_vesting_data(10000, 0, 5000, 200 * SCHEDULE_POINT_SCALE)),
+        ..SinceTransition::default()
+    });
+    assert!(result.is_ok(), "Should succeed - termination at the since epoch, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that anyone can refresh the recorded point to the since of an anonymous update.
+/// The update may change nothing but highest_block_seen, which must hold the since point.
+#[test]
+fn test_since_refresh_update() {
+    let result = verify_since(SinceTransition {
+        beneficiary_signs: false,
+        output_data: Some(create_vesting_data(10000, 0, 0, 200 * SCHEDULE_POINT_SCALE)),
+        ..SinceTransition::default()
+    });
+    assert!(result.is_ok(), "Should succeed - anonymous refresh to the since point, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_since(SinceTransition {
+        beneficiary_signs: false,
+        output_data: Some(create_vesting_data(10000, 0, 0, 201)),
+        ..SinceTransition::default()
+    });
+    assert!(result.is_err(), "Should fail - refresh does not record the since point");
+    assert_eq!(extract_error_code(&result), Some(27)); // Error::BlockNumberMismatch
+
+    let result = verify_since(SinceTransition {
+        beneficiary_signs: false,
+        output_data: Some(create_vesting_data(10000, 1, 0, 200 * SCHEDULE_POINT_SCALE)),
+        ..SinceTransition::default()
+    });
+    assert!(result.is_err(), "Should fail - refresh changes the claimed amount");
+    assert_eq!(extract_error_code(&result), Some(17)); // Error::InvalidStateChange
+}
+
+/// Tests that a termination cannot be priced before the last refresh.
+/// After a refresh at epoch 250 the creator may reclaim only the 2500 unvested then, not the 5000 unvested at epoch 200.
+#[test]
+fn test_stale_since_termination_rejected() {
+    let refreshed_point = 250 * SCHEDULE_POINT_SCALE;
+    let result = verify_since(SinceTransition {
+        since: SINCE_ABSOLUTE_EPOCH | 250,
+        beneficiary_signs: false,
+        output_data: Some(create_vesting_data(10000, 0, 0, refreshed_point)),
+        ..SinceTransition::default()
+    });
+    assert!(result.is_ok(), "Should succeed - refresh at epoch 250, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_since(SinceTransition {
+        recorded_point: refreshed_point,
+        beneficiary_signs: false,
+        creator_signs: true,
+        output_data: Some(create_vesting_data(10000, 0, 5000, 200 * SCHEDULE_POINT_SCALE)),
+        ..SinceTransition::default()
+    });
+    assert!(result.is_err(), "Should fail - termination uses a since before the refresh");
+    assert_eq!(extract_error_code(&result), Some(82)); // Error::StaleSince
+
+    let result = verify_since(SinceTransition {
+        since: SINCE_ABSOLUTE_EPOCH | 250,
+        recorded_point: refreshed_point,
+        beneficiary_signs: false,
+        creator_signs: true,
+        output_data: Some(create_vesting_data(10000, 0, 2500, refreshed_point)),
+        ..SinceTransition::default()
+    });
+    assert!(result.is_ok(), "Should succeed - termination at the refreshed point, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that malformed time sources and options relying on header blocks are rejected.
+/// Explicitly selecting header deps keeps the default behavior and then requires headers.
+#[test]
+fn test_invalid_time_source_options_rejected() {
+    let since = (OPTION_TIME_SOURCE, vec![TIME_SOURCE_INPUT_SINCE]);
+    let cases = [
+        ("unknown source", vec![(OPTION_TIME_SOURCE, vec![2u8])]),
+        ("empty source", vec![(OPTION_TIME_SOURCE, vec![])]),
+        ("keeper tip", vec![since.clone(), (OPTION_KEEPER_TIP, 100u64.to_le_bytes().to_vec())]),
+        ("header quorum", vec![since.clone(), (OPTION_MIN_HEADER_DEPS, 2u64.to_le_bytes().to_vec())]),
+        ("block schedule", vec![since.clone(), (OPTION_SCHEDULE_UNIT, vec![1u8])]),
+    ];
+
+    for (label, options) in cases {
+        let result = verify_since(SinceTransition { options, ..SinceTransition::default() });
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS), "Case {} should fail with invalid args", label);
+    }
+
+    let result = verify_since(SinceTransition {
+        options: vec![(OPTION_TIME_SOURCE, vec![TIME_SOURCE_HEADER_DEPS])],
+        ..SinceTransition::default()
+    });
+    assert_eq!(extract_error_code(&result), Some(35), "Header deps are required"); // Error::NoHeaderDependencies
+}
\ No newline at end of file
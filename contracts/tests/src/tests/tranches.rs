@@ -0,0 +1,227 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+// Base schedule from the args: epochs 100 to 300 with a cliff at 120.
+// One refresher tranche of 4000 vests from epoch 200 to 400 with a cliff at 200.
+const TOTAL_AMOUNT: u64 = 10000;
+const REFRESHER: (u64, u64, u64, u64, u64) = (4000, 0, 200, 400, 200);
+
+/// Party that authorizes a tranche transition.
+#[derive(Clone, Copy)]
+enum Signer {
+    Beneficiary,
+    Creator,
+    Anonymous,
+}
+
+/// Builds and verifies a transition of a tranche-carrying vesting cell at the given epoch.
+/// When output_data is None the vesting cell is consumed without a continuation output.
+fn verify_tranche_transition(
+    signer: Signer,
+    input_data: Bytes,
+    epoch: u64,
+    output_data: Option<Bytes>,
+) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args(
+        creator_hash,
+        beneficiary_hash,
+        100, // start_epoch
+        300, // end_epoch
+        120, // cliff_epoch
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 501, epoch);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(10161u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        input_data,
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .header_dep(header_hash);
+
+    // Add the proxy authorization input for the signing party.
+    let auth_lock = match signer {
+        Signer::Beneficiary => Some(beneficiary_lock),
+        Signer::Creator => Some(creator_lock),
+        Signer::Anonymous => None,
+    };
+    if let Some(auth_lock) = auth_lock {
+        let auth_input_out_point = context.create_cell(
+            CellOutput::new_builder()
+                .capacity(6100000000u64.pack())
+                .lock(auth_lock)
+                .build(),
+            Bytes::new(),
+        );
+        builder = builder.input(CellInput::new_builder().previous_output(auth_input_out_point).build());
+    }
+
+    if let Some(output_data) = output_data {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(10161u64.pack())
+                .lock(lock_script)
+                .build())
+            .output_data(output_data.pack());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that a beneficiary claims the aggregate vested amount across the base schedule and a tranche.
+/// At epoch 250 the base 6000 has vested 4500 and the refresher 4000 has vested 1000.
+#[test]
+fn test_aggregate_claim_across_tranches() {
+    let input_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 0, 500, &[REFRESHER]);
+    let output_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 5500, 0, 501, &[(4000, 1000, 200, 400, 200)]);
+
+    let result = verify_tranche_transition(Signer::Beneficiary, input_data, 250, Some(output_data));
+    assert!(result.is_ok(), "Should succeed - claim of the aggregate vested amount, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a claim within the aggregate but beyond one tranche's vested amount is rejected.
+/// Claimed counters must stay within what each part has vested.
+#[test]
+fn test_tranche_overclaim_rejected() {
+    let input_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 0, 500, &[REFRESHER]);
+    // 1500 attributed to the refresher, which has only vested 1000.
+    let output_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 5500, 0, 501, &[(4000, 1500, 200, 400, 200)]);
+
+    let result = verify_tranche_transition(Signer::Beneficiary, input_data, 250, Some(output_data));
+    assert!(result.is_err(), "Should fail - tranche claimed beyond its vested amount");
+    assert_eq!(extract_error_code(&result), Some(49)); // Error::InvalidTrancheClaim
+}
+
+/// Tests that a claim beyond the aggregate vested amount is rejected.
+/// Validates that tranches never add to the claimable amount beyond their vesting.
+#[test]
+fn test_aggregate_overclaim_rejected() {
+    let input_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 0, 500, &[REFRESHER]);
+    let output_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 5501, 0, 501, &[(4000, 1000, 200, 400, 200)]);
+
+    let result = verify_tranche_transition(Signer::Beneficiary, input_data, 250, Some(output_data));
+    assert!(result.is_err(), "Should fail - claim exceeds the aggregate vested amount");
+    assert_eq!(extract_error_code(&result), Some(21)); // Error::InsufficientVested
+}
+
+/// Tests that a beneficiary cannot alter a tranche schedule while claiming.
+/// Tranche schedules are immutable once the cell is created.
+#[test]
+fn test_tranche_schedule_change_rejected() {
+    let input_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 0, 500, &[REFRESHER]);
+    // The refresher end epoch is moved from 400 to 300 to accelerate vesting.
+    let output_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 4500, 0, 501, &[(4000, 0, 200, 300, 200)]);
+
+    let result = verify_tranche_transition(Signer::Beneficiary, input_data, 250, Some(output_data));
+    assert!(result.is_err(), "Should fail - tranche schedule changed");
+    assert_eq!(extract_error_code(&result), Some(48)); // Error::TrancheChanged
+}
+
+/// Tests that tranche records cannot be dropped from the continuation cell.
+/// Validates that the tranche count is immutable.
+#[test]
+fn test_tranche_removal_rejected() {
+    let input_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 0, 500, &[REFRESHER]);
+    let output_data = create_vesting_data(TOTAL_AMOUNT, 4500, 0, 501);
+
+    let result = verify_tranche_transition(Signer::Beneficiary, input_data, 250, Some(output_data));
+    assert!(result.is_err(), "Should fail - tranche record removed");
+    assert_eq!(extract_error_code(&result), Some(48)); // Error::TrancheChanged
+}
+
+/// Tests that anonymous updates cannot modify tranche records.
+/// Only the highest block seen may change during a block update.
+#[test]
+fn test_anonymous_update_cannot_modify_tranches() {
+    let input_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 0, 500, &[REFRESHER]);
+    let output_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 0, 501, &[(4000, 0, 200, 400, 250)]);
+
+    let result = verify_tranche_transition(Signer::Anonymous, input_data, 250, Some(output_data));
+    assert!(result.is_err(), "Should fail - anonymous update modified a tranche");
+    assert_eq!(extract_error_code(&result), Some(17)); // Error::InvalidStateChange
+}
+
+/// Tests that creator termination reclaims the aggregate unvested amount.
+/// At epoch 250, 5500 of 10000 has vested, so the creator must claim exactly 4500.
+#[test]
+fn test_creator_termination_with_tranches() {
+    let input_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 0, 500, &[REFRESHER]);
+    let output_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 4500, 501, &[REFRESHER]);
+
+    let result = verify_tranche_transition(Signer::Creator, input_data, 250, Some(output_data));
+    assert!(result.is_ok(), "Should succeed - termination of the aggregate unvested amount, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a creator termination ignoring tranche vesting is rejected.
+/// Claiming only the base schedule's unvested amount leaves the aggregate inconsistent.
+#[test]
+fn test_creator_termination_ignoring_tranches_rejected() {
+    let input_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 0, 500, &[REFRESHER]);
+    // 2500 is the unvested amount of a single 10000 schedule at epoch 250.
+    let output_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 2500, 501, &[REFRESHER]);
+
+    let result = verify_tranche_transition(Signer::Creator, input_data, 250, Some(output_data));
+    assert!(result.is_err(), "Should fail - termination amount ignores tranche vesting");
+    assert_eq!(extract_error_code(&result), Some(20)); // Error::InvalidAmount
+}
+
+/// Tests that the beneficiary consumes the cell once every tranche has fully vested.
+/// The refresher ends last at epoch 400.
+#[test]
+fn test_full_consumption_after_last_tranche_ends() {
+    let input_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 5500, 0, 500, &[(4000, 1000, 200, 400, 200)]);
+
+    let result = verify_tranche_transition(Signer::Beneficiary, input_data, 400, None);
+    assert!(result.is_ok(), "Should succeed - all tranches fully vested, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the cell must continue while a tranche is still vesting.
+/// The base schedule has ended at epoch 300 but the refresher has not.
+#[test]
+fn test_consumption_before_last_tranche_ends_rejected() {
+    let input_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 0, 500, &[REFRESHER]);
+
+    let result = verify_tranche_transition(Signer::Beneficiary, input_data, 300, None);
+    assert!(result.is_err(), "Should fail - refresher tranche is still vesting");
+    assert_eq!(extract_error_code(&result), Some(43)); // Error::BeneficiaryPartialClaimMissingOutput
+}
+
+/// Tests that tranche totals exceeding the aggregate total are rejected.
+/// Tranches are part of the aggregate, never in addition to it.
+#[test]
+fn test_tranche_totals_exceeding_aggregate_rejected() {
+    let input_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 0, 500, &[(6000, 0, 200, 400, 200), (5000, 0, 300, 500, 300)]);
+    let output_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 0, 501, &[(6000, 0, 200, 400, 200), (5000, 0, 300, 500, 300)]);
+
+    let result = verify_tranche_transition(Signer::Anonymous, input_data, 250, Some(output_data));
+    assert!(result.is_err(), "Should fail - tranche totals exceed the aggregate total");
+    assert_eq!(extract_error_code(&result), Some(47)); // Error::InvalidTrancheData
+}
+
+/// Tests that a partial tranche record is rejected.
+/// The tranche count promises a whole record that the data does not hold.
+#[test]
+fn test_partial_tranche_record_rejected() {
+    let mut input_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 0, 500, &[REFRESHER]).to_vec();
+    input_data.truncate(input_data.len() - 8);
+    let output_data = create_vesting_data_with_tranches(TOTAL_AMOUNT, 0, 0, 501, &[REFRESHER]);
+
+    let result = verify_tranche_transition(Signer::Anonymous, Bytes::from(input_data), 250, Some(output_data));
+    assert!(result.is_err(), "Should fail - truncated tranche record");
+    assert_eq!(extract_error_code(&result), Some(47)); // Error::InvalidTrancheData
+}
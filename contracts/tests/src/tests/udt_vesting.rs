@@ -0,0 +1,626 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const OPTION_KEEPER_TIP: u8 = 0x03;
+const OPTION_MIN_UPDATE_INTERVAL: u8 = 0x04;
+const OPTION_BPS_DENOMINATION: u8 = 0x0d;
+const OPTION_CO_GRANTORS: u8 = 0x0f;
+const OPTION_UDT_TYPE: u8 = 0x13;
+const OPTION_UDT_UNIT: u8 = 0x1c;
+const OPTION_CHEQUE_LOCK: u8 = 0x1d;
+const OPTION_VESTED_CAPACITY: u8 = 0x1e;
+
+// Args of the always-success type scripts standing in for the vested token and another token
+const UDT_ARGS: u8 = 7;
+const OTHER_UDT_ARGS: u8 = 8;
+
+// Tokens per unit of an 18-decimal token, so the 10000-unit grant holds more than u64::MAX tokens
+const WHOLE_TOKEN: u128 = 1_000_000_000_000_000_000;
+
+// Capacity of each token output
+const TOKEN_CELL_CAPACITY: u64 = 14200000000;
+
+/// Creates UDT vesting cell data from the given token amount and vesting state.
+/// The data is packed as the 16-byte sUDT amount followed by the vesting state.
+fn create_udt_data(amount: u128, state: Bytes) -> Bytes {
+    let mut data = amount.to_le_bytes().to_vec();
+    data.extend_from_slice(&state);
+    Bytes::from(data)
+}
+
+/// A cheque output of the vested token sent by the beneficiary, or by the creator when from_creator is set.
+/// A cheque to the payout lock also redirects the claim there; otherwise it is addressed to the beneficiary.
+struct Cheque {
+    tokens: u128,
+    to_payout: bool,
+    from_creator: bool,
+}
+
+/// A transition of a UDT cell vesting 10000 tokens over epochs 100 to 300 with a cliff at 120.
+/// udt_option false leaves the UDT type option out of the args; cheque_option adds the cheque lock option.
+struct UdtTransition {
+    beneficiary_signs: bool,
+    beneficiary_tokens: Option<u128>,
+    cheque: Option<Cheque>,
+    cheque_option: bool,
+    creator_signs: bool,
+    epoch: u64,
+    options: Vec<(u8, Vec<u8>)>,
+    input_tokens: u128,
+    input_typed: bool,
+    output_capacity: u64,
+    output_data: Option<Bytes>,
+    output_tokens: u128,
+    output_type_args: Option<u8>,
+    payout_tokens: Option<u128>,
+    witness: Option<Bytes>,
+    udt_option: bool,
+}
+
+impl Default for UdtTransition {
+    fn default() -> Self {
+        UdtTransition {
+            beneficiary_signs: true,
+            beneficiary_tokens: Some(5000),
+            cheque: None,
+            cheque_option: false,
+            creator_signs: false,
+            epoch: 200,
+            options: Vec::new(),
+            input_tokens: 10000,
+            input_typed: true,
+            output_capacity: 20000,
+            output_data: Some(create_vesting_data(10000, 5000, 0, 201)),
+            output_tokens: 5000,
+            output_type_args: Some(UDT_ARGS),
+            payout_tokens: None,
+            witness: None,
+            udt_option: true,
+        }
+    }
+}
+
+/// Builds and verifies a transition of a UDT cell holding input_tokens tokens.
+/// output_data None consumes the cell; beneficiary_tokens and payout_tokens add token outputs to the beneficiary and payout locks.
+fn verify_udt(transition: UdtTransition) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+    let beneficiary_output_lock = beneficiary_lock.clone();
+    let (payout_lock, payout_hash) = create_always_success_lock_with_args(&mut context, vec![5u8]);
+    let (udt_type, udt_type_hash) = create_always_success_lock_with_args(&mut context, vec![UDT_ARGS]);
+
+    let mut options = Vec::new();
+    if transition.udt_option {
+        options.push((OPTION_UDT_TYPE, udt_type_hash.to_vec()));
+    }
+    if transition.cheque_option {
+        // The cheque stand-in is an always-success lock, so it shares the token's code hash and hash type.
+        let mut cheque_option = udt_type.code_hash().as_slice().to_vec();
+        cheque_option.extend_from_slice(udt_type.hash_type().as_slice());
+        options.push((OPTION_CHEQUE_LOCK, cheque_option));
+    }
+    options.extend(transition.options);
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            creator_hash,
+            beneficiary_hash,
+            100, // start_epoch
+            300, // end_epoch
+            120, // cliff_epoch
+        ),
+        &options,
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, transition.epoch);
+
+    let input_type = if transition.input_typed { Some(udt_type.clone()) } else { None };
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(20000u64.pack())
+            .lock(lock_script.clone())
+            .type_(input_type.pack())
+            .build(),
+        create_udt_data(transition.input_tokens, create_vesting_data(10000, 0, 0, 200)),
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .header_dep(header_hash);
+
+    for (signs, signer_lock) in [(transition.beneficiary_signs, beneficiary_lock), (transition.creator_signs, creator_lock)] {
+        if signs {
+            let signer_input_out_point = context.create_cell(
+                CellOutput::new_builder()
+                    .capacity(6100000000u64.pack())
+                    .lock(signer_lock)
+                    .build(),
+                Bytes::new(),
+            );
+            builder = builder.input(CellInput::new_builder().previous_output(signer_input_out_point).build());
+        }
+    }
+
+    if let Some(output_data) = transition.output_data {
+        let output_type = transition
+            .output_type_args
+            .map(|type_args| create_always_success_lock_with_args(&mut context, vec![type_args]).0);
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(transition.output_capacity.pack())
+                .lock(lock_script)
+                .type_(output_type.pack())
+                .build())
+            .output_data(create_udt_data(transition.output_tokens, output_data).pack());
+    }
+
+    if let Some(beneficiary_tokens) = transition.beneficiary_tokens {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(TOKEN_CELL_CAPACITY.pack())
+                .lock(beneficiary_output_lock)
+                .type_(Some(udt_type.clone()).pack())
+                .build())
+            .output_data(Bytes::from(beneficiary_tokens.to_le_bytes().to_vec()).pack());
+    }
+
+    if let Some(payout_tokens) = transition.payout_tokens {
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(TOKEN_CELL_CAPACITY.pack())
+                .lock(payout_lock)
+                .type_(Some(udt_type.clone()).pack())
+                .build())
+            .output_data(Bytes::from(payout_tokens.to_le_bytes().to_vec()).pack())
+            .witness(create_payout_witness(payout_hash).pack());
+    }
+
+    if let Some(cheque) = transition.cheque {
+        let receiver_hash = if cheque.to_payout { payout_hash } else { beneficiary_hash };
+        let sender_hash = if cheque.from_creator { creator_hash } else { beneficiary_hash };
+        let mut cheque_args = receiver_hash[..20].to_vec();
+        cheque_args.extend_from_slice(&sender_hash[..20]);
+        let (cheque_lock, _) = create_always_success_lock_with_args(&mut context, cheque_args);
+
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(TOKEN_CELL_CAPACITY.pack())
+                .lock(cheque_lock)
+                .type_(Some(udt_type).pack())
+                .build())
+            .output_data(Bytes::from(cheque.tokens.to_le_bytes().to_vec()).pack());
+        if cheque.to_payout {
+            builder = builder.witness(create_payout_witness(payout_hash).pack());
+        }
+    }
+
+    if let Some(witness) = transition.witness {
+        builder = builder.witness(witness.pack());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that a beneficiary can claim vested tokens from a UDT cell.
+/// At epoch 200 half of the 10000 tokens are vested, so the continuation keeps 5000.
+#[test]
+fn test_udt_partial_claim() {
+    let result = verify_udt(UdtTransition::default());
+    assert!(result.is_ok(), "Should succeed - continuation keeps the unclaimed tokens, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a claim cannot release more tokens than it records.
+/// Claiming 5000 while leaving only 4999 tokens takes one token too many.
+#[test]
+fn test_udt_claim_releasing_extra_tokens_rejected() {
+    let result = verify_udt(UdtTransition { output_tokens: 4999, ..UdtTransition::default() });
+    assert!(result.is_err(), "Should fail - continuation lost an unclaimed token");
+    assert_eq!(extract_error_code(&result), Some(121)); // Error::InvalidUdtBacking
+}
+
+/// Tests that the continuation cell must keep the vested token's type script.
+/// Dropping the type or switching to another token would strip the tokens from the schedule.
+#[test]
+fn test_udt_continuation_type_required() {
+    for output_type_args in [None, Some(OTHER_UDT_ARGS)] {
+        let result = verify_udt(UdtTransition { output_type_args, ..UdtTransition::default() });
+        assert!(result.is_err(), "Should fail - continuation type {:?} is not the vested token", output_type_args);
+        assert_eq!(extract_error_code(&result), Some(97)); // Error::InvalidUdtCell
+    }
+}
+
+/// Tests that a UDT cell without the configured type script is rejected.
+/// Such a cell holds no tokens, so its cell data amounts are meaningless.
+#[test]
+fn test_udt_input_without_type_rejected() {
+    let result = verify_udt(UdtTransition { input_typed: false, ..UdtTransition::default() });
+    assert!(result.is_err(), "Should fail - vesting cell does not carry the token type");
+    assert_eq!(extract_error_code(&result), Some(97)); // Error::InvalidUdtCell
+}
+
+/// Tests that a creator termination reclaims exactly the unvested tokens.
+/// The creator reclaims 5000 at epoch 200, so the continuation must keep the other 5000.
+#[test]
+fn test_udt_termination() {
+    let result = verify_udt(UdtTransition {
+        beneficiary_signs: false,
+        creator_signs: true,
+        output_data: Some(create_vesting_data(10000, 0, 5000, 201)),
+        ..UdtTransition::default()
+    });
+    assert!(result.is_ok(), "Should succeed - termination of the unvested tokens, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_udt(UdtTransition {
+        beneficiary_signs: false,
+        creator_signs: true,
+        output_data: Some(create_vesting_data(10000, 0, 5000, 201)),
+        output_tokens: 4999,
+        ..UdtTransition::default()
+    });
+    assert!(result.is_err(), "Should fail - termination took a vested token");
+    assert_eq!(extract_error_code(&result), Some(121)); // Error::InvalidUdtBacking
+}
+
+/// Tests that anonymous updates keep every token in the cell.
+/// Nothing is claimed, so even a single missing token is rejected.
+#[test]
+fn test_udt_anonymous_update_keeps_tokens() {
+    let result = verify_udt(UdtTransition {
+        beneficiary_signs: false,
+        output_data: Some(create_vesting_data(10000, 0, 0, 201)),
+        output_tokens: 10000,
+        ..UdtTransition::default()
+    });
+    assert!(result.is_ok(), "Should succeed - block update keeps all tokens, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_udt(UdtTransition {
+        beneficiary_signs: false,
+        output_data: Some(create_vesting_data(10000, 0, 0, 201)),
+        output_tokens: 9999,
+        ..UdtTransition::default()
+    });
+    assert!(result.is_err(), "Should fail - block update removed a token");
+    assert_eq!(extract_error_code(&result), Some(121)); // Error::InvalidUdtBacking
+}
+
+/// Tests that a UDT cell's capacity is conserved across transitions.
+/// Claims and terminations release tokens, never capacity, so a continuation one shannon short is rejected.
+#[test]
+fn test_udt_capacity_conserved() {
+    let cases = [
+        ("claim", UdtTransition { output_capacity: 19999, ..UdtTransition::default() }),
+        ("termination", UdtTransition {
+            beneficiary_signs: false,
+            creator_signs: true,
+            output_capacity: 19999,
+            output_data: Some(create_vesting_data(10000, 0, 5000, 201)),
+            ..UdtTransition::default()
+        }),
+    ];
+
+    for (label, transition) in cases {
+        let result = verify_udt(transition);
+        assert!(result.is_err(), "Case {} should fail - continuation lost capacity", label);
+        assert_eq!(extract_error_code(&result), Some(123), "Case {} should fail with UdtCapacityNotConserved", label);
+    }
+}
+
+/// Tests that a UDT transition may take up to the keeper tip from the cell's capacity.
+/// With a tip of 1000, a claim may leave 19000 of the 20000 but not 18999.
+#[test]
+fn test_udt_capacity_allows_keeper_tip() {
+    let options = vec![
+        (OPTION_KEEPER_TIP, 1000u64.to_le_bytes().to_vec()),
+        (OPTION_MIN_UPDATE_INTERVAL, 100u64.to_le_bytes().to_vec()),
+    ];
+
+    let result = verify_udt(UdtTransition { options: options.clone(), output_capacity: 19000, ..UdtTransition::default() });
+    assert!(result.is_ok(), "Should succeed - claim took the keeper tip, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_udt(UdtTransition { options, output_capacity: 18999, ..UdtTransition::default() });
+    assert!(result.is_err(), "Should fail - claim took more than the keeper tip");
+    assert_eq!(extract_error_code(&result), Some(123)); // Error::UdtCapacityNotConserved
+}
+
+/// Tests that a dual-token cell releases capacity alongside its tokens at the fixed ratio.
+/// At 2 shannons per unit, claiming or terminating 5000 may take 10000 of the 20000 shannons but not 10001.
+#[test]
+fn test_udt_vested_capacity_released_with_claim() {
+    let options = vec![(OPTION_VESTED_CAPACITY, 2u64.to_le_bytes().to_vec())];
+    let termination = |output_capacity| UdtTransition {
+        beneficiary_signs: false,
+        creator_signs: true,
+        options: options.clone(),
+        output_capacity,
+        output_data: Some(create_vesting_data(10000, 0, 5000, 201)),
+        ..UdtTransition::default()
+    };
+
+    let result = verify_udt(UdtTransition { options: options.clone(), output_capacity: 10000, ..UdtTransition::default() });
+    assert!(result.is_ok(), "Should succeed - claim took the capacity vested with it, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_udt(termination(10000));
+    assert!(result.is_ok(), "Should succeed - termination took the capacity vested with it, got error code: {:?}", extract_error_code(&result));
+
+    let cases = [
+        ("claim", UdtTransition { options: options.clone(), output_capacity: 9999, ..UdtTransition::default() }),
+        ("termination", termination(9999)),
+    ];
+
+    for (label, transition) in cases {
+        let result = verify_udt(transition);
+        assert!(result.is_err(), "Case {} should fail - continuation released more capacity than vested", label);
+        assert_eq!(extract_error_code(&result), Some(123), "Case {} should fail with UdtCapacityNotConserved", label);
+    }
+}
+
+/// Tests that a redirected dual-token claim pays the vested capacity to the payout lock as well.
+/// The payout token cell covers 2 shannons per unit but not a full token cell's capacity per unit.
+#[test]
+fn test_udt_vested_capacity_redirected_payout() {
+    let redirected = |capacity_per_unit: u64| UdtTransition {
+        beneficiary_tokens: None,
+        options: vec![(OPTION_VESTED_CAPACITY, capacity_per_unit.to_le_bytes().to_vec())],
+        payout_tokens: Some(5000),
+        ..UdtTransition::default()
+    };
+
+    let result = verify_udt(redirected(2));
+    assert!(result.is_ok(), "Should succeed - payout lock received the tokens and capacity, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_udt(redirected(TOKEN_CELL_CAPACITY));
+    assert!(result.is_err(), "Should fail - payout lock received less capacity than vested with the claim");
+    assert_eq!(extract_error_code(&result), Some(46)); // Error::InsufficientPayout
+}
+
+/// Tests that a malformed vested capacity option is rejected.
+/// The ratio must be a non-zero 8-byte value and needs the UDT option.
+#[test]
+fn test_invalid_vested_capacity_rejected() {
+    let cases = [
+        ("zero ratio", vec![(OPTION_VESTED_CAPACITY, 0u64.to_le_bytes().to_vec())], true),
+        ("short ratio", vec![(OPTION_VESTED_CAPACITY, vec![1u8; 7])], true),
+        ("no UDT option", vec![(OPTION_VESTED_CAPACITY, 2u64.to_le_bytes().to_vec())], false),
+    ];
+
+    for (label, options, udt_option) in cases {
+        let result = verify_udt(UdtTransition { options, udt_option, ..UdtTransition::default() });
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS), "Case {} should fail with invalid args", label);
+    }
+}
+
+/// Tests that the final claim consumes a UDT cell like a capacity cell.
+/// At epoch 300 everything is vested and no continuation remains.
+#[test]
+fn test_udt_final_claim_consumes_cell() {
+    let result = verify_udt(UdtTransition {
+        epoch: 300,
+        output_data: None,
+        beneficiary_tokens: Some(10000),
+        ..UdtTransition::default()
+    });
+    assert!(result.is_ok(), "Should succeed - final claim of all tokens, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that an unredirected claim must deliver the claimed tokens to the beneficiary lock.
+/// Without a token output, or with only 4999 of the 5000 claimed tokens, the claim is rejected.
+#[test]
+fn test_udt_claim_delivers_tokens_to_beneficiary() {
+    for beneficiary_tokens in [None, Some(4999)] {
+        let result = verify_udt(UdtTransition { beneficiary_tokens, ..UdtTransition::default() });
+        assert!(result.is_err(), "Should fail - beneficiary received {:?} of the claimed tokens", beneficiary_tokens);
+        assert_eq!(extract_error_code(&result), Some(46)); // Error::InsufficientPayout
+    }
+}
+
+/// Tests that redirected claims are paid in tokens rather than capacity.
+/// The payout lock must receive the 5000 claimed tokens; 4999 falls short.
+#[test]
+fn test_udt_redirected_payout() {
+    let result = verify_udt(UdtTransition { beneficiary_tokens: None, payout_tokens: Some(5000), ..UdtTransition::default() });
+    assert!(result.is_ok(), "Should succeed - payout lock received the claimed tokens, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_udt(UdtTransition { beneficiary_tokens: None, payout_tokens: Some(4999), ..UdtTransition::default() });
+    assert!(result.is_err(), "Should fail - payout lock received fewer tokens than claimed");
+    assert_eq!(extract_error_code(&result), Some(46)); // Error::InsufficientPayout
+}
+
+/// Tests that a UDT unit lets a cell vest more than u64::MAX tokens.
+/// With 10^18 tokens per unit, claiming 5000 units delivers 5000 * 10^18 tokens and the continuation keeps the rest.
+#[test]
+fn test_udt_unit_above_u64_max() {
+    let scaled = |transition: UdtTransition| UdtTransition {
+        options: vec![(OPTION_UDT_UNIT, (WHOLE_TOKEN as u64).to_le_bytes().to_vec())],
+        input_tokens: 10000 * WHOLE_TOKEN,
+        output_tokens: 5000 * WHOLE_TOKEN,
+        ..transition
+    };
+    assert!(10000 * WHOLE_TOKEN > u64::MAX as u128);
+
+    let result = verify_udt(scaled(UdtTransition { beneficiary_tokens: Some(5000 * WHOLE_TOKEN), ..UdtTransition::default() }));
+    assert!(result.is_ok(), "Should succeed - claim delivered 5000 units of tokens, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_udt(scaled(UdtTransition { beneficiary_tokens: None, payout_tokens: Some(5000 * WHOLE_TOKEN), ..UdtTransition::default() }));
+    assert!(result.is_ok(), "Should succeed - payout lock received 5000 units of tokens, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_udt(scaled(UdtTransition { beneficiary_tokens: Some(5000 * WHOLE_TOKEN - 1), ..UdtTransition::default() }));
+    assert!(result.is_err(), "Should fail - beneficiary received one token short of the claimed units");
+    assert_eq!(extract_error_code(&result), Some(46)); // Error::InsufficientPayout
+
+    let result = verify_udt(scaled(UdtTransition { beneficiary_tokens: None, payout_tokens: Some(5000 * WHOLE_TOKEN - 1), ..UdtTransition::default() }));
+    assert!(result.is_err(), "Should fail - payout lock received one token short of the claimed units");
+    assert_eq!(extract_error_code(&result), Some(46)); // Error::InsufficientPayout
+
+    let mut transition = scaled(UdtTransition { beneficiary_tokens: Some(5000 * WHOLE_TOKEN), ..UdtTransition::default() });
+    transition.output_tokens -= 1;
+    let result = verify_udt(transition);
+    assert!(result.is_err(), "Should fail - continuation lost one token of the unclaimed units");
+    assert_eq!(extract_error_code(&result), Some(121)); // Error::InvalidUdtBacking
+}
+
+/// Tests that a malformed UDT unit option is rejected.
+/// The unit must be a non-zero 8-byte value and needs the UDT option to scale.
+#[test]
+fn test_invalid_udt_unit_rejected() {
+    let cases = [
+        ("zero unit", vec![(OPTION_UDT_UNIT, 0u64.to_le_bytes().to_vec())], true),
+        ("short unit", vec![(OPTION_UDT_UNIT, vec![1u8; 7])], true),
+        ("no UDT option", vec![(OPTION_UDT_UNIT, 1u64.to_le_bytes().to_vec())], false),
+    ];
+
+    for (label, options, udt_option) in cases {
+        let result = verify_udt(UdtTransition { options, udt_option, ..UdtTransition::default() });
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS), "Case {} should fail with invalid args", label);
+    }
+}
+
+/// Tests that a redirected claim may pay the payout lock through a cheque from the beneficiary.
+/// Short cheques, cheques from another sender and cheques without the cheque lock option pay nothing.
+#[test]
+fn test_udt_cheque_redirected_payout() {
+    let cheque = |tokens, from_creator| Some(Cheque { tokens, to_payout: true, from_creator });
+
+    let result = verify_udt(UdtTransition {
+        beneficiary_tokens: None,
+        cheque: cheque(5000, false),
+        cheque_option: true,
+        ..UdtTransition::default()
+    });
+    assert!(result.is_ok(), "Should succeed - cheque to the payout lock covers the claim, got error code: {:?}", extract_error_code(&result));
+
+    let cases = [
+        ("short cheque", cheque(4999, false), true),
+        ("cheque from the creator", cheque(5000, true), true),
+        ("no cheque lock option", cheque(5000, false), false),
+    ];
+
+    for (label, cheque, cheque_option) in cases {
+        let result = verify_udt(UdtTransition { beneficiary_tokens: None, cheque, cheque_option, ..UdtTransition::default() });
+        assert!(result.is_err(), "Case {} should fail", label);
+        assert_eq!(extract_error_code(&result), Some(46), "Case {} should fail with InsufficientPayout", label);
+    }
+}
+
+/// Tests that an unredirected claim may deliver its tokens as a cheque the beneficiary sends itself.
+/// The beneficiary needs no token cell of its own to receive the claim.
+#[test]
+fn test_udt_cheque_claim_to_beneficiary() {
+    let result = verify_udt(UdtTransition {
+        beneficiary_tokens: None,
+        cheque: Some(Cheque { tokens: 5000, to_payout: false, from_creator: false }),
+        cheque_option: true,
+        ..UdtTransition::default()
+    });
+    assert!(result.is_ok(), "Should succeed - cheque to the beneficiary delivers the claim, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that a malformed cheque lock option is rejected.
+/// The value must be a 32-byte code hash and a hash type, and needs the UDT option.
+#[test]
+fn test_invalid_cheque_option_rejected() {
+    let cases = [
+        ("short value", vec![(OPTION_CHEQUE_LOCK, vec![1u8; 32])], true),
+        ("long value", vec![(OPTION_CHEQUE_LOCK, vec![1u8; 34])], true),
+        ("no UDT option", vec![(OPTION_CHEQUE_LOCK, vec![1u8; 33])], false),
+    ];
+
+    for (label, options, udt_option) in cases {
+        let result = verify_udt(UdtTransition { options, udt_option, ..UdtTransition::default() });
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS), "Case {} should fail with invalid args", label);
+    }
+}
+
+/// Tests that creator buyouts are rejected for UDT cells.
+/// The buyout amount is paid in capacity and cannot cover vested tokens.
+#[test]
+fn test_udt_buyout_rejected() {
+    let result = verify_udt(UdtTransition {
+        creator_signs: true,
+        output_data: None,
+        witness: Some(create_buyout_witness(5000)),
+        ..UdtTransition::default()
+    });
+    assert!(result.is_err(), "Should fail - buyouts cannot price UDT grants");
+    assert_eq!(extract_error_code(&result), Some(61)); // Error::InvalidBuyout
+}
+
+/// Tests that options pricing amounts in capacity cannot be combined with the UDT option.
+/// Basis-point denomination and co-grantor splits both measure capacity.
+#[test]
+fn test_udt_incompatible_options_rejected() {
+    let mut co_grantor = create_dummy_lock_hash(9).to_vec();
+    co_grantor.extend_from_slice(&5000u16.to_le_bytes());
+
+    let cases = [
+        ("bps denomination", (OPTION_BPS_DENOMINATION, 10000u64.to_le_bytes().to_vec())),
+        ("co-grantors", (OPTION_CO_GRANTORS, co_grantor)),
+    ];
+
+    for (label, option) in cases {
+        let result = verify_udt(UdtTransition { options: vec![option], ..UdtTransition::default() });
+        assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS), "Case {} should fail with invalid args", label);
+    }
+}
+
+/// Tests that a malformed UDT type option is rejected.
+/// The value must be exactly one 32-byte type script hash.
+#[test]
+fn test_invalid_udt_option_rejected() {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            creator_hash,
+            beneficiary_hash,
+            100, // start_epoch
+            300, // end_epoch
+            120, // cliff_epoch
+        ),
+        &[(OPTION_UDT_TYPE, vec![7u8; 31])],
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(20000u64.pack())
+            .lock(lock_script.clone())
+            .build(),
+        create_udt_data(10000, create_vesting_data(10000, 0, 0, 200)),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock)
+            .build(),
+        Bytes::new(),
+    );
+
+    let tx = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(20000u64.pack())
+            .lock(lock_script)
+            .build())
+        .output_data(create_udt_data(5000, create_vesting_data(10000, 5000, 0, 201)).pack())
+        .header_dep(header_hash)
+        .build();
+    let tx = context.complete_tx(tx);
+
+    let result = context.verify_tx(&tx, MAX_CYCLES);
+    assert!(result.is_err(), "Should fail - UDT type option is not 32 bytes");
+    assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS));
+}
\ No newline at end of file
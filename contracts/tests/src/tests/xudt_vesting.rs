@@ -0,0 +1,292 @@
+use super::helpers::*;
+use crate::Loader;
+use ckb_testtool::ckb_types::{bytes::Bytes, core::TransactionBuilder, packed::*, prelude::*};
+use ckb_testtool::context::Context;
+
+const OPTION_UDT_TYPE: u8 = 0x13;
+const OPTION_XUDT_TYPE: u8 = 0x14;
+
+// Owner byte of the always-success type scripts standing in for the vested token and another token
+const XUDT_OWNER: u8 = 7;
+const OTHER_UDT_OWNER: u8 = 8;
+
+// xUDT args flags selecting extension scripts in the args or by hash; either makes every cell carry a XudtData table
+const XUDT_FLAGS_EXTENSION_SCRIPTS: u32 = 1;
+const XUDT_FLAGS_EXTENSION_HASH: u32 = 2;
+
+// iCKB's xUDT args flags: owner mode by input type and no extension scripts
+const ICKB_FLAGS: u32 = 0x8000_0000;
+
+// Capacity of the beneficiary token output
+const TOKEN_CELL_CAPACITY: u64 = 14200000000;
+
+/// Creates xUDT type script args from an owner lock hash filled with the given byte and optional flags.
+/// Flag 1 is followed by an empty extension ScriptVec and flag 2 by a 20-byte extension hash.
+fn create_xudt_args(owner: u8, flags: Option<u32>) -> Vec<u8> {
+    let mut args = vec![owner; 32];
+    if let Some(flags) = flags {
+        args.extend_from_slice(&flags.to_le_bytes());
+        match flags {
+            XUDT_FLAGS_EXTENSION_SCRIPTS => args.extend_from_slice(&4u32.to_le_bytes()),
+            XUDT_FLAGS_EXTENSION_HASH => args.extend_from_slice(&[9u8; 20]),
+            _ => {}
+        }
+    }
+    args
+}
+
+/// Serializes a molecule XudtData table with an empty owner lock and the given extension data items.
+/// An empty table is 20 bytes: the table header, an empty Bytes and an empty BytesVec.
+fn create_xudt_extension(items: &[Vec<u8>]) -> Vec<u8> {
+    // BytesVec is a dynvec: total size, one offset per item, then each item as a Bytes fixvec.
+    let header_len = 4 + 4 * items.len();
+    let mut offsets = Vec::new();
+    let mut bodies = Vec::new();
+    for item in items {
+        offsets.extend_from_slice(&((header_len + bodies.len()) as u32).to_le_bytes());
+        bodies.extend_from_slice(&(item.len() as u32).to_le_bytes());
+        bodies.extend_from_slice(item);
+    }
+    let mut data_vec = ((header_len + bodies.len()) as u32).to_le_bytes().to_vec();
+    data_vec.extend_from_slice(&offsets);
+    data_vec.extend_from_slice(&bodies);
+
+    // The owner lock field is an empty Bytes fixvec.
+    let lock = 0u32.to_le_bytes().to_vec();
+
+    let table_header_len = 12;
+    let total_len = table_header_len + lock.len() + data_vec.len();
+    let mut table = (total_len as u32).to_le_bytes().to_vec();
+    table.extend_from_slice(&(table_header_len as u32).to_le_bytes());
+    table.extend_from_slice(&((table_header_len + lock.len()) as u32).to_le_bytes());
+    table.extend_from_slice(&lock);
+    table.extend_from_slice(&data_vec);
+    table
+}
+
+/// Creates xUDT vesting cell data from the given token amount, XudtData table and vesting state.
+/// The vesting state follows the table, so its position depends on the extension data carried.
+fn create_xudt_data(amount: u128, extension: &[u8], state: Bytes) -> Bytes {
+    let mut data = amount.to_le_bytes().to_vec();
+    data.extend_from_slice(extension);
+    data.extend_from_slice(&state);
+    Bytes::from(data)
+}
+
+/// A transition of an xUDT cell vesting 10000 tokens over epochs 100 to 300 with a cliff at 120.
+/// flags are the xUDT args flags; without extension flags the cells carry no XudtData table.
+struct XudtTransition {
+    options: Vec<(u8, Vec<u8>)>,
+    flags: Option<u32>,
+    input_extension: Vec<u8>,
+    output_extension: Vec<u8>,
+    output_tokens: u128,
+    beneficiary_tokens: Option<u128>,
+    beneficiary_type_owner: u8,
+}
+
+impl Default for XudtTransition {
+    fn default() -> Self {
+        XudtTransition {
+            options: Vec::new(),
+            flags: Some(XUDT_FLAGS_EXTENSION_HASH),
+            input_extension: create_xudt_extension(&[vec![1u8; 8]]),
+            output_extension: create_xudt_extension(&[vec![1u8; 8]]),
+            output_tokens: 5000,
+            beneficiary_tokens: Some(5000),
+            beneficiary_type_owner: XUDT_OWNER,
+        }
+    }
+}
+
+/// Builds and verifies a beneficiary claim of 5000 tokens from an xUDT cell at epoch 200.
+/// The beneficiary token output carries its own XudtData table, as real xUDT cells do.
+fn verify_xudt(transition: XudtTransition) -> Result<ckb_testtool::ckb_types::core::Cycle, ckb_testtool::ckb_error::Error> {
+    let mut context = Context::default();
+    let contract_bin: Bytes = Loader::default().load_binary("vesting_lock");
+    let out_point = context.deploy_cell(contract_bin);
+
+    let (beneficiary_lock, beneficiary_hash, _creator_lock, creator_hash) = setup_authorization_locks(&mut context);
+    let (xudt_type, xudt_type_hash) = create_always_success_lock_with_args(&mut context, create_xudt_args(XUDT_OWNER, transition.flags));
+    let (beneficiary_type, _) =
+        create_always_success_lock_with_args(&mut context, create_xudt_args(transition.beneficiary_type_owner, transition.flags));
+
+    let mut options = vec![(OPTION_XUDT_TYPE, xudt_type_hash.to_vec())];
+    options.extend(transition.options);
+    let args = create_vesting_args_with_options(
+        create_vesting_args(
+            creator_hash,
+            beneficiary_hash,
+            100, // start_epoch
+            300, // end_epoch
+            120, // cliff_epoch
+        ),
+        &options,
+    );
+
+    let lock_script = context.build_script(&out_point, args).expect("script");
+    let header_hash = setup_header_with_block_and_epoch(&mut context, 201, 200);
+
+    let vesting_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(20000u64.pack())
+            .lock(lock_script.clone())
+            .type_(Some(xudt_type.clone()).pack())
+            .build(),
+        create_xudt_data(10000, &transition.input_extension, create_vesting_data(10000, 0, 0, 200)),
+    );
+
+    let beneficiary_input_out_point = context.create_cell(
+        CellOutput::new_builder()
+            .capacity(6100000000u64.pack())
+            .lock(beneficiary_lock.clone())
+            .build(),
+        Bytes::new(),
+    );
+
+    let mut builder = TransactionBuilder::default()
+        .input(CellInput::new_builder().previous_output(vesting_input_out_point).build())
+        .input(CellInput::new_builder().previous_output(beneficiary_input_out_point).build())
+        .output(CellOutput::new_builder()
+            .capacity(20000u64.pack())
+            .lock(lock_script)
+            .type_(Some(xudt_type).pack())
+            .build())
+        .output_data(create_xudt_data(
+            transition.output_tokens,
+            &transition.output_extension,
+            create_vesting_data(10000, 5000, 0, 201),
+        ).pack())
+        .header_dep(header_hash);
+
+    if let Some(beneficiary_tokens) = transition.beneficiary_tokens {
+        let beneficiary_extension = match transition.flags {
+            Some(XUDT_FLAGS_EXTENSION_SCRIPTS | XUDT_FLAGS_EXTENSION_HASH) => create_xudt_extension(&[vec![1u8; 8]]),
+            _ => Vec::new(),
+        };
+        builder = builder
+            .output(CellOutput::new_builder()
+                .capacity(TOKEN_CELL_CAPACITY.pack())
+                .lock(beneficiary_lock)
+                .type_(Some(beneficiary_type).pack())
+                .build())
+            .output_data(create_xudt_data(beneficiary_tokens, &beneficiary_extension, Bytes::new()).pack());
+    }
+
+    let tx = context.complete_tx(builder.build());
+    context.verify_tx(&tx, MAX_CYCLES)
+}
+
+/// Tests that a beneficiary can claim vested tokens from an xUDT cell carrying extension data.
+/// The vesting state is found after the XudtData table, and the continuation keeps the unclaimed 5000.
+#[test]
+fn test_xudt_partial_claim() {
+    let result = verify_xudt(XudtTransition::default());
+    assert!(result.is_ok(), "Should succeed - claim skips the xUDT extension data, got error code: {:?}", extract_error_code(&result));
+}
+
+/// Tests that the extension data may change size between the input and the continuation.
+/// Each cell's state offset is read from its own XudtData table.
+#[test]
+fn test_xudt_extension_size_may_differ() {
+    let cases = [
+        ("empty input table", create_xudt_extension(&[]), create_xudt_extension(&[vec![1u8; 8]])),
+        ("empty output table", create_xudt_extension(&[vec![1u8; 8]]), create_xudt_extension(&[])),
+        ("two output items", create_xudt_extension(&[vec![1u8; 8]]), create_xudt_extension(&[vec![2u8; 3], vec![3u8; 40]])),
+    ];
+
+    for (label, input_extension, output_extension) in cases {
+        let result = verify_xudt(XudtTransition { input_extension, output_extension, ..XudtTransition::default() });
+        assert!(result.is_ok(), "Case {} should succeed, got error code: {:?}", label, extract_error_code(&result));
+    }
+}
+
+/// Tests that an xUDT cell whose args flags enable no extension scripts is read without a XudtData table.
+/// Missing flags, zero flags and flags with only the high owner-mode bits set all put the state right after the amount.
+#[test]
+fn test_xudt_without_extension_flags() {
+    let cases = [
+        ("no flags", None),
+        ("zero flags", Some(0u32)),
+        ("owner mode bit only", Some(ICKB_FLAGS)),
+    ];
+
+    for (label, flags) in cases {
+        let result = verify_xudt(XudtTransition {
+            flags,
+            input_extension: Vec::new(),
+            output_extension: Vec::new(),
+            ..XudtTransition::default()
+        });
+        assert!(result.is_ok(), "Case {} should succeed, got error code: {:?}", label, extract_error_code(&result));
+    }
+}
+
+/// Tests that a grant backed by iCKB-style receipt tokens pays out receipts as they vest.
+/// The cells carry no XudtData table, and a claim must still deliver the full 5000 receipts.
+#[test]
+fn test_xudt_receipt_token_claim() {
+    let receipt = |beneficiary_tokens| XudtTransition {
+        flags: Some(ICKB_FLAGS),
+        input_extension: Vec::new(),
+        output_extension: Vec::new(),
+        beneficiary_tokens,
+        ..XudtTransition::default()
+    };
+
+    let result = verify_xudt(receipt(Some(5000)));
+    assert!(result.is_ok(), "Should succeed - claim paid out the vested receipts, got error code: {:?}", extract_error_code(&result));
+
+    let result = verify_xudt(receipt(Some(4999)));
+    assert!(result.is_err(), "Should fail - beneficiary received fewer receipts than claimed");
+    assert_eq!(extract_error_code(&result), Some(46)); // Error::InsufficientPayout
+}
+
+/// Tests that the extension flags 1 and 2 both require a XudtData table.
+/// Flag 1 carries the extension scripts in the args themselves instead of their hash.
+#[test]
+fn test_xudt_extension_flags_read_table() {
+    for flags in [XUDT_FLAGS_EXTENSION_SCRIPTS, XUDT_FLAGS_EXTENSION_HASH] {
+        let result = verify_xudt(XudtTransition { flags: Some(flags), ..XudtTransition::default() });
+        assert!(result.is_ok(), "Flags {} should succeed, got error code: {:?}", flags, extract_error_code(&result));
+    }
+}
+
+/// Tests that a continuation whose XudtData size is below the 20-byte empty table is rejected.
+/// Such a size cannot describe a table, so the state offset derived from it is meaningless.
+#[test]
+fn test_xudt_malformed_extension_rejected() {
+    for size in [12u32, 19u32] {
+        let result = verify_xudt(XudtTransition { output_extension: size.to_le_bytes().to_vec(), ..XudtTransition::default() });
+        assert!(result.is_err(), "Should fail - XudtData size {} is below an empty table", size);
+        assert_eq!(extract_error_code(&result), Some(97)); // Error::InvalidUdtCell
+    }
+}
+
+/// Tests that a claim must transfer the claimed xUDT amount to the beneficiary lock.
+/// Outputs of another token, no output, or 4999 of the 5000 claimed tokens all fall short.
+#[test]
+fn test_xudt_claim_delivers_tokens_to_beneficiary() {
+    let cases = [
+        ("no token output", XudtTransition { beneficiary_tokens: None, ..XudtTransition::default() }),
+        ("short token output", XudtTransition { beneficiary_tokens: Some(4999), ..XudtTransition::default() }),
+        ("other token", XudtTransition { beneficiary_type_owner: OTHER_UDT_OWNER, ..XudtTransition::default() }),
+    ];
+
+    for (label, transition) in cases {
+        let result = verify_xudt(transition);
+        assert!(result.is_err(), "Case {} should fail", label);
+        assert_eq!(extract_error_code(&result), Some(46), "Case {} should fail with InsufficientPayout", label);
+    }
+}
+
+/// Tests that the UDT option cannot be combined with the xUDT option.
+/// A cell vests a single token, so a second token type is ambiguous.
+#[test]
+fn test_xudt_and_udt_options_rejected() {
+    let result = verify_xudt(XudtTransition {
+        options: vec![(OPTION_UDT_TYPE, vec![7u8; 32])],
+        ..XudtTransition::default()
+    });
+    assert_eq!(extract_error_code(&result), Some(ERROR_INVALID_ARGS), "Should fail with invalid args");
+}
\ No newline at end of file